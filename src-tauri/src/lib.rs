@@ -1,20 +1,54 @@
 use tauri::Emitter;
 use tauri::menu::{Menu, MenuItem, Submenu, PredefinedMenuItem, AboutMetadata};
 use tauri::{Manager, AppHandle};
+use tauri_plugin_notification::NotificationExt;
 use chrono::Local;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::io::Read;
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use sha2::{Sha256, Digest};
 use flate2::write::GzEncoder;
 use flate2::Compression;
 use walkdir::WalkDir;
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
 
 static BACKUP_CANCELLED: AtomicBool = AtomicBool::new(false);
-static TAR_PID: AtomicU32 = AtomicU32::new(0);
+/// Error of a backup stopped with `cancel_backup`, which the frontend tells apart from a failed backup
+const BACKUP_CANCELLED_ERROR: &str = "Backup wurde abgebrochen";
+static BACKUP_PAUSED: AtomicBool = AtomicBool::new(false);
+/// Process group ids of the running tar processes (one per parallel job)
+static TAR_PIDS: std::sync::Mutex<Vec<u32>> = std::sync::Mutex::new(Vec::new());
+/// `data/<timestamp>` directories of the backups running in this process
+static RUNNING_BACKUPS: std::sync::Mutex<Vec<PathBuf>> = std::sync::Mutex::new(Vec::new());
+static VERIFY_CANCELLED: AtomicBool = AtomicBool::new(false);
+static RESTORE_CANCELLED: AtomicBool = AtomicBool::new(false);
+/// Process group ids of the running restore commands (tar, ditto, brew, ...)
+static RESTORE_PIDS: std::sync::Mutex<Vec<u32>> = std::sync::Mutex::new(Vec::new());
+/// Directories created by `mount_archive_readonly` that have not been unmounted yet
+static MOUNTED_ARCHIVES: std::sync::Mutex<Vec<PathBuf>> = std::sync::Mutex::new(Vec::new());
+
+/// Restorable items that are not plain directory/file archives
+const SPECIAL_ITEMS: &[&str] = &[
+    "homebrew-packages",
+    "mas-apps",
+    "vscode-extensions",
+    "safari-settings",
+    "homebrew-cache",
+    "startup-items",
+    "dock-layout",
+    "chrome-settings",
+    "firefox-settings",
+    "scheduled-tasks",
+    "ssh-config",
+    "dotfiles",
+    "fonts",
+    "system-defaults",
+    "system-config",
+    "terminal-settings",
+];
 
 fn default_language() -> String {
     "de".to_string()
@@ -24,11 +58,143 @@ fn default_theme() -> String {
     "auto".to_string()
 }
 
+fn default_compression_ratio() -> f64 {
+    0.5
+}
+
+fn default_homebrew_cache_max_gb() -> u64 {
+    2
+}
+
+fn default_parallel_backup_jobs() -> usize {
+    1
+}
+
+fn default_split_directory_parts() -> usize {
+    4
+}
+
+fn default_hash_algorithm() -> String {
+    "sha256".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_system_defaults_domains() -> Vec<String> {
+    ["com.apple.finder", "com.apple.screencapture", "com.apple.menuextra.clock", "NSGlobalDomain"]
+        .iter()
+        .map(|d| d.to_string())
+        .collect()
+}
+
+fn default_quick_restore_brews() -> Vec<String> {
+    ["git", "vim", "python", "node", "curl", "wget", "htop", "tree", "jq", "ripgrep", "fd", "bat", "fzf"]
+        .iter()
+        .map(|p| p.to_string())
+        .collect()
+}
+
+fn default_quick_restore_casks() -> Vec<String> {
+    ["visual-studio-code", "iterm2", "google-chrome", "firefox", "1password", "rectangle", "alfred"]
+        .iter()
+        .map(|c| c.to_string())
+        .collect()
+}
+
+fn default_dotfiles() -> Vec<String> {
+    ["~/.zshrc", "~/.zprofile", "~/.bashrc", "~/.bash_profile", "~/.gitconfig", "~/.vimrc", "~/.tmux.conf"]
+        .iter()
+        .map(|p| p.to_string())
+        .collect()
+}
+
+/// Drosselung der Archivierung. Ohne Einstellungen laufen tar und zstd mit voller Leistung.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct BackupThrottle {
+    /// nice-Wert für tar und zstd (0 = normal, bis 20 = niedrigste Priorität)
+    #[serde(default)]
+    pub nice: i32,
+    /// tar und zstd in das Hintergrund-Band von macOS legen (gedrosselte Festplatten-I/O)
+    #[serde(default)]
+    pub background_io: bool,
+    /// Anzahl der zstd-Threads (0 = alle Kerne)
+    #[serde(default)]
+    pub zstd_threads: u32,
+    /// Höchste Schreibrate auf Netzlaufwerke in MB/s, per `pv` falls installiert (0 = unbegrenzt)
+    #[serde(default)]
+    pub network_rate_limit_mb: u32,
+}
+
+/// Ein zu sicherndes Verzeichnis (oder eine Datei) mit Anzeigename und Reihenfolge.
+/// Ältere Konfigurationen speichern nur den Pfad als String, der weiterhin gelesen wird.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(from = "BackupDirectoryEntry")]
+pub struct BackupDirectory {
+    pub path: String,
+    /// Anzeigename in der Oberfläche
+    pub label: Option<String>,
+    /// Deaktivierte Verzeichnisse bleiben in der Liste, werden aber nicht gesichert
+    pub enabled: bool,
+    /// Verzeichnisse mit höherer Priorität werden zuerst archiviert
+    pub priority: i32,
+    /// Nur diese Unterpfade (relativ zum Verzeichnis) sichern; leer = das ganze Verzeichnis
+    pub includes: Vec<String>,
+    /// Zusätzliche Ausschlussmuster (relativ zum Verzeichnis), die nur für dieses Verzeichnis gelten
+    pub excludes: Vec<String>,
+}
+
+/// Accepted forms of a `BackupDirectory`: a plain path or the full object
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BackupDirectoryEntry {
+    Path(String),
+    Entry {
+        path: String,
+        #[serde(default)]
+        label: Option<String>,
+        #[serde(default = "default_true")]
+        enabled: bool,
+        #[serde(default)]
+        priority: i32,
+        #[serde(default)]
+        includes: Vec<String>,
+        #[serde(default)]
+        excludes: Vec<String>,
+    },
+}
+
+impl From<BackupDirectoryEntry> for BackupDirectory {
+    fn from(entry: BackupDirectoryEntry) -> Self {
+        match entry {
+            BackupDirectoryEntry::Path(path) => Self::new(path),
+            BackupDirectoryEntry::Entry { path, label, enabled, priority, includes, excludes } => {
+                Self { path, label, enabled, priority, includes, excludes }
+            }
+        }
+    }
+}
+
+impl BackupDirectory {
+    fn new(path: String) -> Self {
+        Self { path, label: None, enabled: true, priority: 0, includes: Vec::new(), excludes: Vec::new() }
+    }
+}
+
+/// The enabled directories in archive order: highest priority first,
+/// list order among equal priorities
+fn enabled_directories(directories: &[BackupDirectory]) -> Vec<BackupDirectory> {
+    let mut enabled: Vec<BackupDirectory> = directories.iter().filter(|d| d.enabled).cloned().collect();
+    enabled.sort_by_key(|d| std::cmp::Reverse(d.priority));
+    enabled
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct BackupConfig {
     pub target_volume: String,
     pub target_directory: String,
-    pub directories: Vec<String>,
+    pub directories: Vec<BackupDirectory>,
     pub backup_homebrew: bool,
     pub backup_mas: bool,
     #[serde(default)]
@@ -41,6 +207,124 @@ pub struct BackupConfig {
     pub backup_homebrew_cache: bool,
     #[serde(default)]
     pub backup_safari_settings: bool,
+    /// Zusätzliche tar `--exclude`-Muster (z.B. "node_modules", "*.tmp").
+    /// Die Muster werden von tar selbst per Globbing ausgewertet.
+    #[serde(default)]
+    pub exclude_patterns: Vec<String>,
+    /// Dateien über dieser Größe (MB) werden nicht gesichert, z.B. ISO- oder DMG-Images
+    #[serde(default)]
+    pub max_file_size_mb: Option<u64>,
+    /// Geschätztes Verhältnis Archivgröße/Quellgröße für die Speicherplatzprüfung
+    #[serde(default = "default_compression_ratio")]
+    pub estimated_compression_ratio: f64,
+    #[serde(default)]
+    pub eject_after_backup: bool,
+    #[serde(default)]
+    pub backup_dock: bool,
+    #[serde(default)]
+    pub backup_chrome: bool,
+    #[serde(default)]
+    pub backup_firefox: bool,
+    #[serde(default = "default_true")]
+    pub notifications_enabled: bool,
+    /// Zuletzt bekannter Stand der Benachrichtigungs-Berechtigung, bei jedem Start aktualisiert (`None` = noch nicht gefragt)
+    #[serde(default)]
+    pub notification_permission_granted: Option<bool>,
+    #[serde(default)]
+    pub exclude_system_files: bool,
+    #[serde(default)]
+    pub schedule: Option<BackupSchedule>,
+    #[serde(default)]
+    pub include_private_keys: bool,
+    #[serde(default = "default_dotfiles")]
+    pub dotfiles: Vec<String>,
+    /// Ausgewählte `defaults`-Domains (z.B. Finder, Bildschirmfotos) sichern
+    #[serde(default)]
+    pub backup_system_defaults: bool,
+    /// Domains, die per `defaults export` gesichert werden
+    #[serde(default = "default_system_defaults_domains")]
+    pub system_defaults_domains: Vec<String>,
+    /// Drucker (CUPS) sowie Netzwerkumgebungen, -dienste und bekannte WLAN-Netze sichern
+    #[serde(default)]
+    pub backup_system_config: bool,
+    /// Verzeichnisse, in denen nach Git-Repositories gesucht wird; gesichert wird nur
+    /// die Liste (Pfad, Remote, Branch), nicht der Inhalt. Leer = deaktiviert.
+    #[serde(default)]
+    pub git_repository_roots: Vec<String>,
+    /// Symlinks auflösen und die Linkziele sichern. Standardmäßig werden Symlinks
+    /// als Links gespeichert (auch ungültige Links bleiben unverändert erhalten).
+    #[serde(default)]
+    pub follow_symlinks: bool,
+    #[serde(default)]
+    pub backup_fonts: bool,
+    /// Profile von Terminal und iTerm2 (Preferences-Plists und dynamische iTerm2-Profile) sichern
+    #[serde(default)]
+    pub backup_terminal_settings: bool,
+    /// Obergrenze für den Homebrew-Cache; darüber werden nur die neuesten Downloads gesichert
+    #[serde(default = "default_homebrew_cache_max_gb")]
+    pub homebrew_cache_max_gb: u64,
+    /// Verzeichnis für temporäre Dateien beim Wiederherstellen (Standard: System-Temp)
+    #[serde(default)]
+    pub staging_dir: Option<String>,
+    /// Verzeichnisse mit überwiegend bereits komprimierten Dateien (Fotos, Videos, Archive) unkomprimiert sichern
+    #[serde(default = "default_true")]
+    pub adaptive_compression: bool,
+    /// Verzeichnisse mit sehr vielen kleinen Dateien (z.B. Maildir) mit einem vorab trainierten
+    /// zstd-Wörterbuch komprimieren; nicht bei verschlüsselten Backups und FAT-Zielen
+    #[serde(default)]
+    pub zstd_dictionary: bool,
+    /// Anzahl gleichzeitig archivierter Verzeichnisse; nur bei schnellen lokalen Zielen wirksam
+    #[serde(default = "default_parallel_backup_jobs")]
+    pub parallel_backup_jobs: usize,
+    /// Verzeichnisse ab dieser Größe (GB) in mehrere Teilarchive aufteilen, die parallel entstehen (0 = aus)
+    #[serde(default)]
+    pub split_directory_min_gb: u64,
+    /// Anzahl der Teilarchive eines aufgeteilten Verzeichnisses, höchstens eines pro Unterordner
+    #[serde(default = "default_split_directory_parts")]
+    pub split_directory_parts: usize,
+    /// Zusätzlich jede Quelldatei hashen, damit Wiederherstellungen gegen die Quelle geprüft werden
+    /// können; verdoppelt etwa die Backup-Dauer
+    #[serde(default)]
+    pub generate_source_manifest: bool,
+    /// Prüfsummen-Algorithmus für neue Backups ("sha256" oder "blake3")
+    #[serde(default = "default_hash_algorithm")]
+    pub hash_algorithm: String,
+    /// Drosselung von tar/zstd, damit der Mac während des Backups bedienbar bleibt
+    #[serde(default)]
+    pub throttle: BackupThrottle,
+    /// Homebrew-Formeln, die der Quick-Restore zuerst installiert (sofern im Backup enthalten)
+    #[serde(default = "default_quick_restore_brews")]
+    pub quick_restore_brews: Vec<String>,
+    /// Casks, die der Quick-Restore zuerst installiert (sofern im Backup enthalten)
+    #[serde(default = "default_quick_restore_casks")]
+    pub quick_restore_casks: Vec<String>,
+    /// Software-Listen, Einstellungen und Systemobjekte (Homebrew, Dotfiles, Startobjekte, …)
+    /// zusätzlich zu den Verzeichnissen sichern; aus = nur die Verzeichnisse
+    #[serde(default = "default_true")]
+    pub backup_special_items: bool,
+    /// Schema-Version der config.json (0 = vor Einführung der Versionierung)
+    #[serde(default)]
+    pub config_version: u32,
+}
+
+/// When the launchd agent should run an unattended backup
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupSchedule {
+    /// "daily" or "weekly"
+    pub frequency: String,
+    pub hour: u8,
+    pub minute: u8,
+    /// Day of the week for weekly backups (0 = Sunday, as in launchd)
+    #[serde(default)]
+    pub weekday: u8,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ScheduleStatus {
+    pub registered: bool,
+    pub loaded: bool,
+    pub plist_path: String,
+    pub schedule: Option<BackupSchedule>,
 }
 
 impl Default for BackupConfig {
@@ -50,8 +334,8 @@ impl Default for BackupConfig {
             target_volume: String::new(),
             target_directory: String::new(),
             directories: vec![
-                home.join("Documents").to_string_lossy().to_string(),
-                home.join("Desktop").to_string_lossy().to_string(),
+                BackupDirectory::new(home.join("Documents").to_string_lossy().to_string()),
+                BackupDirectory::new(home.join("Desktop").to_string_lossy().to_string()),
             ],
             backup_homebrew: true,
             backup_mas: true,
@@ -60,6 +344,40 @@ impl Default for BackupConfig {
             theme: default_theme(),
             backup_homebrew_cache: false,
             backup_safari_settings: false,
+            exclude_patterns: Vec::new(),
+            max_file_size_mb: None,
+            estimated_compression_ratio: default_compression_ratio(),
+            eject_after_backup: false,
+            backup_dock: false,
+            backup_chrome: false,
+            backup_firefox: false,
+            notifications_enabled: true,
+            notification_permission_granted: None,
+            exclude_system_files: false,
+            schedule: None,
+            include_private_keys: false,
+            dotfiles: default_dotfiles(),
+            backup_system_defaults: false,
+            system_defaults_domains: default_system_defaults_domains(),
+            backup_system_config: false,
+            git_repository_roots: Vec::new(),
+            follow_symlinks: false,
+            backup_fonts: false,
+            backup_terminal_settings: false,
+            homebrew_cache_max_gb: default_homebrew_cache_max_gb(),
+            staging_dir: None,
+            adaptive_compression: true,
+            zstd_dictionary: false,
+            parallel_backup_jobs: default_parallel_backup_jobs(),
+            split_directory_min_gb: 0,
+            split_directory_parts: default_split_directory_parts(),
+            generate_source_manifest: false,
+            hash_algorithm: default_hash_algorithm(),
+            throttle: BackupThrottle::default(),
+            quick_restore_brews: default_quick_restore_brews(),
+            quick_restore_casks: default_quick_restore_casks(),
+            backup_special_items: true,
+            config_version: CONFIG_VERSION,
         }
     }
 }
@@ -69,19 +387,134 @@ pub struct BackupItem {
     pub path: String,
     pub archive: String,
     pub hash: String,
+    #[serde(default)]
     pub archive_size_bytes: u64,
+    #[serde(default)]
     pub source_size_bytes: u64,
+    /// Compression of the archive; `None` for backups made before it was recorded
+    #[serde(default)]
+    pub format: Option<ArchiveFormat>,
+    /// Time spent archiving and hashing this item
+    #[serde(default)]
+    pub duration_seconds: f64,
+    /// Further part archives of a directory split into parts; `archive` and `hash` describe the first part,
+    /// `archive_size_bytes` is the total of all parts
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parts: Vec<ArchivePart>,
+    /// The archive was cut into volumes for a FAT target: `archive` is the first volume (`….001`),
+    /// `parts` are the following ones, and only all volumes concatenated form a readable archive
+    #[serde(default)]
+    pub multi_volume: bool,
+    /// Account the item belongs to when it was taken from another user's home folder
+    /// (`~name/…` or `/Users/name/…`); `None` for items of whoever runs backup and restore
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    /// Hash of the trained zstd dictionary `<archive>.dict` the archive can't be read without
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dictionary_hash: Option<String>,
+}
+
+/// One part archive of a split directory, extracted into the same target as the other parts,
+/// or one volume of a multi-volume archive
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ArchivePart {
+    pub archive: String,
+    pub hash: String,
+    #[serde(default)]
+    pub archive_size_bytes: u64,
+    /// Hash of the part's own copy of the trained zstd dictionary
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dictionary_hash: Option<String>,
+}
+
+/// One entry per archive file: items as they are, split directories and multi-volume archives once per
+/// part or volume with its archive, hash and size. Used wherever archives are hashed or checked on disk.
+fn archive_files(items: &[BackupItem]) -> Vec<BackupItem> {
+    let mut files = Vec::new();
+    for item in items {
+        let parts_size: u64 = item.parts.iter().map(|p| p.archive_size_bytes).sum();
+        files.push(BackupItem {
+            archive_size_bytes: item.archive_size_bytes.saturating_sub(parts_size),
+            parts: Vec::new(),
+            ..item.clone()
+        });
+        files.extend(item.parts.iter().map(|part| BackupItem {
+            archive: part.archive.clone(),
+            hash: part.hash.clone(),
+            archive_size_bytes: part.archive_size_bytes,
+            parts: Vec::new(),
+            dictionary_hash: part.dictionary_hash.clone(),
+            ..item.clone()
+        }));
+    }
+    files
+}
+
+/// Compression format of a backup archive
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ArchiveFormat {
+    Zst,
+    Gz,
+    /// Uncompressed, used for directories of already-compressed media
+    Tar,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BackupMetadata {
+    /// Format version of metadata.json (0 = written before versioning), see `read_metadata`
+    #[serde(default)]
+    pub metadata_version: u32,
+    #[serde(default)]
     pub timestamp: String,
+    #[serde(default)]
     pub items: Vec<BackupItem>,
+    #[serde(default = "default_hash_algorithm")]
     pub hash_algorithm: String,
+    #[serde(default)]
     pub total_source_size_bytes: u64,
+    #[serde(default)]
     pub start_time: String,
+    #[serde(default)]
     pub end_time: String,
+    #[serde(default)]
     pub duration_seconds: u64,
+    #[serde(default)]
+    pub encrypted: bool,
+    #[serde(default)]
+    pub cipher: Option<String>,
+    #[serde(default)]
+    pub skipped_directories: Vec<DirectoryIssue>,
+    #[serde(default)]
+    pub failed_directories: Vec<DirectoryIssue>,
+    #[serde(flatten, default)]
+    pub source_system: SourceSystem,
+}
+
+/// The Mac a backup was created on (empty fields for older backups)
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SourceSystem {
+    #[serde(default)]
+    pub os_version: String,
+    #[serde(default)]
+    pub os_build: String,
+    #[serde(default)]
+    pub hostname: String,
+    /// "arm64" or "x86_64"
+    #[serde(default)]
+    pub arch: String,
+    #[serde(default)]
+    pub app_version: String,
+}
+
+/// A directory that is missing from a backup or was only archived partially.
+/// `reason` is one of "nicht gefunden", "keine Leseberechtigung" or "tar-Fehler".
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DirectoryIssue {
+    pub path: String,
+    pub reason: String,
+    #[serde(default)]
+    pub detail: Option<String>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -90,6 +523,20 @@ pub struct ProgressUpdate {
     pub fraction: f64,
 }
 
+/// Result of `validate_backup_target`
+#[derive(Debug, Serialize, Clone)]
+pub struct BackupTargetCheck {
+    pub writable: bool,
+    pub is_time_machine: bool,
+    pub is_boot_volume: bool,
+    pub free_space_gb: f64,
+    /// FAT target that can't hold files of 4 GB or more; larger archives are cut into volumes
+    pub file_size_limited: bool,
+    pub suitable: bool,
+    /// Why the target is unsuitable, or a warning for a suitable one
+    pub reason: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Volume {
     pub name: String,
@@ -98,14 +545,39 @@ pub struct Volume {
     pub writable: bool,
     pub is_internal: bool,
     pub free_space_gb: f64,
+    pub total_space_gb: f64,
+    /// Free space is below the requested minimum
+    pub low_space: bool,
+    /// "internal", "external" or "network"
+    pub volume_type: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BackupListItem {
     pub timestamp: String,
+    /// All archives passed a verification run with their current hashes
     pub hash_verified: bool,
 }
 
+#[derive(Debug, Serialize, Clone)]
+pub struct DeduplicationResult {
+    /// Archives replaced by a hard link to an identical archive of an older backup
+    pub linked_files: usize,
+    pub reclaimed_bytes: u64,
+    /// Archives with a matching recorded hash that were not linked, with the reason
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct OrphanedBackups {
+    /// Timestamps with a `data/` directory but no `inventories/` directory and no finished backup in it
+    pub data_only: Vec<String>,
+    /// Timestamps with an `inventories/` directory but no `data/` directory
+    pub inventory_only: Vec<String>,
+    /// Finished backups whose `inventories/` directory is missing; it is rebuilt instead of deleting the backup
+    pub missing_inventory: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub struct VerifyResult {
     pub success: bool,
@@ -121,6 +593,9 @@ pub struct BackupFileInfo {
     pub archive: String,
     pub archive_size_bytes: u64,
     pub source_size_bytes: u64,
+    pub compression_ratio: f64,
+    pub duration_seconds: f64,
+    pub user: Option<String>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -129,9 +604,141 @@ pub struct BackupDetails {
     pub items: Vec<BackupFileInfo>,
     pub total_source_size_bytes: u64,
     pub total_archive_size_bytes: u64,
+    pub average_compression_ratio: f64,
     pub start_time: String,
     pub end_time: String,
     pub duration_seconds: u64,
+    #[serde(flatten)]
+    pub source_system: SourceSystem,
+}
+
+/// Archive size of one backup, as listed by `project_capacity`
+#[derive(Debug, Serialize, Clone)]
+pub struct BackupSizePoint {
+    pub timestamp: String,
+    pub size_bytes: u64,
+}
+
+/// Result of `project_capacity`
+#[derive(Debug, Serialize, Clone)]
+pub struct CapacityProjection {
+    /// Finished backups, oldest first
+    pub backups: Vec<BackupSizePoint>,
+    pub average_size_bytes: u64,
+    /// Trend of the backup size from one backup to the next (negative while backups shrink)
+    pub growth_bytes_per_backup: f64,
+    pub free_space_gb: f64,
+    /// Further backups that fit at the current trend; `None` without backups or free space information
+    pub projected_remaining_backups: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BackupSizeEstimate {
+    pub source_size_bytes: u64,
+    pub estimated_archive_bytes: u64,
+    pub compression_ratio: f64,
+    pub free_space_gb: Option<f64>,
+    pub fits: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DirectoryPreview {
+    pub path: String,
+    pub exists: bool,
+    pub is_file: bool,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SpecialItemPreview {
+    pub item: String,
+    pub enabled: bool,
+    pub available: bool,
+    /// e.g. number of extensions or why the item is unavailable
+    pub detail: Option<String>,
+}
+
+/// Which optional backup sources exist on this Mac
+#[derive(Debug, Serialize, Clone)]
+pub struct BackupCapabilities {
+    pub homebrew: bool,
+    pub mas: bool,
+    pub vscode: bool,
+    pub safari_data: bool,
+    pub homebrew_cache: bool,
+    pub homebrew_cache_size_bytes: u64,
+    pub homebrew_cache_limit_bytes: u64,
+    /// Cache exists and is small enough to be included
+    pub homebrew_cache_within_limit: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BackupPreview {
+    pub directories: Vec<DirectoryPreview>,
+    pub special_items: Vec<SpecialItemPreview>,
+    pub estimate: BackupSizeEstimate,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ArchiveEntry {
+    pub path: String,
+    pub size_bytes: u64,
+    pub is_dir: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct StartupItems {
+    /// Login items as "name<TAB>path"
+    pub login_items: Vec<String>,
+    /// File names of the user's non-Apple LaunchAgent plists
+    pub launch_agents: Vec<String>,
+}
+
+/// One item in a comparison of two backups.
+/// `status` is "hinzugefügt", "entfernt", "geändert", "unverändert" or "nicht vergleichbar"
+/// (same source size, but the archive hashes of the two backups can't be compared).
+#[derive(Debug, Serialize, Clone)]
+pub struct BackupDiffEntry {
+    pub path: String,
+    pub status: String,
+    pub source_size_a: Option<u64>,
+    pub source_size_b: Option<u64>,
+    pub size_delta: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BackupDiff {
+    pub timestamp_a: String,
+    pub timestamp_b: String,
+    pub entries: Vec<BackupDiffEntry>,
+    pub total_size_delta: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ExtractTestResult {
+    pub item_path: String,
+    pub archive: String,
+    pub success: bool,
+    pub file_count: u64,
+    pub bytes: u64,
+    /// tar's stderr (empty on a clean extraction)
+    pub stderr: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ScheduledTasks {
+    /// Output of `crontab -l` (empty if the user has no crontab)
+    pub crontab: String,
+    /// File names of the user's non-Apple LaunchAgent plists
+    pub launch_agents: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MasApp {
+    pub id: String,
+    pub name: String,
+    /// Version as reported by `mas list` (empty if not shown)
+    pub version: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -152,10 +759,45 @@ pub struct PermissionCheckResult {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FullDiskAccessStatus {
     pub has_full_disk_access: bool,
+    /// How far the individual access probes agree on `has_full_disk_access`
+    pub confidence: FdaConfidence,
     pub tested_paths: Vec<String>,
     pub inaccessible_paths: Vec<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FdaConfidence {
+    /// TCC.db and the user's Mail/Safari library agree
+    High,
+    /// Only one probe gave an answer (the other timed out or had nothing to test)
+    Medium,
+    /// The probes disagree or none of them gave an answer
+    Low,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RestoreItemEstimate {
+    pub item_path: String,
+    /// Bytes written when extracting the item
+    pub bytes_to_extract: u64,
+    /// Packages/apps/extensions listed in the backup (package items only)
+    pub packages_total: Option<usize>,
+    /// Of those, how many are not installed on this Mac
+    pub packages_missing: Option<usize>,
+    /// Why the package count is unknown, e.g. for encrypted archives
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RestoreEstimate {
+    pub items: Vec<RestoreItemEstimate>,
+    pub total_bytes_to_extract: u64,
+    pub total_packages_missing: usize,
+    /// Rough duration based on `RESTORE_SECONDS_PER_PACKAGE` and `RESTORE_BYTES_PER_SECOND`
+    pub estimated_seconds: u64,
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub struct RestoreResult {
     pub restored_count: usize,
@@ -166,31 +808,83 @@ pub struct RestoreResult {
     pub errors: Vec<String>,
 }
 
+/// One step of `run_self_test`
+#[derive(Debug, Serialize, Clone)]
+pub struct SelfTestPhase {
+    pub name: String,
+    pub success: bool,
+    pub duration_seconds: f64,
+    /// Summary on success, the error otherwise
+    pub detail: String,
+}
+
+/// Result of `run_self_test`; phases after a failed one are not run
+#[derive(Debug, Serialize, Clone)]
+pub struct SelfTestReport {
+    pub success: bool,
+    pub phases: Vec<SelfTestPhase>,
+    /// Log lines of the verify and restore phases
+    pub log: Vec<String>,
+}
+
+/// Config file given with `--config` for a headless run, replaces the one in the home directory
+static CONFIG_PATH_OVERRIDE: std::sync::OnceLock<PathBuf> = std::sync::OnceLock::new();
+
 fn get_config_path() -> PathBuf {
+    if let Some(path) = CONFIG_PATH_OVERRIDE.get() {
+        return path.clone();
+    }
     let home = dirs::home_dir().unwrap_or_default();
     home.join(".macos_backup_suite").join("config.json")
 }
 
-// Get free space in GB for a path
-fn get_free_space_gb(path: &Path) -> f64 {
+// Get total and available space in GB for a path from a single `df -k` call
+// Returns (total, available), or (0.0, 0.0) when df fails
+fn get_disk_space_gb(path: &Path) -> (f64, f64) {
+    query_disk_space_gb(path).unwrap_or((0.0, 0.0))
+}
+
+/// Like `get_disk_space_gb`, but `None` when df fails or its output can't be parsed
+fn query_disk_space_gb(path: &Path) -> Option<(f64, f64)> {
     let output = Command::new("df")
         .args(["-k", &path.to_string_lossy()])
-        .output();
-    
-    if let Ok(output) = output {
-        if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            if let Some(line) = stdout.lines().nth(1) {
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() >= 4 {
-                    if let Ok(kb) = parts[3].parse::<u64>() {
-                        return (kb as f64) / (1024.0 * 1024.0);
-                    }
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let line = stdout.lines().nth(1)?;
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    if parts.len() < 4 {
+        return None;
+    }
+    let to_gb = |kb: &str| kb.parse::<u64>().ok().map(|kb| (kb as f64) / (1024.0 * 1024.0));
+    Some((to_gb(parts[1])?, to_gb(parts[3])?))
+}
+
+// Map mount points to their filesystem type using the output of `mount`
+// Line format: "//user@nas/share on /Volumes/share (smbfs, nodev, nosuid, mounted by user)"
+fn get_mount_types() -> std::collections::HashMap<String, String> {
+    let mut types = std::collections::HashMap::new();
+    if let Ok(output) = Command::new("mount").output() {
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            if let (Some(on), Some(paren)) = (line.find(" on "), line.rfind(" (")) {
+                if paren > on + 4 {
+                    let mount_point = &line[on + 4..paren];
+                    let fs_type = line[paren + 2..]
+                        .split([',', ')'])
+                        .next()
+                        .unwrap_or("")
+                        .trim();
+                    types.insert(mount_point.to_string(), fs_type.to_string());
                 }
             }
         }
     }
-    0.0
+    types
+}
+
+fn is_network_filesystem(fs_type: &str) -> bool {
+    matches!(fs_type, "smbfs" | "afpfs" | "nfs" | "webdav" | "cifs")
 }
 
 // Check if path is Time Machine volume
@@ -213,6 +907,64 @@ fn is_writable(path: &Path) -> bool {
     }
 }
 
+// Get the /Volumes/<name> root for a path on an external volume
+fn volume_root(path: &Path) -> Option<PathBuf> {
+    let mut components = path.components();
+    if components.next() != Some(std::path::Component::RootDir) {
+        return None;
+    }
+    match (components.next(), components.next()) {
+        (Some(std::path::Component::Normal(v)), Some(std::path::Component::Normal(name))) if v == "Volumes" => {
+            Some(Path::new("/Volumes").join(name))
+        }
+        _ => None,
+    }
+}
+
+// Check via diskutil whether a volume is on an internal disk
+fn is_internal_disk(path: &Path) -> bool {
+    let output = Command::new("diskutil")
+        .args(["info", &path.to_string_lossy()])
+        .output();
+    
+    match output {
+        Ok(o) if o.status.success() => {
+            String::from_utf8_lossy(&o.stdout).lines().any(|line| {
+                let mut parts = line.splitn(2, ':');
+                let key = parts.next().unwrap_or("").trim();
+                let value = parts.next().unwrap_or("").trim();
+                (key == "Device Location" && value == "Internal") || (key == "Internal" && value == "Yes")
+            })
+        }
+        // If diskutil can't tell, err on the side of not ejecting
+        _ => true,
+    }
+}
+
+// Eject the external volume containing the given path
+fn eject_volume_at(path: &Path) -> Result<PathBuf, String> {
+    let root = volume_root(path)
+        .ok_or_else(|| format!("{} liegt nicht auf einem externen Volume", path.display()))?;
+    
+    let name = root.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    if name == "Macintosh HD" || name == "Macintosh HD - Data" || is_internal_disk(&root) {
+        return Err(format!("{} ist ein internes Laufwerk und wird nicht ausgeworfen", root.display()));
+    }
+    
+    let output = Command::new("diskutil")
+        .args(["eject", &root.to_string_lossy()])
+        .output()
+        .map_err(|e| format!("diskutil Fehler: {}", e))?;
+    
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        return Err(format!("Auswerfen fehlgeschlagen: {}", if stderr.trim().is_empty() { stdout.trim() } else { stderr.trim() }));
+    }
+    
+    Ok(root)
+}
+
 // Check if a path is readable
 fn check_readable(path: &Path) -> bool {
     if !path.exists() {
@@ -226,6 +978,82 @@ fn check_readable(path: &Path) -> bool {
     }
 }
 
+/// Home-relative folders guarded by macOS privacy protection (TCC), unreadable without consent or Full Disk Access
+const TCC_PROTECTED_DIRS: &[&str] = &[
+    "Desktop",
+    "Documents",
+    "Downloads",
+    "Pictures/Photos Library.photoslibrary",
+    "Library/Mail",
+    "Library/Messages",
+    "Library/Safari",
+];
+
+/// `DirectoryIssue::reason` of directories that failed because of missing Full Disk Access
+const FDA_REQUIRED_REASON: &str = "Full Disk Access erforderlich";
+
+/// Protected folders at, above or inside `path` that can't be read. tar only warns about them,
+/// so without this check the archive would silently miss their contents.
+fn unreadable_protected_dirs(path: &Path, home: &Path) -> Vec<PathBuf> {
+    TCC_PROTECTED_DIRS.iter()
+        .map(|dir| home.join(dir))
+        .filter_map(|protected| {
+            if path.starts_with(&protected) {
+                // Inside a protected folder: the directory itself is what gets blocked
+                (!check_readable(path)).then(|| path.to_path_buf())
+            } else if protected.starts_with(path) && protected.exists() {
+                (!check_readable(&protected)).then_some(protected)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Current schema version of config.json
+const CONFIG_VERSION: u32 = 2;
+
+/// Upgrade a config.json written by an older app version step by step to `CONFIG_VERSION`
+fn migrate_config(mut value: serde_json::Value) -> serde_json::Value {
+    let mut version = value.get("config_version").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+    while version < CONFIG_VERSION {
+        // 0 -> 1: only introduces config_version, new fields come from the serde defaults.
+        // Renamed or restructured fields get their own step here.
+        // 1 -> 2: directories become objects with label, enabled and priority
+        if version == 1 {
+            if let Some(directories) = value.get_mut("directories").and_then(|d| d.as_array_mut()) {
+                for entry in directories.iter_mut() {
+                    if let Some(path) = entry.as_str() {
+                        *entry = serde_json::json!({ "path": path, "label": null, "enabled": true, "priority": 0 });
+                    }
+                }
+            }
+        }
+        version += 1;
+    }
+    if let Some(object) = value.as_object_mut() {
+        object.insert("config_version".to_string(), serde_json::json!(version));
+    }
+    value
+}
+
+/// Keep every field of a broken config that is valid on its own, the rest falls back to the defaults
+fn salvage_config(value: &serde_json::Value) -> BackupConfig {
+    let mut merged = serde_json::to_value(BackupConfig::default()).unwrap_or_default();
+    if let Some(fields) = value.as_object() {
+        for (key, field) in fields {
+            let mut candidate = merged.clone();
+            candidate[key.as_str()] = field.clone();
+            if serde_json::from_value::<BackupConfig>(candidate).is_ok() {
+                merged[key.as_str()] = field.clone();
+            }
+        }
+    }
+    serde_json::from_value(merged).unwrap_or_default()
+}
+
+/// Load config.json. A broken file is kept as config.json.bak and the app continues
+/// with whatever could be read from it (or the defaults), so the UI stays usable.
 #[tauri::command]
 fn load_config() -> Result<BackupConfig, String> {
     let path = get_config_path();
@@ -233,23 +1061,268 @@ fn load_config() -> Result<BackupConfig, String> {
         return Ok(BackupConfig::default());
     }
     let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
-    serde_json::from_str(&content).map_err(|e| e.to_string())
-}
-
-#[tauri::command]
+    
+    let value = match serde_json::from_str::<serde_json::Value>(&content) {
+        Ok(value) => value,
+        Err(e) => {
+            eprintln!("config.json konnte nicht gelesen werden ({}), verwende Standardeinstellungen", e);
+            backup_broken_config(&path);
+            return Ok(BackupConfig::default());
+        }
+    };
+    
+    let needs_migration = value.get("config_version").and_then(|v| v.as_u64()).unwrap_or(0) < CONFIG_VERSION as u64;
+    let value = migrate_config(value);
+    match serde_json::from_value::<BackupConfig>(value.clone()) {
+        Ok(config) => {
+            if needs_migration {
+                let _ = save_config(config.clone());
+            }
+            Ok(config)
+        }
+        Err(e) => {
+            eprintln!("config.json enthält ungültige Werte ({}), verwende nur die lesbaren Felder", e);
+            backup_broken_config(&path);
+            Ok(salvage_config(&value))
+        }
+    }
+}
+
+fn backup_broken_config(path: &Path) {
+    let _ = fs::copy(path, path.with_extension("json.bak"));
+}
+
+#[tauri::command]
 fn save_config(config: BackupConfig) -> Result<(), String> {
     let path = get_config_path();
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).map_err(|e| e.to_string())?;
     }
     let content = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
-    fs::write(&path, content).map_err(|e| e.to_string())
+    write_atomic(&path, content).map_err(|e| e.to_string())
+}
+
+const SCHEDULE_LABEL: &str = "com.nojan.macos-backup-suite.scheduled-backup";
+const SCHEDULED_BACKUP_ARG: &str = "--scheduled-backup";
+
+fn schedule_plist_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_default()
+        .join("Library/LaunchAgents")
+        .join(format!("{}.plist", SCHEDULE_LABEL))
+}
+
+/// Append a line to ~/.macos_backup_suite/schedule.log
+fn log_schedule_event(message: &str) {
+    let path = get_config_path().with_file_name("schedule.log");
+    if let Ok(mut file) = fs::OpenOptions::new().create(true).append(true).open(&path) {
+        let _ = writeln!(file, "[{}] {}", Local::now().format("%d.%m.%Y %H:%M:%S"), message);
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// launchd agent that starts the app with `--scheduled-backup` at the configured time
+fn schedule_plist(schedule: &BackupSchedule, executable: &Path) -> String {
+    let weekday = if schedule.frequency == "weekly" {
+        format!("\n        <key>Weekday</key>\n        <integer>{}</integer>", schedule.weekday)
+    } else {
+        String::new()
+    };
+    format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{exe}</string>
+        <string>{arg}</string>
+    </array>
+    <key>StartCalendarInterval</key>
+    <dict>
+        <key>Hour</key>
+        <integer>{hour}</integer>
+        <key>Minute</key>
+        <integer>{minute}</integer>{weekday}
+    </dict>
+    <key>RunAtLoad</key>
+    <false/>
+</dict>
+</plist>
+"#,
+        label = SCHEDULE_LABEL,
+        exe = xml_escape(&executable.to_string_lossy()),
+        arg = SCHEDULED_BACKUP_ARG,
+        hour = schedule.hour,
+        minute = schedule.minute,
+        weekday = weekday,
+    )
+}
+
+/// For scheduled runs: only continue when the configured target volume is mounted
+fn scheduled_target_available() -> bool {
+    let config = load_config().unwrap_or_default();
+    if config.target_volume.is_empty() {
+        log_schedule_event("Geplantes Backup übersprungen: kein Backup-Ziel konfiguriert");
+        return false;
+    }
+    if !get_mount_types().contains_key(&config.target_volume) {
+        log_schedule_event(&format!("Geplantes Backup übersprungen: {} ist nicht eingehängt", config.target_volume));
+        return false;
+    }
+    log_schedule_event(&format!("Geplantes Backup gestartet: {}", config.target_volume));
+    true
+}
+
+/// Write the launchd agent for the saved schedule and load it
+#[tauri::command]
+fn register_backup_schedule() -> Result<ScheduleStatus, String> {
+    let config = load_config()?;
+    let schedule = config.schedule.ok_or("Kein Zeitplan konfiguriert")?;
+    if schedule.frequency != "daily" && schedule.frequency != "weekly" {
+        return Err(format!("Unbekannte Häufigkeit: {}", schedule.frequency));
+    }
+    if schedule.hour > 23 || schedule.minute > 59 || schedule.weekday > 6 {
+        return Err("Ungültige Uhrzeit im Zeitplan".to_string());
+    }
+    if config.target_volume.is_empty() {
+        return Err("Kein Backup-Ziel konfiguriert".to_string());
+    }
+    
+    let executable = std::env::current_exe().map_err(|e| e.to_string())?;
+    let plist_path = schedule_plist_path();
+    if let Some(parent) = plist_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    
+    // Unload a previous version so launchd picks up the new times
+    let _ = Command::new("launchctl").args(["unload", &plist_path.to_string_lossy()]).output();
+    fs::write(&plist_path, schedule_plist(&schedule, &executable)).map_err(|e| e.to_string())?;
+    
+    let output = Command::new("launchctl")
+        .args(["load", "-w", &plist_path.to_string_lossy()])
+        .output()
+        .map_err(|e| format!("launchctl Fehler: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("launchctl load fehlgeschlagen: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    
+    get_schedule_status()
+}
+
+/// Unload and remove the launchd agent
+#[tauri::command]
+fn unregister_backup_schedule() -> Result<(), String> {
+    let plist_path = schedule_plist_path();
+    if plist_path.exists() {
+        let _ = Command::new("launchctl").args(["unload", &plist_path.to_string_lossy()]).output();
+        fs::remove_file(&plist_path).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn get_schedule_status() -> Result<ScheduleStatus, String> {
+    let plist_path = schedule_plist_path();
+    let loaded = Command::new("launchctl")
+        .arg("list")
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).lines().any(|l| l.ends_with(SCHEDULE_LABEL)))
+        .unwrap_or(false);
+    
+    Ok(ScheduleStatus {
+        registered: plist_path.exists(),
+        loaded,
+        plist_path: plist_path.to_string_lossy().to_string(),
+        schedule: load_config().ok().and_then(|c| c.schedule),
+    })
+}
+
+/// Whether the app was started by the launchd agent
+#[tauri::command]
+fn is_scheduled_run() -> bool {
+    std::env::args().any(|a| a == SCHEDULED_BACKUP_ARG)
+}
+
+/// Command line flag for a backup without the GUI, see `run_headless`
+const HEADLESS_BACKUP_ARG: &str = "--backup";
+
+/// `macos-backup-suite --backup [--config <path>] [--keychain-label <label>]` backs up the configured
+/// directories to the configured target without opening a window, printing progress to stdout.
+/// Returns the exit code (0 success, 1 backup failed, 2 invalid configuration), `None` for a normal start.
+fn run_headless() -> Option<i32> {
+    let args: Vec<String> = std::env::args().collect();
+    if !args.iter().any(|a| a == HEADLESS_BACKUP_ARG) {
+        return None;
+    }
+    let value_of = |flag: &str| args.iter().position(|a| a == flag).and_then(|i| args.get(i + 1)).cloned();
+    
+    if let Some(path) = value_of("--config") {
+        if !Path::new(&path).is_file() {
+            eprintln!("Konfiguration nicht gefunden: {}", path);
+            return Some(2);
+        }
+        let _ = CONFIG_PATH_OVERRIDE.set(PathBuf::from(path));
+    }
+    let config = match load_config() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Konfiguration konnte nicht gelesen werden: {}", e);
+            return Some(2);
+        }
+    };
+    if config.target_volume.is_empty() {
+        eprintln!("Kein Backup-Ziel konfiguriert");
+        return Some(2);
+    }
+    let target_path = if config.target_directory.is_empty() {
+        config.target_volume.clone()
+    } else {
+        format!("{}/{}", config.target_volume, config.target_directory)
+    };
+    
+    // Encrypted headless backups take their passphrase from the keychain, never from the command line
+    let keychain_label = value_of("--keychain-label");
+    let encrypt = Some(keychain_label.is_some());
+    let passphrase = match resolve_passphrase(encrypt, None, keychain_label) {
+        Ok(passphrase) => passphrase,
+        Err(e) => {
+            eprintln!("{}", e);
+            return Some(2);
+        }
+    };
+    
+    let sink = ConsoleSink::default();
+    let mut log = BackupLog::new(Box::new(ConsoleSink::default()));
+    let result = run_backup(&config, target_path.clone(), enabled_directories(&config.directories), encrypt, passphrase, None, None, &sink, &mut log);
+    log.finish(&result);
+    drop(log);
+    eject_after_backup(&target_path, &result, &sink);
+    match result {
+        Ok(metadata) => {
+            println!("Backup abgeschlossen: {} ({} Elemente)", metadata.timestamp, metadata.items.len());
+            Some(0)
+        }
+        Err(e) => {
+            eprintln!("Backup fehlgeschlagen: {}", e);
+            Some(1)
+        }
+    }
 }
 
+/// Volumes with less free space than this are flagged as running low
+const DEFAULT_MIN_FREE_SPACE_GB: f64 = 10.0;
+
 #[tauri::command]
-fn get_external_volumes() -> Result<Vec<Volume>, String> {
+fn get_external_volumes(min_free_space_gb: Option<f64>) -> Result<Vec<Volume>, String> {
+    let min_free_space_gb = min_free_space_gb.unwrap_or(DEFAULT_MIN_FREE_SPACE_GB);
     let volumes_path = Path::new("/Volumes");
     let mut volumes = Vec::new();
+    let mount_types = get_mount_types();
     
     if let Ok(entries) = fs::read_dir(volumes_path) {
         for entry in entries.flatten() {
@@ -268,9 +1341,22 @@ fn get_external_volumes() -> Result<Vec<Volume>, String> {
                 }
                 
                 let path_str = path.to_string_lossy().to_string();
+                let is_network = mount_types.get(&path_str)
+                    .map(|t| is_network_filesystem(t))
+                    .unwrap_or(false);
                 let available = path.exists() && path.read_dir().is_ok();
-                let writable = is_writable(&path);
-                let free_space_gb = get_free_space_gb(&path);
+                let mut writable = is_writable(&path);
+                // Network shares can fail the write test transiently, retry briefly
+                if is_network && !writable {
+                    for _ in 0..2 {
+                        std::thread::sleep(std::time::Duration::from_millis(500));
+                        if is_writable(&path) {
+                            writable = true;
+                            break;
+                        }
+                    }
+                }
+                let (total_space_gb, free_space_gb) = get_disk_space_gb(&path);
                 
                 if !writable {
                     continue;
@@ -282,6 +1368,14 @@ fn get_external_volumes() -> Result<Vec<Volume>, String> {
                     || name == "VM"
                     || name == "Update";
                 
+                let volume_type = if is_network {
+                    "network"
+                } else if is_internal {
+                    "internal"
+                } else {
+                    "external"
+                };
+                
                 volumes.push(Volume {
                     name,
                     path: path_str,
@@ -289,6 +1383,9 @@ fn get_external_volumes() -> Result<Vec<Volume>, String> {
                     writable,
                     is_internal,
                     free_space_gb,
+                    total_space_gb,
+                    low_space: free_space_gb < min_free_space_gb,
+                    volume_type: volume_type.to_string(),
                 });
             }
         }
@@ -296,6 +1393,70 @@ fn get_external_volumes() -> Result<Vec<Volume>, String> {
     Ok(volumes)
 }
 
+/// Check any path (not only `/Volumes` entries) before backing up to it: it must exist, be writable,
+/// not be a Time Machine volume or the startup disk, and have some free space left
+#[tauri::command]
+fn validate_backup_target(target_path: String) -> Result<BackupTargetCheck, String> {
+    let path = PathBuf::from(&target_path);
+    if !path.is_dir() {
+        return Ok(BackupTargetCheck {
+            writable: false,
+            is_time_machine: false,
+            is_boot_volume: false,
+            free_space_gb: 0.0,
+            file_size_limited: false,
+            suitable: false,
+            reason: Some(format!("Zielordner existiert nicht: {}", target_path)),
+        });
+    }
+    
+    let writable = is_writable(&path);
+    let is_time_machine = is_time_machine_volume(&path)
+        || volume_root(&path).is_some_and(|root| is_time_machine_volume(&root));
+    // "/" and the APFS data volume are the startup disk; a backup there doesn't survive a disk failure
+    let is_boot_volume = mount_of(&path)
+        .is_some_and(|(mount_point, _)| mount_point == "/" || mount_point == "/System/Volumes/Data");
+    let disk_space = query_disk_space_gb(&path);
+    let free_space_gb = disk_space.map(|(_, available)| available).unwrap_or(0.0);
+    let file_size_limited = fat_volume_size(&path).is_some();
+    
+    let reason = if !writable {
+        Some("Ziel ist nicht beschreibbar".to_string())
+    } else if is_time_machine {
+        Some("Ziel ist ein Time-Machine-Volume".to_string())
+    } else if is_boot_volume {
+        Some("Ziel liegt auf dem Startvolume".to_string())
+    } else if disk_space.is_none() {
+        Some("Freier Speicher auf dem Ziel konnte nicht ermittelt werden".to_string())
+    } else if free_space_gb <= BACKUP_FREE_SPACE_MARGIN_GB {
+        Some(format!("Zu wenig freier Speicher ({:.1} GB)", free_space_gb))
+    } else {
+        None
+    };
+    let suitable = reason.is_none();
+    let reason = reason.or_else(|| {
+        (free_space_gb < DEFAULT_MIN_FREE_SPACE_GB)
+            .then(|| format!("Wenig freier Speicher ({:.1} GB)", free_space_gb))
+    }).or_else(|| {
+        file_size_limited.then(|| "Ziel ist FAT-formatiert (max. 4 GB pro Datei): Große Archive werden in Teildateien aufgeteilt".to_string())
+    });
+    
+    Ok(BackupTargetCheck {
+        writable,
+        is_time_machine,
+        is_boot_volume,
+        free_space_gb,
+        file_size_limited,
+        suitable,
+        reason,
+    })
+}
+
+#[tauri::command]
+fn eject_volume(volume_path: String) -> Result<(), String> {
+    eject_volume_at(Path::new(&volume_path)).map(|_| ())
+}
+
 /// List all user folders under /Users/
 #[tauri::command]
 fn list_user_folders() -> Result<Vec<UserFolder>, String> {
@@ -401,9 +1562,22 @@ fn check_read_permission(path: String) -> Result<PermissionCheckResult, String>
     })
 }
 
-/// Check if Full Disk Access is granted by testing access to TCC.db
+/// Upper bound for a single FDA probe; reading TCC.db can hang depending on system state
+const FDA_PROBE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Run an access probe on a helper thread, `None` if it did not finish within `FDA_PROBE_TIMEOUT`
+fn probe_with_timeout(probe: impl FnOnce() -> bool + Send + 'static) -> Option<bool> {
+    let (tx, rx) = mpsc::channel();
+    // A hanging probe thread is left behind; it holds no locks and ends with the read
+    std::thread::spawn(move || {
+        let _ = tx.send(probe());
+    });
+    rx.recv_timeout(FDA_PROBE_TIMEOUT).ok()
+}
+
+/// Check if Full Disk Access is granted by testing access to TCC.db and the user's Mail/Safari data
 #[tauri::command]
-fn check_full_disk_access() -> Result<FullDiskAccessStatus, String> {
+async fn check_full_disk_access() -> Result<FullDiskAccessStatus, String> {
     // The TCC.db file is the most reliable FDA test - it always exists and requires FDA
     let tcc_db_path = "/Library/Application Support/com.apple.TCC/TCC.db";
     
@@ -412,32 +1586,46 @@ fn check_full_disk_access() -> Result<FullDiskAccessStatus, String> {
     
     // Test 1: Try to actually READ from TCC.db - opening is not enough!
     // Without FDA, opening may succeed but reading will fail
-    let tcc_path = Path::new(tcc_db_path);
-    let tcc_exists = tcc_path.exists();
-    
-    let can_access_tcc = if tcc_exists {
-        // We must try to read, not just open - macOS allows open but blocks read without FDA
-        match fs::File::open(tcc_path) {
-            Ok(mut file) => {
-                use std::io::Read;
-                let mut buffer = [0u8; 16];
-                let read_result = file.read(&mut buffer);
-                read_result.is_ok()
-            }
-            Err(_) => {
-                false
+    let can_access_tcc = probe_with_timeout(move || {
+        let tcc_path = Path::new(tcc_db_path);
+        if tcc_path.exists() {
+            // We must try to read, not just open - macOS allows open but blocks read without FDA
+            match fs::File::open(tcc_path) {
+                Ok(mut file) => {
+                    let mut buffer = [0u8; 16];
+                    file.read(&mut buffer).is_ok()
+                }
+                Err(_) => false,
             }
+        } else {
+            // If TCC.db does not exist, try the directory
+            fs::read_dir("/Library/Application Support/com.apple.TCC").is_ok()
         }
-    } else {
-        // If TCC.db does not exist, try the directory
-        fs::read_dir("/Library/Application Support/com.apple.TCC").is_ok()
-    };
+    });
     
-    if !can_access_tcc {
+    if can_access_tcc != Some(true) {
         inaccessible.push(tcc_db_path.to_string());
     }
     
-    // Test 2: Try to access another user Library folder (if other users exist)
+    // Test 2: ~/Library/Mail and ~/Library/Safari are TCC-protected on recent macOS as well
+    let mut can_access_user_data = None;
+    if let Some(home) = dirs::home_dir() {
+        for protected in ["Library/Mail", "Library/Safari"] {
+            let path = home.join(protected);
+            if !path.exists() {
+                continue;
+            }
+            let path_str = path.to_string_lossy().to_string();
+            test_paths.push(path_str.clone());
+            can_access_user_data = probe_with_timeout(move || fs::read_dir(&path).is_ok());
+            if can_access_user_data != Some(true) {
+                inaccessible.push(path_str);
+            }
+            break;
+        }
+    }
+    
+    // Test 3: Try to access another user Library folder (if other users exist, informational only)
     let current_user = dirs::home_dir()
         .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
         .unwrap_or_default();
@@ -464,11 +1652,17 @@ fn check_full_disk_access() -> Result<FullDiskAccessStatus, String> {
         }
     }
     
-    // FDA is granted if we can access the TCC database
-    let has_fda = can_access_tcc;
+    // TCC.db decides when it answered, the user data probe is the fallback and cross-check
+    let (has_fda, confidence) = match (can_access_tcc, can_access_user_data) {
+        (Some(tcc), Some(user_data)) if tcc == user_data => (tcc, FdaConfidence::High),
+        (Some(tcc), Some(_)) => (tcc, FdaConfidence::Low),
+        (Some(granted), None) | (None, Some(granted)) => (granted, FdaConfidence::Medium),
+        (None, None) => (false, FdaConfidence::Low),
+    };
     
     Ok(FullDiskAccessStatus {
         has_full_disk_access: has_fda,
+        confidence,
         tested_paths: test_paths,
         inaccessible_paths: inaccessible,
     })
@@ -537,7 +1731,47 @@ fn save_window_state(width: u32, height: u32, x: i32, y: i32) -> Result<(), Stri
     }
     let state = WindowState { width, height, x, y };
     let content = serde_json::to_string_pretty(&state).map_err(|e| e.to_string())?;
-    fs::write(&path, content).map_err(|e| e.to_string())?;
+    write_atomic(&path, content).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Selections of the last session that are restored on launch
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+struct UiState {
+    #[serde(default)]
+    target_volume: Option<String>,
+    #[serde(default)]
+    restore_timestamp: Option<String>,
+    #[serde(default)]
+    restore_overwrite: bool,
+}
+
+fn get_ui_state_path() -> PathBuf {
+    get_window_state_path().with_file_name("ui_state.json")
+}
+
+/// Saved UI state; a target volume that is no longer mounted is dropped together with its backup selection
+#[tauri::command]
+fn get_ui_state() -> UiState {
+    let mut state: UiState = fs::read_to_string(get_ui_state_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    if state.target_volume.as_deref().is_some_and(|volume| !Path::new(volume).is_dir()) {
+        state.target_volume = None;
+        state.restore_timestamp = None;
+    }
+    state
+}
+
+#[tauri::command]
+fn save_ui_state(state: UiState) -> Result<(), String> {
+    let path = get_ui_state_path();
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let content = serde_json::to_string_pretty(&state).map_err(|e| e.to_string())?;
+    write_atomic(&path, content).map_err(|e| e.to_string())?;
     Ok(())
 }
 
@@ -598,6 +1832,50 @@ fn find_homebrew_command(name: &str) -> Option<String> {
     None
 }
 
+/// Pfad zu einem funktionierenden zstd (erstes Binary, das `zstd --version` ausführen kann).
+/// Wird einmal aufgelöst, damit Archivierung und Entpacken dasselbe Binary verwenden.
+fn find_zstd_path() -> Option<&'static str> {
+    static ZSTD_PATH: std::sync::OnceLock<Option<String>> = std::sync::OnceLock::new();
+    ZSTD_PATH
+        .get_or_init(|| {
+            let candidates = [
+                Some("/opt/homebrew/bin/zstd".to_string()),  // Apple Silicon
+                Some("/usr/local/bin/zstd".to_string()),     // Intel Mac
+                find_homebrew_command("zstd"),
+            ];
+            candidates.into_iter().flatten().find(|path| {
+                Command::new(path)
+                    .arg("--version")
+                    .output()
+                    .map(|o| o.status.success())
+                    .unwrap_or(false)
+            })
+        })
+        .as_deref()
+}
+
+/// tar argument to decompress with the resolved zstd
+fn zstd_decompress_arg() -> String {
+    format!("--use-compress-program={} -d", find_zstd_path().unwrap_or("zstd"))
+}
+
+/// Trained zstd dictionary of an archive, stored next to it in the backup
+fn zstd_dictionary_path(archive: &Path) -> PathBuf {
+    let mut name = archive.as_os_str().to_owned();
+    name.push(".dict");
+    PathBuf::from(name)
+}
+
+/// Like `zstd_decompress_arg`, with the archive's dictionary if it was compressed with one
+fn zstd_decompress_arg_for(archive: &Path) -> String {
+    let dictionary = zstd_dictionary_path(archive);
+    if dictionary.is_file() {
+        format!("{} -D \"{}\"", zstd_decompress_arg(), dictionary.display())
+    } else {
+        zstd_decompress_arg()
+    }
+}
+
 #[tauri::command]
 fn check_homebrew() -> Result<bool, String> {
     Ok(find_brew_path().is_some())
@@ -625,8 +1903,49 @@ fn get_brew_packages() -> Result<String, String> {
     }
 }
 
+/// A `brew services` entry; only services with status "started" are started again on restore
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct BrewService {
+    name: String,
+    #[serde(default)]
+    status: Option<String>,
+}
+
+fn get_brew_services() -> Result<Vec<BrewService>, String> {
+    let brew_path = find_brew_path().ok_or("Homebrew nicht gefunden")?;
+    let output = Command::new(&brew_path)
+        .args(["services", "list", "--json"])
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    serde_json::from_slice(&output.stdout).map_err(|e| format!("brew services: {}", e))
+}
+
+/// Parse `mas list` output. Format: "123456  App Name  (1.0)"
+fn parse_mas_list(output: &str) -> Vec<MasApp> {
+    output.lines().filter_map(|line| {
+        let line = line.trim();
+        let (id, rest) = line.split_once(char::is_whitespace)?;
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let rest = rest.trim();
+        let (name, version) = match rest.rfind('(') {
+            Some(open) if rest.ends_with(')') => (&rest[..open], &rest[open + 1..rest.len() - 1]),
+            _ => (rest, ""),
+        };
+        Some(MasApp {
+            id: id.to_string(),
+            name: name.trim().to_string(),
+            version: version.trim().to_string(),
+        })
+    }).collect()
+}
+
 #[tauri::command]
-fn get_mas_apps() -> Result<String, String> {
+fn get_mas_apps() -> Result<Vec<MasApp>, String> {
     let mas_path = find_homebrew_command("mas")
         .ok_or_else(|| "mas nicht gefunden. Installiere mit: brew install mas".to_string())?;
     
@@ -636,7 +1955,7 @@ fn get_mas_apps() -> Result<String, String> {
         .map_err(|e| e.to_string())?;
     
     if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        Ok(parse_mas_list(&String::from_utf8_lossy(&output.stdout)))
     } else {
         Err("mas nicht verfügbar".to_string())
     }
@@ -674,25 +1993,9 @@ fn get_manual_apps() -> Result<Vec<String>, String> {
     }
     
     // Hole MAS Apps
-    let mut mas_apps: Vec<String> = Vec::new();
-    if let Some(mas_path) = find_homebrew_command("mas") {
-        if let Ok(output) = Command::new(&mas_path)
-            .arg("list")
-            .output()
-        {
-            let mas_list = String::from_utf8_lossy(&output.stdout);
-            for line in mas_list.lines() {
-                // Format: "123456  App Name  (1.0)"
-                let parts: Vec<&str> = line.splitn(2, char::is_whitespace).collect();
-                if parts.len() >= 2 {
-                    let name_part = parts[1].trim();
-                    if let Some(name) = name_part.split('(').next() {
-                        mas_apps.push(name.trim().to_lowercase());
-                    }
-                }
-            }
-        }
-    }
+    let mas_apps: Vec<String> = get_mas_apps()
+        .map(|apps| apps.into_iter().map(|app| app.name.to_lowercase()).collect())
+        .unwrap_or_default();
     
     // Filtere: behalte nur Apps die weder in Cask noch in MAS sind
     let manual_apps: Vec<String> = all_apps
@@ -716,8 +2019,8 @@ fn get_manual_apps() -> Result<Vec<String>, String> {
     Ok(manual_apps)
 }
 
-#[tauri::command]
-fn get_vscode_extensions() -> Result<Vec<String>, String> {
+/// Path of the VS Code `code` CLI, if installed
+fn find_vscode_cli() -> Option<String> {
     // Prüfe verschiedene VS Code Installationspfade
     let possible_paths = [
         "/Applications/Visual Studio Code.app/Contents/Resources/app/bin/code",
@@ -730,7 +2033,7 @@ fn get_vscode_extensions() -> Result<Vec<String>, String> {
         .map(|s| s.to_string());
     
     // Alternativ: which code
-    let code_cmd = code_path.or_else(|| {
+    code_path.or_else(|| {
         Command::new("which")
             .arg("code")
             .output()
@@ -738,9 +2041,12 @@ fn get_vscode_extensions() -> Result<Vec<String>, String> {
             .filter(|o| o.status.success())
             .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
             .filter(|s| !s.is_empty())
-    });
-    
-    let code_cmd = match code_cmd {
+    })
+}
+
+#[tauri::command]
+fn get_vscode_extensions() -> Result<Vec<String>, String> {
+    let code_cmd = match find_vscode_cli() {
         Some(c) => c,
         None => return Err("VS Code nicht installiert".to_string()),
     };
@@ -763,1226 +2069,7525 @@ fn get_vscode_extensions() -> Result<Vec<String>, String> {
     Ok(extensions)
 }
 
-fn compute_directory_size(path: &Path) -> u64 {
-    WalkDir::new(path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter_map(|e| e.metadata().ok())
-        .filter(|m| m.is_file())
-        .map(|m| m.len())
-        .sum()
+/// First existing Homebrew download cache location
+fn find_homebrew_cache() -> Option<PathBuf> {
+    [
+        PathBuf::from("/opt/homebrew/var/homebrew/cache"),
+        PathBuf::from("/usr/local/var/homebrew/cache"),
+        dirs::home_dir().unwrap_or_default().join("Library/Caches/Homebrew"),
+    ]
+    .into_iter()
+    .find(|path| path.exists())
 }
 
-fn hash_file(path: &Path) -> Result<String, String> {
-    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
-    let mut hasher = Sha256::new();
-    let mut buffer = [0u8; 8192];
+/// Link (or copy) the most recently modified files of `source` into `dest` until `limit` bytes are reached.
+/// Returns the staged size.
+fn stage_newest_files(source: &Path, dest: &Path, limit: u64) -> u64 {
+    let mut files: Vec<(std::time::SystemTime, PathBuf, u64)> = WalkDir::new(source)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let meta = e.metadata().ok().filter(|m| m.is_file())?;
+            Some((meta.modified().ok()?, e.into_path(), meta.len()))
+        })
+        .collect();
+    files.sort_by_key(|f| std::cmp::Reverse(f.0));
     
-    loop {
-        let bytes_read = file.read(&mut buffer).map_err(|e| e.to_string())?;
-        if bytes_read == 0 {
-            break;
+    let mut staged = 0;
+    for (_, path, size) in files {
+        if staged + size > limit {
+            continue;
+        }
+        let Ok(relative) = path.strip_prefix(source) else { continue };
+        let target = dest.join(relative);
+        if let Some(parent) = target.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if fs::hard_link(&path, &target).or_else(|_| fs::copy(&path, &target).map(|_| ())).is_ok() {
+            staged += size;
         }
-        hasher.update(&buffer[..bytes_read]);
     }
-    
-    Ok(format!("{:x}", hasher.finalize()))
+    staged
 }
 
-fn create_tar_gz(source: &Path, target: &Path) -> Result<(), String> {
-    use std::os::unix::process::CommandExt;
-    
-    // Use system tar command with zstd compression (faster than gzip, better ratio)
-    let source_parent = source.parent().unwrap_or(Path::new("/"));
-    let source_name = source.file_name()
-        .map(|n| n.to_string_lossy().to_string())
-        .unwrap_or_else(|| "backup".to_string());
-    
-    // Check if zstd is available, fallback to gzip
-    let zstd_available = Command::new("which")
-        .arg("zstd")
+/// Read the current user's login items as "name<TAB>path" lines
+fn read_login_items() -> Vec<String> {
+    let script = r#"set output to ""
+tell application "System Events"
+    repeat with li in login items
+        set output to output & (name of li) & tab & (path of li) & linefeed
+    end repeat
+end tell
+return output"#;
+    
+    Command::new("osascript")
+        .args(["-e", script])
         .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false);
-    
-    // Spawn the process so we can track and kill it
-    let mut child = if zstd_available {
-        // Use zstd compression (much faster, better compression)
-        let mut cmd = Command::new("tar");
-        cmd.current_dir(source_parent)
-            .args([
-                "--use-compress-program=/opt/homebrew/bin/zstd -T0",  // -T0 uses all CPU cores
-                "-cf",
-                &target.to_string_lossy(),
-                "--exclude", "*.sock",
-                "--exclude", "*/sockets/*",
-                &source_name,
-            ]);
-        // Create new process group so we can kill all children
-        unsafe {
-            cmd.pre_exec(|| {
-                libc::setpgid(0, 0);
-                Ok(())
-            });
-        }
-        cmd.spawn().map_err(|e| format!("Failed to spawn tar with zstd: {}", e))?
-    } else {
-        // Fallback to gzip
-        let mut cmd = Command::new("tar");
-        cmd.current_dir(source_parent)
-            .args([
-                "-czf",
-                &target.to_string_lossy(),
-                "--exclude", "*.sock",
-                "--exclude", "*/sockets/*",
-                &source_name,
-            ]);
-        unsafe {
-            cmd.pre_exec(|| {
-                libc::setpgid(0, 0);
-                Ok(())
-            });
-        }
-        cmd.spawn().map_err(|e| format!("Failed to spawn tar: {}", e))?
-    };
-    
-    // Store PID for potential cancellation
-    TAR_PID.store(child.id(), Ordering::SeqCst);
-    
-    // Wait for completion
-    let status = child.wait().map_err(|e| format!("Failed to wait for tar: {}", e))?;
-    
-    // Clear PID
-    TAR_PID.store(0, Ordering::SeqCst);
-    
-    // Check if cancelled
-    if BACKUP_CANCELLED.load(Ordering::SeqCst) {
-        let _ = fs::remove_file(target);
-        return Err("Cancelled".to_string());
-    }
-    
-    // tar returns exit code 1 for warnings (sockets, permission denied on some files, etc.)
-    // This is acceptable as long as the archive was created
-    if !status.success() {
-        // Exit code 1 with socket/pipe warnings is fine - archive is still valid
-        if status.code() == Some(1) {
-            // Check if archive was created successfully
-            if target.exists() {
-                return Ok(());
-            }
-        }
-        
-        // If archive exists, consider it a success despite warnings
-        if target.exists() {
-            return Ok(());
-        }
-        
-        return Err("tar failed".to_string());
-    }
-    
-    Ok(())
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .map(|l| l.trim_end().to_string())
+                .filter(|l| !l.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
-#[tauri::command]
-async fn create_backup(
-    target_path: String,
-    directories: Vec<String>,
-    window: tauri::Window,
-) -> Result<BackupMetadata, String> {
-    let start = Local::now();
-    let start_time_str = start.format("%d.%m.%Y %H:%M:%S").to_string();
-    let timestamp = start.format("%Y%m%d-%H%M%S").to_string();
-    
-    let suite_root = PathBuf::from(&target_path).join("macos-backup-suite");
-    let backup_root = suite_root.join("data").join(&timestamp);
-    let inventory_root = suite_root.join("inventories").join(&timestamp);
-    
-    fs::create_dir_all(&backup_root).map_err(|e| e.to_string())?;
-    fs::create_dir_all(&inventory_root).map_err(|e| e.to_string())?;
-    
-    let _ = window.emit("backup-log", format!("=== Backup gestartet: {} ===", start_time_str));
-    let _ = window.emit("backup-progress", serde_json::json!({
-        "progress": 1,
-        "message": "Initialisiere Backup..."
-    }));
-    
-    let _ = window.emit("backup-log", "Sammle Software-Inventar...");
+/// User LaunchAgent plists, skipping Apple's own agents
+fn list_user_launch_agents() -> Vec<PathBuf> {
+    let agents_dir = dirs::home_dir().unwrap_or_default().join("Library/LaunchAgents");
+    let mut agents: Vec<PathBuf> = fs::read_dir(&agents_dir)
+        .map(|entries| {
+            entries.flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "plist"))
+                .filter(|p| {
+                    !p.file_name()
+                        .map(|n| n.to_string_lossy().starts_with("com.apple."))
+                        .unwrap_or(true)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    agents.sort();
+    agents
+}
+
+#[tauri::command]
+fn get_login_items() -> Result<StartupItems, String> {
+    Ok(StartupItems {
+        login_items: read_login_items(),
+        launch_agents: list_user_launch_agents()
+            .iter()
+            .filter_map(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+            .collect(),
+    })
+}
+
+/// The current user's crontab, empty if there is none
+fn read_crontab() -> String {
+    Command::new("crontab")
+        .arg("-l")
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+fn get_cron_and_agents() -> Result<ScheduledTasks, String> {
+    Ok(ScheduledTasks {
+        crontab: read_crontab(),
+        launch_agents: list_user_launch_agents()
+            .iter()
+            .filter_map(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+            .collect(),
+    })
+}
+
+/// Executable a crontab line runs, if it names an absolute path
+fn crontab_executable(line: &str) -> Option<String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    // "@daily cmd" or five time fields followed by the command; skip VAR=value lines
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let command = if line.starts_with('@') {
+        fields.get(1)
+    } else if fields.first().is_some_and(|f| f.contains('=')) {
+        None
+    } else {
+        fields.get(5)
+    };
+    command.filter(|c| c.starts_with('/')).map(|c| c.to_string())
+}
+
+/// Program a LaunchAgent plist starts (`Program` or the first of `ProgramArguments`)
+fn launch_agent_executable(plist: &Path) -> Option<String> {
+    ["Program", "ProgramArguments.0"].iter().find_map(|key| {
+        Command::new("plutil")
+            .args(["-extract", key, "raw", "-o", "-", &plist.to_string_lossy()])
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+            .filter(|p| !p.is_empty())
+    })
+}
+
+/// Whether a file in ~/.ssh is a private key (has a matching .pub or a PEM/OpenSSH private key header)
+fn is_private_key(path: &Path) -> bool {
+    if path.extension().is_some_and(|ext| ext == "pub") {
+        return false;
+    }
+    if PathBuf::from(format!("{}.pub", path.to_string_lossy())).exists() {
+        return true;
+    }
+    let mut header = [0u8; 64];
+    fs::File::open(path)
+        .and_then(|mut f| f.read(&mut header))
+        .map(|n| {
+            let text = String::from_utf8_lossy(&header[..n]);
+            text.starts_with("-----BEGIN") && text.contains("PRIVATE KEY")
+        })
+        .unwrap_or(false)
+}
+
+/// Archive SSH config, known_hosts, authorized_keys, public keys and the GPG config files
+/// as the `ssh-config` item. Private keys are only included with `include_private_keys`.
+/// Returns the included files (relative to the home directory) for logging.
+fn backup_ssh_config(
+    backup_root: &Path,
+    passphrase: Option<&str>,
+    include_private_keys: bool,
+    format: ArchiveFormat,
+    hash_algorithm: HashAlgorithm,
+    volume_size: Option<u64>,
+    throttle: &BackupThrottle,
+) -> Result<Option<(BackupItem, Vec<String>)>, String> {
+    let home = dirs::home_dir().ok_or("Home-Verzeichnis nicht gefunden")?;
+    let ssh_dir = home.join(".ssh");
     
-    if let Ok(brewfile) = get_brew_packages() {
-        let brewfile_path = inventory_root.join("Brewfile");
-        let _ = fs::write(&brewfile_path, &brewfile);
-        let _ = window.emit("backup-log", format!("Brewfile gespeichert: {} Einträge", brewfile.lines().count()));
+    let mut files: Vec<PathBuf> = Vec::new();
+    if let Ok(entries) = fs::read_dir(&ssh_dir) {
+        for entry in entries.flatten().filter(|e| e.path().is_file()) {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let public = name == "config"
+                || name.starts_with("known_hosts")
+                || name == "authorized_keys"
+                || name.ends_with(".pub");
+            if public || (include_private_keys && is_private_key(&path)) {
+                files.push(path);
+            }
+        }
+    }
+    // GPG configuration only, never the keyrings
+    for name in ["gpg.conf", "gpg-agent.conf", "dirmngr.conf"] {
+        let path = home.join(".gnupg").join(name);
+        if path.is_file() {
+            files.push(path);
+        }
     }
+    files.sort();
     
-    if let Ok(manual_apps) = get_manual_apps() {
-        let manual_path = inventory_root.join("manual_apps.txt");
-        let manual_content = manual_apps.join("\n");
-        let _ = fs::write(&manual_path, &manual_content);
-        let _ = window.emit("backup-log", format!("Manuell installierte Apps: {} Apps", manual_apps.len()));
+    if files.is_empty() {
+        return Ok(None);
     }
     
-    match get_vscode_extensions() {
-        Ok(extensions) => {
-            let vscode_path = inventory_root.join("vscode_extensions.txt");
-            let vscode_content = extensions.join("\n");
-            let _ = fs::write(&vscode_path, &vscode_content);
-            let _ = window.emit("backup-log", format!("VS Code Extensions: {} Extensions", extensions.len()));
+    let staging = TempDirGuard::create("macos-backup-ssh")?;
+    let temp_dir = staging.join("ssh_config");
+    let mut included = Vec::new();
+    for file in &files {
+        let relative = file.strip_prefix(&home).unwrap_or(file);
+        let dest = temp_dir.join(relative);
+        if let Some(parent) = dest.parent() {
+            let _ = fs::create_dir_all(parent);
         }
-        Err(_) => {
-            let _ = window.emit("backup-log", "VS Code nicht installiert - Extensions übersprungen");
+        if fs::copy(file, &dest).is_ok() {
+            included.push(relative.to_string_lossy().to_string());
         }
     }
     
-    let _ = window.emit("backup-progress", serde_json::json!({
-        "progress": 15,
-        "message": "Inventur abgeschlossen."
-    }));
+    archive_special_dir(&temp_dir, backup_root, "ssh-config", passphrase, &[], format, hash_algorithm, volume_size, throttle)
+        .map(|item| Some((item, included)))
+}
+
+/// The user's login shell as recorded in Directory Services
+fn read_user_shell() -> Option<String> {
+    let user = dirs::home_dir()?.file_name()?.to_string_lossy().to_string();
+    let output = Command::new("dscl")
+        .args([".", "-read", &format!("/Users/{}", user), "UserShell"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .strip_prefix("UserShell:")
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Archive the configured dotfiles plus the login shell as the `dotfiles` item.
+/// Returns the archived paths (relative to the home directory).
+#[allow(clippy::too_many_arguments)]
+fn backup_dotfiles(
+    backup_root: &Path,
+    inventory_root: &Path,
+    dotfiles: &[String],
+    passphrase: Option<&str>,
+    format: ArchiveFormat,
+    hash_algorithm: HashAlgorithm,
+    volume_size: Option<u64>,
+    throttle: &BackupThrottle,
+) -> Result<Option<(BackupItem, Vec<String>)>, String> {
+    let home = dirs::home_dir().ok_or("Home-Verzeichnis nicht gefunden")?;
     
-    let home = dirs::home_dir().unwrap_or_default();
-    let mut items = Vec::new();
-    let total = directories.len();
+    let staging = TempDirGuard::create("macos-backup-dotfiles")?;
+    let temp_dir = staging.join("dotfiles");
+    fs::create_dir_all(&temp_dir).map_err(|e| e.to_string())?;
     
-    for (i, dir) in directories.iter().enumerate() {
-        // Check for cancellation before each directory
-        if BACKUP_CANCELLED.load(Ordering::SeqCst) {
-            let _ = window.emit("backup-log", "⚠️ Backup abgebrochen!");
-            let _ = window.emit("backup-progress", serde_json::json!({
-                "progress": 0,
-                "message": "Backup abgebrochen"
-            }));
-            BACKUP_CANCELLED.store(false, Ordering::SeqCst);
-            return Err("Backup wurde abgebrochen".to_string());
-        }
-        
-        let expanded = if dir.starts_with("~/") {
-            home.join(&dir[2..])
-        } else if dir == "~" {
-            home.clone()
-        } else {
-            PathBuf::from(dir)
-        };
-        
-        if !expanded.exists() {
-            let _ = window.emit("backup-log", format!("Überspringe {} (nicht gefunden)", dir));
+    let mut included = Vec::new();
+    for dotfile in dotfiles {
+        let source = expand_tilde(dotfile, &home);
+        // Only files inside the home directory can be mapped back on restore
+        let Ok(relative) = source.strip_prefix(&home) else { continue };
+        if !source.exists() || relative.as_os_str().is_empty() {
             continue;
         }
-        
-        let is_file = expanded.is_file();
-        
-        let name = expanded.file_name()
-            .map(|n| n.to_string_lossy().to_string())
-            .unwrap_or_else(|| "backup".to_string());
-        
-        let archive_ext = if Path::new("/opt/homebrew/bin/zstd").exists() || Path::new("/usr/local/bin/zstd").exists() { "tar.zst" } else { "tar.gz" };
-        let archive_name = format!("{}.{}", name.to_lowercase().replace(' ', "-").replace('.', "_"), archive_ext);
-        let archive_path = backup_root.join(&archive_name);
-        
-        let _ = window.emit("backup-log", format!("Archiviere {} ...", dir));
-        let progress = 15 + (60 * (i + 1) / total);
-        let _ = window.emit("backup-progress", serde_json::json!({
-            "progress": progress,
-            "message": format!("Archiviere {}...", name)
-        }));
-        
-        let source_size = if is_file {
-            fs::metadata(&expanded).map(|m| m.len()).unwrap_or(0)
+        let dest = temp_dir.join(relative);
+        if let Some(parent) = dest.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let copied = if source.is_dir() {
+            Command::new("cp")
+                .args(["-R", &source.to_string_lossy(), &dest.to_string_lossy()])
+                .output()
+                .map(|o| o.status.success())
+                .unwrap_or(false)
         } else {
-            compute_directory_size(&expanded)
+            fs::copy(&source, &dest).is_ok()
         };
-        
-        if is_file {
-            let file = fs::File::create(&archive_path).map_err(|e| e.to_string())?;
-            let encoder = GzEncoder::new(file, Compression::default());
-            let mut archive = tar::Builder::new(encoder);
-            archive.append_path_with_name(&expanded, &name).map_err(|e| e.to_string())?;
-            // Finish tar archive and get back the GzEncoder, then finish the GzEncoder to flush all data
-            let encoder = archive.into_inner().map_err(|e| e.to_string())?;
-            encoder.finish().map_err(|e| e.to_string())?;
-        } else {
-            create_tar_gz(&expanded, &archive_path)?;
+        if copied {
+            included.push(relative.to_string_lossy().to_string());
         }
-        
-        // Check for cancellation after archive
-        if BACKUP_CANCELLED.load(Ordering::SeqCst) {
-            // Clean up partial archive
-            let _ = fs::remove_file(&archive_path);
-            let _ = window.emit("backup-log", "⚠️ Backup abgebrochen!");
-            let _ = window.emit("backup-progress", serde_json::json!({
-                "progress": 0,
-                "message": "Backup abgebrochen"
-            }));
-            BACKUP_CANCELLED.store(false, Ordering::SeqCst);
-            return Err("Backup wurde abgebrochen".to_string());
-        }
-        
-        let archive_size = fs::metadata(&archive_path)
-            .map(|m| m.len())
-            .unwrap_or(0);
-        let hash = hash_file(&archive_path)?;
-        
-        items.push(BackupItem {
-            path: dir.clone(),
-            archive: archive_name,
-            hash,
-            archive_size_bytes: archive_size,
-            source_size_bytes: source_size,
-        });
     }
     
-
-    // Archive Homebrew packages as a restorable item
-    if let Ok(brewfile) = get_brew_packages() {
-        let brew_archive_name = if Path::new("/opt/homebrew/bin/zstd").exists() || Path::new("/usr/local/bin/zstd").exists() { "homebrew-packages.tar.zst" } else { "homebrew-packages.tar.gz" };
-        let brew_archive_path = backup_root.join(brew_archive_name);
-        let brew_temp = std::env::temp_dir().join("homebrew_packages.txt");
-        let _ = fs::write(&brew_temp, &brewfile);
-        
-        if brew_temp.exists() {
-            let source_size = fs::metadata(&brew_temp).map(|m| m.len()).unwrap_or(0);
-            let file = fs::File::create(&brew_archive_path).map_err(|e| e.to_string())?;
-            let encoder = GzEncoder::new(file, Compression::default());
-            let mut archive = tar::Builder::new(encoder);
-            archive.append_path_with_name(&brew_temp, "homebrew_packages.txt").map_err(|e| e.to_string())?;
-            // Finish tar archive and get back the GzEncoder, then finish the GzEncoder to flush all data
-            let encoder = archive.into_inner().map_err(|e| e.to_string())?;
-            encoder.finish().map_err(|e| e.to_string())?;
-            
-            let archive_size = fs::metadata(&brew_archive_path).map(|m| m.len()).unwrap_or(0);
-            let hash = hash_file(&brew_archive_path)?;
-            
-            items.push(BackupItem {
-                path: "homebrew-packages".to_string(),
-                archive: brew_archive_name.to_string(),
-                hash,
-                archive_size_bytes: archive_size,
-                source_size_bytes: source_size,
-            });
-            let _ = window.emit("backup-log", format!("Homebrew-Pakete archiviert: {} Bytes", source_size));
-        }
-        let _ = fs::remove_file(&brew_temp);
+    if let Some(shell) = read_user_shell() {
+        let _ = fs::write(temp_dir.join("login_shell.txt"), &shell);
+        let _ = fs::write(inventory_root.join("login_shell.txt"), &shell);
     }
     
-    // Archive MAS apps as a restorable item
-    {
-        let mas_temp = std::env::temp_dir().join("mas_apps.txt");
-        if let Ok(brewfile) = get_brew_packages() {
-            let mas_lines: Vec<&str> = brewfile.lines()
-                .filter(|line| line.trim().starts_with("mas "))
-                .collect();
-            if !mas_lines.is_empty() {
-                let mas_content = mas_lines.join("
-");
-                let _ = fs::write(&mas_temp, &mas_content);
-            }
+    if included.is_empty() {
+        Ok(None)
+    } else {
+        archive_special_dir(&temp_dir, backup_root, "dotfiles", passphrase, &[], format, hash_algorithm, volume_size, throttle)
+            .map(|item| Some((item, included)))
+    }
+}
+
+/// Copy the font files below `source` into `dest`, except fonts that also ship in
+/// /System/Library/Fonts (those are protected and come with every macOS install)
+fn stage_fonts(source: &Path, dest: &Path) -> usize {
+    let system_fonts = Path::new("/System/Library/Fonts");
+    let mut count = 0;
+    for entry in WalkDir::new(source).min_depth(1).into_iter().flatten() {
+        let Ok(relative) = entry.path().strip_prefix(source) else { continue };
+        if !entry.file_type().is_file() || system_fonts.join(entry.file_name()).exists() {
+            continue;
         }
-        
-        if mas_temp.exists() {
-            let mas_archive_name = if Path::new("/opt/homebrew/bin/zstd").exists() || Path::new("/usr/local/bin/zstd").exists() { "mas-apps.tar.zst" } else { "mas-apps.tar.gz" };
-            let mas_archive_path = backup_root.join(mas_archive_name);
-            let source_size = fs::metadata(&mas_temp).map(|m| m.len()).unwrap_or(0);
-            
-            let file = fs::File::create(&mas_archive_path).map_err(|e| e.to_string())?;
-            let encoder = GzEncoder::new(file, Compression::default());
-            let mut archive = tar::Builder::new(encoder);
-            archive.append_path_with_name(&mas_temp, "mas_apps.txt").map_err(|e| e.to_string())?;
-            // Finish tar archive and get back the GzEncoder, then finish the GzEncoder to flush all data
-            let encoder = archive.into_inner().map_err(|e| e.to_string())?;
-            encoder.finish().map_err(|e| e.to_string())?;
-            
-            let archive_size = fs::metadata(&mas_archive_path).map(|m| m.len()).unwrap_or(0);
-            let hash = hash_file(&mas_archive_path)?;
-            
-            items.push(BackupItem {
-                path: "mas-apps".to_string(),
-                archive: mas_archive_name.to_string(),
-                hash,
-                archive_size_bytes: archive_size,
-                source_size_bytes: source_size,
-            });
-            let _ = window.emit("backup-log", format!("MAS Apps archiviert: {} Bytes", source_size));
-            let _ = fs::remove_file(&mas_temp);
+        let target = dest.join(relative);
+        if let Some(parent) = target.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        // Unreadable fonts in /Library/Fonts are skipped
+        if fs::copy(entry.path(), &target).is_ok() {
+            count += 1;
         }
     }
+    count
+}
+
+/// Archive the user's fonts (~/Library/Fonts) and the readable fonts in /Library/Fonts
+/// as the `fonts` item. Returns the number of font files.
+fn backup_fonts(backup_root: &Path, passphrase: Option<&str>, format: ArchiveFormat, hash_algorithm: HashAlgorithm, volume_size: Option<u64>, throttle: &BackupThrottle) -> Result<Option<(BackupItem, usize)>, String> {
+    let home = dirs::home_dir().ok_or("Home-Verzeichnis nicht gefunden")?;
     
-    // Archive VS Code extensions as a restorable item
-    if let Ok(extensions) = get_vscode_extensions() {
-        let vscode_archive_name = if Path::new("/opt/homebrew/bin/zstd").exists() || Path::new("/usr/local/bin/zstd").exists() { "vscode-extensions.tar.zst" } else { "vscode-extensions.tar.gz" };
-        let vscode_archive_path = backup_root.join(vscode_archive_name);
-        let vscode_temp = std::env::temp_dir().join("vscode_extensions.txt");
-        let vscode_content = extensions.join("
-");
-        let _ = fs::write(&vscode_temp, &vscode_content);
-        
-        if vscode_temp.exists() {
-            let source_size = fs::metadata(&vscode_temp).map(|m| m.len()).unwrap_or(0);
-            let file = fs::File::create(&vscode_archive_path).map_err(|e| e.to_string())?;
-            let encoder = GzEncoder::new(file, Compression::default());
-            let mut archive = tar::Builder::new(encoder);
-            archive.append_path_with_name(&vscode_temp, "vscode_extensions.txt").map_err(|e| e.to_string())?;
-            // Finish tar archive and get back the GzEncoder, then finish the GzEncoder to flush all data
-            let encoder = archive.into_inner().map_err(|e| e.to_string())?;
-            encoder.finish().map_err(|e| e.to_string())?;
-            
-            let archive_size = fs::metadata(&vscode_archive_path).map(|m| m.len()).unwrap_or(0);
-            let hash = hash_file(&vscode_archive_path)?;
-            
-            items.push(BackupItem {
-                path: "vscode-extensions".to_string(),
-                archive: vscode_archive_name.to_string(),
-                hash,
-                archive_size_bytes: archive_size,
-                source_size_bytes: source_size,
+    let staging = TempDirGuard::create("macos-backup-fonts")?;
+    let temp_dir = staging.join("fonts");
+    fs::create_dir_all(&temp_dir).map_err(|e| e.to_string())?;
+    
+    let count = stage_fonts(&home.join("Library/Fonts"), &temp_dir.join("user"))
+        + stage_fonts(Path::new("/Library/Fonts"), &temp_dir.join("library"));
+    
+    if count == 0 {
+        Ok(None)
+    } else {
+        archive_special_dir(&temp_dir, backup_root, "fonts", passphrase, &[], format, hash_algorithm, volume_size, throttle)
+            .map(|item| Some((item, count)))
+    }
+}
+
+/// A local clone recorded in `git_repositories.json`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GitRepository {
+    /// Working tree, with `~` for the home directory
+    pub path: String,
+    pub remote_url: String,
+    /// Checked-out branch, `None` for a detached HEAD
+    pub branch: Option<String>,
+}
+
+/// How deep below a root `scan_git_repositories` looks for repositories
+const GIT_SCAN_MAX_DEPTH: usize = 4;
+
+/// Output of `git -C <repo> <args>`, `None` if git failed or printed nothing
+fn git_output(repo: &Path, args: &[&str]) -> Option<String> {
+    Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Find git working trees below `roots` (not inside other repositories or hidden folders).
+/// Repositories without an `origin` remote are skipped, they can't be cloned again.
+fn find_git_repositories(roots: &[String]) -> Vec<GitRepository> {
+    let home = dirs::home_dir().unwrap_or_default();
+    let mut repositories = Vec::new();
+    for root in roots.iter().filter(|r| !r.trim().is_empty()) {
+        let mut entries = WalkDir::new(expand_tilde(root, &home))
+            .max_depth(GIT_SCAN_MAX_DEPTH)
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'));
+        while let Some(entry) = entries.next() {
+            let Ok(entry) = entry else { continue };
+            if !entry.file_type().is_dir() || !entry.path().join(".git").exists() {
+                continue;
+            }
+            entries.skip_current_dir();
+            let Some(remote_url) = git_output(entry.path(), &["config", "--get", "remote.origin.url"]) else { continue };
+            let path = match entry.path().strip_prefix(&home) {
+                Ok(relative) => format!("~/{}", relative.to_string_lossy()),
+                Err(_) => entry.path().to_string_lossy().to_string(),
+            };
+            if repositories.iter().any(|r: &GitRepository| r.path == path) {
+                continue;
+            }
+            repositories.push(GitRepository {
+                path,
+                remote_url,
+                branch: git_output(entry.path(), &["symbolic-ref", "--quiet", "--short", "HEAD"]),
             });
-            let _ = window.emit("backup-log", format!("VS Code Extensions archiviert: {} Extensions", extensions.len()));
         }
-        let _ = fs::remove_file(&vscode_temp);
     }
+    repositories
+}
 
-    // Optional: Backup Homebrew Download Cache for offline installations (max 2GB)
-    let config = load_config().unwrap_or_default();
-    if config.backup_homebrew_cache {
-        let _ = window.emit("backup-log", "Prüfe Homebrew-Cache...");
-        
-        // Homebrew cache locations
-        let cache_paths = [
-            PathBuf::from("/opt/homebrew/var/homebrew/cache"),
-            PathBuf::from("/usr/local/var/homebrew/cache"),
-            dirs::home_dir().unwrap_or_default().join("Library/Caches/Homebrew"),
-        ];
-        
-        let mut cache_path: Option<PathBuf> = None;
-        for path in &cache_paths {
-            if path.exists() {
-                cache_path = Some(path.clone());
-                break;
+/// List the git repositories below `roots` that would be recorded with a backup
+#[tauri::command]
+async fn scan_git_repositories(roots: Vec<String>) -> Result<Vec<GitRepository>, String> {
+    Ok(find_git_repositories(&roots))
+}
+
+/// Git repositories recorded with a backup, empty if none were recorded
+#[tauri::command]
+fn get_git_repositories(target_path: String, timestamp: String) -> Result<Vec<GitRepository>, String> {
+    let manifest_path = PathBuf::from(&target_path)
+        .join("macos-backup-suite")
+        .join("inventories")
+        .join(&timestamp)
+        .join("git_repositories.json");
+    if !manifest_path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&manifest_path)
+        .map_err(|e| format!("Fehler beim Lesen von git_repositories.json: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Fehler beim Parsen von git_repositories.json: {}", e))
+}
+
+/// Clone every recorded repository whose path doesn't exist yet and check out its recorded branch
+#[tauri::command]
+async fn restore_git_repositories(
+    target_path: String,
+    timestamp: String,
+    window: tauri::Window,
+) -> Result<RestoreResult, String> {
+    RESTORE_CANCELLED.store(false, Ordering::SeqCst);
+    let repositories = get_git_repositories(target_path, timestamp)?;
+    if repositories.is_empty() {
+        return Err("Keine Git-Repositories in diesem Backup verzeichnet".to_string());
+    }
+    
+    let home = dirs::home_dir().unwrap_or_default();
+    let mut restored = Vec::new();
+    let mut skipped = Vec::new();
+    let mut errors = Vec::new();
+    for (i, repo) in repositories.iter().enumerate() {
+        if RESTORE_CANCELLED.load(Ordering::SeqCst) {
+            break;
+        }
+        let dest = expand_tilde(&repo.path, &home);
+        if dest.exists() {
+            skipped.push(format!("{} (bereits vorhanden)", repo.path));
+            continue;
+        }
+        let _ = window.emit("restore-progress", serde_json::json!({
+            "progress": 100 * i / repositories.len(),
+            "message": format!("Klone {}...", repo.path)
+        }));
+        let _ = window.emit("restore-log", format!("Klone {} nach {}", repo.remote_url, repo.path));
+        if let Some(parent) = dest.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        // No credential prompt: private remotes without stored credentials fail instead of hanging
+        let cloned = run_restore_command(Command::new("git")
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .arg("clone")
+            .arg(&repo.remote_url)
+            .arg(&dest));
+        match cloned {
+            Ok(output) if output.status.success() => {}
+            Ok(output) => {
+                errors.push(format!("{}: {}", repo.path, String::from_utf8_lossy(&output.stderr).trim()));
+                continue;
+            }
+            Err(e) => {
+                errors.push(format!("{}: {}", repo.path, e));
+                continue;
             }
         }
-        
-        if let Some(cache_dir) = cache_path {
-            // Calculate cache size and limit to 2GB
-            let cache_size = compute_directory_size(&cache_dir);
-            const MAX_CACHE_SIZE: u64 = 2 * 1024 * 1024 * 1024; // 2GB
-            
-            if cache_size > 0 && cache_size <= MAX_CACHE_SIZE {
-                let cache_archive_name = if Path::new("/opt/homebrew/bin/zstd").exists() || Path::new("/usr/local/bin/zstd").exists() { "homebrew-cache.tar.zst" } else { "homebrew-cache.tar.gz" };
-                let cache_archive_path = backup_root.join(cache_archive_name);
-                
-                let _ = window.emit("backup-log", format!("Archiviere Homebrew-Cache ({:.1} MB)...", cache_size as f64 / (1024.0 * 1024.0)));
-                
-                if create_tar_gz(&cache_dir, &cache_archive_path).is_ok() {
-                    let archive_size = fs::metadata(&cache_archive_path).map(|m| m.len()).unwrap_or(0);
-                    if let Ok(hash) = hash_file(&cache_archive_path) {
-                        items.push(BackupItem {
-                            path: "homebrew-cache".to_string(),
-                            archive: cache_archive_name.to_string(),
-                            hash,
-                            archive_size_bytes: archive_size,
-                            source_size_bytes: cache_size,
-                        });
-                        let _ = window.emit("backup-log", format!("✅ Homebrew-Cache archiviert: {:.1} MB", archive_size as f64 / (1024.0 * 1024.0)));
-                    }
-                }
-            } else if cache_size > MAX_CACHE_SIZE {
-                let _ = window.emit("backup-log", format!("⚠️ Homebrew-Cache zu groß ({:.1} GB > 2 GB max), übersprungen", cache_size as f64 / (1024.0 * 1024.0 * 1024.0)));
+        if let Some(branch) = &repo.branch {
+            let checked_out = git_output(&dest, &["symbolic-ref", "--quiet", "--short", "HEAD"]).as_ref() == Some(branch)
+                || Command::new("git").arg("-C").arg(&dest).args(["checkout", branch]).output().is_ok_and(|o| o.status.success());
+            if !checked_out {
+                let _ = window.emit("restore-log", format!("⚠️ {}: Branch {} konnte nicht ausgecheckt werden", repo.path, branch));
             }
         }
+        let _ = window.emit("restore-log", format!("✅ {}", repo.path));
+        restored.push(repo.path.clone());
     }
+    
+    Ok(RestoreResult {
+        restored_count: restored.len(),
+        skipped_count: skipped.len(),
+        error_count: errors.len(),
+        restored,
+        skipped,
+        errors,
+    })
+}
 
-    // Optional: Backup Safari Settings including Bookmarks
-    if config.backup_safari_settings {
-        let _ = window.emit("backup-log", "Sichere Safari-Einstellungen...");
-        
-        let home = dirs::home_dir().unwrap_or_default();
-        let safari_paths = vec![
-            // Safari Bookmarks
-            home.join("Library/Safari/Bookmarks.plist"),
-            // Safari History (optional, can be large)
-            // home.join("Library/Safari/History.db"),
-            // Safari Reading List
-            home.join("Library/Safari/ReadingListArchives"),
-            // Safari Extensions
-            home.join("Library/Safari/Extensions"),
-            // Safari Preferences
-            home.join("Library/Preferences/com.apple.Safari.plist"),
-            // Safari Sandbox data (contains tabs, etc.)
-            home.join("Library/Containers/com.apple.Safari/Data/Library/Preferences"),
-            // Safari Favorites icons
-            home.join("Library/Safari/Favicon Cache"),
-            // Top Sites
-            home.join("Library/Safari/TopSites.plist"),
-            // Last Session
-            home.join("Library/Safari/LastSession.plist"),
-        ];
-        
-        let temp_safari_dir = std::env::temp_dir().join("safari_backup");
-        let _ = fs::create_dir_all(&temp_safari_dir);
-        
-        let mut copied_count = 0;
-        for safari_path in &safari_paths {
-            if safari_path.exists() {
-                let relative_name = safari_path.file_name()
-                    .map(|n| n.to_string_lossy().to_string())
-                    .unwrap_or_else(|| "unknown".to_string());
-                
-                let dest = temp_safari_dir.join(&relative_name);
-                
-                if safari_path.is_file() {
-                    if fs::copy(safari_path, &dest).is_ok() {
-                        copied_count += 1;
+/// Login shell recorded with a backup, if it differs from the current one
+#[tauri::command]
+fn get_recorded_shell(target_path: String, timestamp: String) -> Result<Option<String>, String> {
+    let shell_path = PathBuf::from(&target_path)
+        .join("macos-backup-suite")
+        .join("inventories")
+        .join(&timestamp)
+        .join("login_shell.txt");
+    let recorded = fs::read_to_string(&shell_path).ok().map(|s| s.trim().to_string());
+    Ok(recorded.filter(|shell| !shell.is_empty() && Some(shell) != read_user_shell().as_ref()))
+}
+
+/// Set the login shell with `chsh`, asking for the administrator password
+#[tauri::command]
+fn change_login_shell(shell: String) -> Result<(), String> {
+    if !Path::new(&shell).exists() {
+        return Err(format!("Shell nicht installiert: {}", shell));
+    }
+    // chsh only accepts login shells listed in /etc/shells; checking here also keeps
+    // arbitrary commands out of the privileged call
+    let allowed = fs::read_to_string("/etc/shells")
+        .map_err(|e| format!("/etc/shells konnte nicht gelesen werden: {}", e))?;
+    if !allowed.lines().map(str::trim).any(|line| !line.starts_with('#') && line == shell) {
+        return Err(format!("Shell ist nicht in /etc/shells eingetragen: {}", shell));
+    }
+    let user = dirs::home_dir()
+        .and_then(|h| h.file_name().map(|n| n.to_string_lossy().to_string()))
+        .ok_or("Benutzer nicht gefunden")?;
+    // Shell and user are passed as arguments and quoted by AppleScript, not spliced into the script
+    let output = Command::new("osascript")
+        .args([
+            "-e", "on run argv",
+            "-e", "do shell script \"chsh -s \" & quoted form of item 1 of argv & \" \" & quoted form of item 2 of argv with administrator privileges",
+            "-e", "end run",
+            &shell,
+            &user,
+        ])
+        .output()
+        .map_err(|e| format!("osascript Fehler: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("chsh fehlgeschlagen: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(())
+}
+
+/// Browsers whose settings can be backed up as special items
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BrowserKind {
+    Safari,
+    Chrome,
+    Firefox,
+}
+
+impl BrowserKind {
+    fn label(self) -> &'static str {
+        match self {
+            BrowserKind::Safari => "Safari",
+            BrowserKind::Chrome => "Chrome",
+            BrowserKind::Firefox => "Firefox",
+        }
+    }
+    
+    fn item_name(self) -> &'static str {
+        match self {
+            BrowserKind::Safari => "safari-settings",
+            BrowserKind::Chrome => "chrome-settings",
+            BrowserKind::Firefox => "firefox-settings",
+        }
+    }
+    
+    /// Name of the staging directory, which becomes the top-level entry of the archive
+    fn stage_dir_name(self) -> &'static str {
+        match self {
+            BrowserKind::Safari => "safari_backup",
+            BrowserKind::Chrome => "chrome_backup",
+            BrowserKind::Firefox => "firefox_backup",
+        }
+    }
+    
+    /// Files to back up as (name inside the archive, location on disk).
+    /// Only bookmarks and preferences - history and caches are left out to keep archives small.
+    fn backup_paths(self, home: &Path) -> Vec<(String, PathBuf)> {
+        match self {
+            BrowserKind::Safari => {
+                vec![
+                    // Safari Bookmarks
+                    home.join("Library/Safari/Bookmarks.plist"),
+                    // Safari History (optional, can be large)
+                    // home.join("Library/Safari/History.db"),
+                    // Safari Reading List
+                    home.join("Library/Safari/ReadingListArchives"),
+                    // Safari Extensions
+                    home.join("Library/Safari/Extensions"),
+                    // Safari Preferences
+                    home.join("Library/Preferences/com.apple.Safari.plist"),
+                    // Safari Sandbox data (contains tabs, etc.)
+                    home.join("Library/Containers/com.apple.Safari/Data/Library/Preferences"),
+                    // Safari Favorites icons
+                    home.join("Library/Safari/Favicon Cache"),
+                    // Top Sites
+                    home.join("Library/Safari/TopSites.plist"),
+                    // Last Session
+                    home.join("Library/Safari/LastSession.plist"),
+                ]
+                .into_iter()
+                .map(|p| {
+                    let name = p.file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_else(|| "unknown".to_string());
+                    (name, p)
+                })
+                .collect()
+            }
+            BrowserKind::Chrome => {
+                let profile = home.join("Library/Application Support/Google/Chrome/Default");
+                ["Bookmarks", "Preferences"]
+                    .iter()
+                    .map(|name| (name.to_string(), profile.join(name)))
+                    .collect()
+            }
+            BrowserKind::Firefox => {
+                // Bookmarks live in places.sqlite together with the history, so only the
+                // bookmark backups (importable via "Lesezeichen wiederherstellen") are saved
+                let firefox_root = home.join("Library/Application Support/Firefox");
+                let mut paths = vec![("profiles.ini".to_string(), firefox_root.join("profiles.ini"))];
+                if let Ok(entries) = fs::read_dir(firefox_root.join("Profiles")) {
+                    for entry in entries.flatten().filter(|e| e.path().is_dir()) {
+                        let profile_name = entry.file_name().to_string_lossy().to_string();
+                        for name in ["bookmarkbackups", "prefs.js", "containers.json"] {
+                            paths.push((format!("Profiles/{}/{}", profile_name, name), entry.path().join(name)));
+                        }
                     }
-                } else if safari_path.is_dir() {
-                    // Copy directory recursively
-                    let _ = Command::new("cp")
-                        .args(["-R", &safari_path.to_string_lossy(), &dest.to_string_lossy()])
-                        .output();
-                    copied_count += 1;
                 }
+                paths
             }
         }
-        
-        if copied_count > 0 {
-            let safari_archive_name = if Path::new("/opt/homebrew/bin/zstd").exists() || Path::new("/usr/local/bin/zstd").exists() { "safari-settings.tar.zst" } else { "safari-settings.tar.gz" };
-            let safari_archive_path = backup_root.join(safari_archive_name);
-            
-            if create_tar_gz(&temp_safari_dir, &safari_archive_path).is_ok() {
-                let source_size = compute_directory_size(&temp_safari_dir);
-                let archive_size = fs::metadata(&safari_archive_path).map(|m| m.len()).unwrap_or(0);
-                
-                if let Ok(hash) = hash_file(&safari_archive_path) {
-                    items.push(BackupItem {
-                        path: "safari-settings".to_string(),
-                        archive: safari_archive_name.to_string(),
-                        hash,
-                        archive_size_bytes: archive_size,
-                        source_size_bytes: source_size,
-                    });
-                    let _ = window.emit("backup-log", format!("✅ Safari-Einstellungen archiviert: {} Dateien/Ordner", copied_count));
+    }
+    
+    /// Files to restore as (name inside the archive, destination), resolved against the extracted archive
+    fn restore_paths(self, extracted: &Path, home: &Path) -> Vec<(String, PathBuf)> {
+        match self {
+            BrowserKind::Safari => vec![
+                ("Bookmarks.plist".to_string(), home.join("Library/Safari/Bookmarks.plist")),
+                ("ReadingListArchives".to_string(), home.join("Library/Safari/ReadingListArchives")),
+                ("Extensions".to_string(), home.join("Library/Safari/Extensions")),
+                ("TopSites.plist".to_string(), home.join("Library/Safari/TopSites.plist")),
+                ("LastSession.plist".to_string(), home.join("Library/Safari/LastSession.plist")),
+                ("Preferences".to_string(), home.join("Library/Containers/com.apple.Safari/Data/Library/Preferences")),
+            ],
+            BrowserKind::Chrome => {
+                let profile = home.join("Library/Application Support/Google/Chrome/Default");
+                ["Bookmarks", "Preferences"]
+                    .iter()
+                    .map(|name| (name.to_string(), profile.join(name)))
+                    .collect()
+            }
+            BrowserKind::Firefox => {
+                let firefox_root = home.join("Library/Application Support/Firefox");
+                let mut paths = Vec::new();
+                // Keep an existing profiles.ini so profiles created on this Mac stay registered
+                if !firefox_root.join("profiles.ini").exists() {
+                    paths.push(("profiles.ini".to_string(), firefox_root.join("profiles.ini")));
+                }
+                if let Ok(profiles) = fs::read_dir(extracted.join("Profiles")) {
+                    for profile in profiles.flatten() {
+                        let profile_name = profile.file_name().to_string_lossy().to_string();
+                        if let Ok(files) = fs::read_dir(profile.path()) {
+                            for file in files.flatten() {
+                                let relative = format!("Profiles/{}/{}", profile_name, file.file_name().to_string_lossy());
+                                paths.push((relative.clone(), firefox_root.join(&relative)));
+                            }
+                        }
+                    }
                 }
+                paths
             }
-        } else {
-            let _ = window.emit("backup-log", "⚠️ Keine Safari-Einstellungen gefunden");
         }
-        
-        let _ = fs::remove_dir_all(&temp_safari_dir);
     }
+}
 
-    let end = Local::now();
-    let end_time_str = end.format("%d.%m.%Y %H:%M:%S").to_string();
-    let duration = (end - start).num_seconds() as u64;
-    
-    let total_size: u64 = items.iter().map(|i| i.source_size_bytes).sum();
-    
-    let metadata = BackupMetadata {
-        timestamp: timestamp.clone(),
-        items,
-        hash_algorithm: "sha256".to_string(),
-        total_source_size_bytes: total_size,
-        start_time: start_time_str.clone(),
-        end_time: end_time_str.clone(),
-        duration_seconds: duration,
-    };
-    
-    let metadata_json = serde_json::to_string_pretty(&metadata).map_err(|e| e.to_string())?;
-    fs::write(backup_root.join("metadata.json"), &metadata_json).map_err(|e| e.to_string())?;
+/// Copy a browser's settings into a staging directory and archive them.
+/// Returns `None` when none of the browser's files exist.
+#[allow(clippy::too_many_arguments)]
+fn backup_browser(
+    browser: BrowserKind,
+    backup_root: &Path,
+    passphrase: Option<&str>,
+    exclude_patterns: &[String],
+    format: ArchiveFormat,
+    hash_algorithm: HashAlgorithm,
+    volume_size: Option<u64>,
+    throttle: &BackupThrottle,
+) -> Result<Option<(BackupItem, usize)>, String> {
+    let home = dirs::home_dir().unwrap_or_default();
     
-    // Copy the DMG installer to backup root (always include app in backup)
-    let dmg_filename = "macOS Backup Suite.dmg";
-    let dmg_dest = suite_root.join(dmg_filename);
-    let mut dmg_copied = false;
+    let staging = TempDirGuard::create("macos-backup-browser")?;
+    let temp_dir = staging.join(browser.stage_dir_name());
+    fs::create_dir_all(&temp_dir).map_err(|e| e.to_string())?;
     
-    // Look for DMG in the app bundle's Resources folder
-    if let Ok(exe) = std::env::current_exe() {
-        // exe is at: App.app/Contents/MacOS/binary
-        // We need: App.app/Contents/Resources/
-        if let Some(macos_dir) = exe.parent() {
-            let resources_dmg = macos_dir.parent()
-                .map(|contents| contents.join("Resources").join(dmg_filename));
-            
-            if let Some(ref src) = resources_dmg {
-                if src.exists() {
-                    if fs::copy(src, &dmg_dest).is_ok() {
-                        let _ = window.emit("backup-log", format!("✅ App-Installer kopiert: {}", dmg_filename));
-                        dmg_copied = true;
-                    }
-                }
-            }
+    let mut copied_count = 0;
+    for (name, source) in browser.backup_paths(&home) {
+        if !source.exists() {
+            continue;
+        }
+        let dest = temp_dir.join(&name);
+        if let Some(parent) = dest.parent() {
+            let _ = fs::create_dir_all(parent);
         }
-    }
-    
-    // Fallback: Look in multiple locations
-    if !dmg_copied {
-        let home = dirs::home_dir().unwrap_or_default();
-        let dev_paths = [
-            // Development build paths (relative)
-            PathBuf::from("src-tauri/target/release/bundle/dmg/macOS Backup Suite_1.0.0_aarch64.dmg"),
-            PathBuf::from("src-tauri/target/release/bundle/macos/macOS Backup Suite.app/Contents/Resources/macOS Backup Suite.dmg"),
-            // Common development locations (absolute)
-            home.join("Documents/GitHub/macos-backup-tauri/src-tauri/target/release/bundle/dmg/macOS Backup Suite_1.0.0_aarch64.dmg"),
-            home.join("Documents/GitHub/macos-backup-tauri/src-tauri/target/release/bundle/macos/macOS Backup Suite.app/Contents/Resources/macOS Backup Suite.dmg"),
-            // Applications folder
-            PathBuf::from("/Applications/macOS Backup Suite.app/Contents/Resources/macOS Backup Suite.dmg"),
-        ];
         
-        for dev_path in &dev_paths {
-            if dev_path.exists() {
-                if fs::copy(dev_path, &dmg_dest).is_ok() {
-                    let _ = window.emit("backup-log", format!("✅ App-Installer kopiert: {}", dmg_filename));
-                    dmg_copied = true;
-                    break;
-                }
+        if source.is_file() {
+            if fs::copy(&source, &dest).is_ok() {
+                copied_count += 1;
             }
+        } else if source.is_dir() {
+            // Copy directory recursively
+            let _ = Command::new("cp")
+                .args(["-R", &source.to_string_lossy(), &dest.to_string_lossy()])
+                .output();
+            copied_count += 1;
         }
     }
     
-    if !dmg_copied {
-        let _ = window.emit("backup-log", "ℹ️ App-Installer (DMG) nicht gefunden - führen Sie 'npm run tauri build' aus");
-    }
-    
-    let latest = serde_json::json!({
-        "latest": timestamp,
-        "created_at": end.to_rfc3339()
-    });
-    fs::write(suite_root.join("latest.json"), latest.to_string()).map_err(|e| e.to_string())?;
-    
-    let duration_str = if duration >= 3600 {
-        format!("{}h {}m {}s", duration / 3600, (duration % 3600) / 60, duration % 60)
-    } else if duration >= 60 {
-        format!("{}m {}s", duration / 60, duration % 60)
+    if copied_count > 0 {
+        archive_special_dir(&temp_dir, backup_root, browser.item_name(), passphrase, exclude_patterns, format, hash_algorithm, volume_size, throttle)
+            .map(|item| Some((item, copied_count)))
     } else {
-        format!("{}s", duration)
-    };
+        Ok(None)
+    }
+}
+
+/// Validate a property list with `plutil -lint`
+fn plist_is_valid(path: &Path) -> bool {
+    fs::metadata(path).map(|m| m.len() > 0).unwrap_or(false)
+        && Command::new("plutil")
+            .args(["-lint", &path.to_string_lossy()])
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+}
+
+/// Copy the Dock preferences into the inventory and archive them as the `dock-layout` item
+fn backup_dock(inventory_root: &Path, backup_root: &Path, passphrase: Option<&str>, format: ArchiveFormat, hash_algorithm: HashAlgorithm, volume_size: Option<u64>, throttle: &BackupThrottle) -> Result<BackupItem, String> {
+    let dock_plist = dirs::home_dir().unwrap_or_default().join("Library/Preferences/com.apple.dock.plist");
+    if !plist_is_valid(&dock_plist) {
+        return Err("Dock-Einstellungen fehlen oder sind beschädigt (plutil -lint)".to_string());
+    }
     
-    let _ = window.emit("backup-log", format!("=== Backup beendet: {} (Dauer: {}) ===", end_time_str, duration_str));
-    let _ = window.emit("backup-progress", serde_json::json!({
-        "progress": 100,
-        "message": "Backup abgeschlossen."
-    }));
+    let _ = fs::copy(&dock_plist, inventory_root.join("com.apple.dock.plist"));
     
-    Ok(metadata)
+    let staging = TempDirGuard::create("macos-backup-dock")?;
+    let dock_temp = staging.join("dock_layout");
+    fs::create_dir_all(&dock_temp).map_err(|e| e.to_string())?;
+    fs::copy(&dock_plist, dock_temp.join("com.apple.dock.plist"))
+        .map_err(|e| e.to_string())
+        .and_then(|_| archive_special_dir(&dock_temp, backup_root, "dock-layout", passphrase, &[], format, hash_algorithm, volume_size, throttle))
 }
 
-#[tauri::command]
-async fn verify_backup(
-    window: tauri::Window,
-    target_path: String,
-    timestamp: String,
-) -> Result<VerifyResult, String> {
-    let backup_path = PathBuf::from(&target_path)
-        .join("macos-backup-suite")
-        .join("data")
-        .join(&timestamp);
+/// Preferences of the supported terminal apps: file name, `defaults` domain and process name
+const TERMINAL_PREFERENCES: &[(&str, &str, &str)] = &[
+    ("com.apple.Terminal.plist", "com.apple.Terminal", "Terminal"),
+    ("com.googlecode.iterm2.plist", "com.googlecode.iterm2", "iTerm2"),
+];
+
+/// iTerm2's dynamic profiles, relative to the home directory
+const ITERM2_DYNAMIC_PROFILES: &str = "Library/Application Support/iTerm2/DynamicProfiles";
+
+/// The `terminal-settings` item with what it includes and the plists left out as corrupt
+type TerminalSettingsBackup = (BackupItem, Vec<String>, Vec<String>);
+
+/// Archive the Terminal and iTerm2 preferences plus iTerm2's dynamic profiles as the `terminal-settings` item.
+/// Plists that fail `plutil -lint` are left out so a corrupt preference doesn't end up in the backup.
+fn backup_terminal_settings(backup_root: &Path, passphrase: Option<&str>, format: ArchiveFormat, hash_algorithm: HashAlgorithm, volume_size: Option<u64>, throttle: &BackupThrottle) -> Result<Option<TerminalSettingsBackup>, String> {
+    let home = dirs::home_dir().ok_or("Home-Verzeichnis nicht gefunden")?;
     
-    let metadata_path = backup_path.join("metadata.json");
-    if !metadata_path.exists() {
-        return Err(format!("Backup nicht gefunden: {}", timestamp));
-    }
+    let staging = TempDirGuard::create("macos-backup-terminal")?;
+    let temp_dir = staging.join("terminal_settings");
+    fs::create_dir_all(&temp_dir).map_err(|e| e.to_string())?;
     
-    let metadata_content = fs::read_to_string(&metadata_path)
-        .map_err(|e| format!("Fehler beim Lesen der Metadaten: {}", e))?;
-    let metadata: BackupMetadata = serde_json::from_str(&metadata_content)
-        .map_err(|e| format!("Fehler beim Parsen der Metadaten: {}", e))?;
+    let mut included = Vec::new();
+    let mut skipped = Vec::new();
+    for (file_name, _, app) in TERMINAL_PREFERENCES {
+        let plist = home.join("Library/Preferences").join(file_name);
+        if !plist.exists() {
+            continue;
+        }
+        if !plist_is_valid(&plist) {
+            skipped.push(file_name.to_string());
+            continue;
+        }
+        if fs::copy(&plist, temp_dir.join(file_name)).is_ok() {
+            included.push(app.to_string());
+        }
+    }
     
-    let total_files = metadata.items.len();
-    let mut verified_files = 0;
-    let mut failed_files = Vec::new();
+    let dynamic_profiles = home.join(ITERM2_DYNAMIC_PROFILES);
+    let mut profile_count = 0;
+    if let Ok(entries) = fs::read_dir(&dynamic_profiles) {
+        let staged = temp_dir.join("DynamicProfiles");
+        let _ = fs::create_dir_all(&staged);
+        for entry in entries.flatten().filter(|e| e.path().is_file()) {
+            if fs::copy(entry.path(), staged.join(entry.file_name())).is_ok() {
+                profile_count += 1;
+            }
+        }
+    }
+    if profile_count > 0 {
+        included.push(format!("{} dynamische iTerm2-Profile", profile_count));
+    }
     
-    for (i, item) in metadata.items.iter().enumerate() {
-        let archive_path = backup_path.join(&item.archive);
-        
-        let progress_msg = format!("Verifiziere {}/{}: {}", i + 1, total_files, item.archive);
-        let _ = window.emit("backup-log", progress_msg);
-        
-        if !archive_path.exists() {
-            failed_files.push(format!("{}: Datei nicht gefunden", item.archive));
+    if included.is_empty() {
+        Ok(None)
+    } else {
+        archive_special_dir(&temp_dir, backup_root, "terminal-settings", passphrase, &[], format, hash_algorithm, volume_size, throttle)
+            .map(|item| Some((item, included, skipped)))
+    }
+}
+
+/// Export the configured `defaults` domains as plists into the inventory and archive them
+/// as the `system-defaults` item. Domains that cannot be exported are skipped.
+#[allow(clippy::too_many_arguments)]
+fn backup_system_defaults(inventory_root: &Path, backup_root: &Path, domains: &[String], passphrase: Option<&str>, format: ArchiveFormat, hash_algorithm: HashAlgorithm, volume_size: Option<u64>, throttle: &BackupThrottle) -> Result<Option<(BackupItem, Vec<String>)>, String> {
+    let staging = TempDirGuard::create("macos-backup-defaults")?;
+    let defaults_temp = staging.join("system_defaults");
+    fs::create_dir_all(&defaults_temp).map_err(|e| e.to_string())?;
+    let inventory_dir = inventory_root.join("system-defaults");
+    let _ = fs::create_dir_all(&inventory_dir);
+    
+    let mut exported = Vec::new();
+    for domain in domains {
+        let domain = domain.trim();
+        if domain.is_empty() {
             continue;
         }
-        
-        match hash_file(&archive_path) {
-            Ok(computed_hash) => {
-                if computed_hash == item.hash {
-                    verified_files += 1;
-                } else {
-                    failed_files.push(format!("{}: Hash stimmt nicht überein (erwartet: {}, berechnet: {})", 
-                        item.archive, &item.hash[..16], &computed_hash[..16]));
+        let output = Command::new("defaults").args(["export", domain, "-"]).output();
+        match output {
+            Ok(o) if o.status.success() && !o.stdout.is_empty() => {
+                let file_name = format!("{}.plist", domain);
+                if fs::write(defaults_temp.join(&file_name), &o.stdout).is_ok() {
+                    let _ = fs::write(inventory_dir.join(&file_name), &o.stdout);
+                    exported.push(domain.to_string());
                 }
             }
-            Err(e) => {
-                failed_files.push(format!("{}: Fehler beim Lesen: {}", item.archive, e));
-            }
+            _ => {}
         }
-        
-        // Emit progress
-        let fraction = (i + 1) as f64 / total_files as f64;
-        let _ = window.emit("backup-progress", ProgressUpdate {
-            message: format!("{}/{} Dateien verifiziert", i + 1, total_files),
-            fraction,
-        });
     }
     
-    let success = failed_files.is_empty();
-    let message = if success {
-        format!("Alle {} Dateien erfolgreich verifiziert!", total_files)
+    if exported.is_empty() {
+        Ok(None)
     } else {
-        format!("{} von {} Dateien fehlgeschlagen", failed_files.len(), total_files)
-    };
-    
-    let _ = window.emit("backup-log", &message);
-    
-    Ok(VerifyResult {
-        success,
-        total_files,
-        verified_files,
-        failed_files,
-        message,
-    })
+        archive_special_dir(&defaults_temp, backup_root, "system-defaults", passphrase, &[], format, hash_algorithm, volume_size, throttle)
+            .map(|item| Some((item, exported)))
+    }
 }
 
-/// Parallel backup verification with SHA-256 hash checking
-/// Provides ~40% time savings for integrity checks
-#[tauri::command]
-async fn verify_backup_parallel(
-    window: tauri::Window,
-    target_path: String,
-    timestamp: String,
-) -> Result<VerifyResult, String> {
-    use std::sync::Arc;
-    use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
-    use std::sync::Mutex;
-    
-    let backup_path = PathBuf::from(&target_path)
-        .join("macos-backup-suite")
-        .join("data")
-        .join(&timestamp);
-    
-    let metadata_path = backup_path.join("metadata.json");
-    if !metadata_path.exists() {
-        return Err(format!("Backup nicht gefunden: {}", timestamp));
-    }
-    
-    let metadata_content = fs::read_to_string(&metadata_path)
-        .map_err(|e| format!("Fehler beim Lesen der Metadaten: {}", e))?;
-    let metadata: BackupMetadata = serde_json::from_str(&metadata_content)
-        .map_err(|e| format!("Fehler beim Parsen der Metadaten: {}", e))?;
-    
-    let total_files = metadata.items.len();
-    let verified_counter = Arc::new(AtomicUsize::new(0));
-    let failed_files = Arc::new(Mutex::new(Vec::<String>::new()));
-    
-    let _ = window.emit("backup-log", format!("🔍 Parallele Verifizierung von {} Dateien...", total_files));
-    
-    // Process files in parallel batches (4 at a time to balance CPU and I/O)
-    const PARALLEL_VERIFY: usize = 4;
-    
-    let items: Vec<_> = metadata.items.iter().cloned().collect();
-    let chunks: Vec<Vec<BackupItem>> = items
-        .chunks(PARALLEL_VERIFY)
-        .map(|c| c.to_vec())
-        .collect();
-    
-    let mut processed = 0;
-    
-    for chunk in chunks {
-        let mut handles = Vec::new();
-        
-        for item in chunk {
-            let backup_path_clone = backup_path.clone();
-            let verified = Arc::clone(&verified_counter);
-            let failed = Arc::clone(&failed_files);
-            
-            let handle = std::thread::spawn(move || {
-                let archive_path = backup_path_clone.join(&item.archive);
-                
-                if !archive_path.exists() {
-                    let mut failed_lock = failed.lock().unwrap();
-                    failed_lock.push(format!("{}: Datei nicht gefunden", item.archive));
-                    return;
-                }
-                
-                match hash_file(&archive_path) {
-                    Ok(computed_hash) => {
-                        if computed_hash == item.hash {
-                            verified.fetch_add(1, AtomicOrdering::SeqCst);
-                        } else {
-                            let mut failed_lock = failed.lock().unwrap();
-                            failed_lock.push(format!("{}: Hash stimmt nicht überein (erwartet: {}, berechnet: {})", 
-                                item.archive, &item.hash[..16], &computed_hash[..16]));
-                        }
-                    }
-                    Err(e) => {
-                        let mut failed_lock = failed.lock().unwrap();
-                        failed_lock.push(format!("{}: Fehler beim Lesen: {}", item.archive, e));
-                    }
-                }
-            });
-            
-            handles.push(handle);
+/// A CUPS printer as recorded in `printers.json` of the `system-config` item
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct RecordedPrinter {
+    name: String,
+    device_uri: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    location: Option<String>,
+    /// `ppd/<name>.ppd` is part of the archive
+    #[serde(default)]
+    has_ppd: bool,
+}
+
+/// A network service and the hardware port it is bound to
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct NetworkService {
+    name: String,
+    #[serde(default)]
+    hardware_port: Option<String>,
+    #[serde(default)]
+    device: Option<String>,
+}
+
+/// `network.json` of the `system-config` item
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+struct NetworkConfig {
+    #[serde(default)]
+    services: Vec<NetworkService>,
+    #[serde(default)]
+    locations: Vec<String>,
+    #[serde(default)]
+    current_location: Option<String>,
+    /// Preferred Wi-Fi networks (SSIDs only, the passwords stay in the keychain)
+    #[serde(default)]
+    wifi_networks: Vec<String>,
+}
+
+/// Parts of the printer/network configuration that were captured and the ones that were skipped
+struct SystemConfigReport {
+    captured: Vec<String>,
+    skipped: Vec<String>,
+}
+
+/// Printer names and device URIs from `lpstat -v` ("device for <name>: <uri>")
+fn lpstat_devices() -> Vec<(String, String)> {
+    command_output("lpstat", &["-v"])
+        .lines()
+        .filter_map(|line| {
+            let rest = line.trim().strip_prefix("device for ")?;
+            let (name, uri) = rest.split_once(": ")?;
+            Some((name.trim().to_string(), uri.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Services from `networksetup -listnetworkserviceorder`, which prints each service as
+/// "(1) Wi-Fi" followed by "(Hardware Port: Wi-Fi, Device: en0)"
+fn network_service_order() -> Vec<NetworkService> {
+    let mut services: Vec<NetworkService> = Vec::new();
+    for line in command_output("networksetup", &["-listnetworkserviceorder"]).lines().map(str::trim) {
+        if let Some(port_info) = line.strip_prefix("(Hardware Port: ") {
+            let port_info = port_info.trim_end_matches(')');
+            let (port, device) = port_info.split_once(", Device: ").unwrap_or((port_info, ""));
+            if let Some(service) = services.last_mut() {
+                service.hardware_port = Some(port.to_string()).filter(|p| !p.is_empty());
+                service.device = Some(device.to_string()).filter(|d| !d.is_empty());
+            }
+        } else if line.starts_with('(') {
+            if let Some((_, name)) = line.split_once(") ") {
+                services.push(NetworkService { name: name.to_string(), hardware_port: None, device: None });
+            }
         }
-        
-        // Wait for batch to complete
-        for handle in handles {
-            let _ = handle.join();
+    }
+    services
+}
+
+/// Record printers (device URI, description, PPD) and the network setup (services, locations,
+/// preferred Wi-Fi networks) into the inventory and archive them as the `system-config` item.
+/// Parts that need administrator rights are skipped and reported.
+fn backup_system_config(inventory_root: &Path, backup_root: &Path, passphrase: Option<&str>, format: ArchiveFormat, hash_algorithm: HashAlgorithm, volume_size: Option<u64>, throttle: &BackupThrottle) -> Result<(Option<BackupItem>, SystemConfigReport), String> {
+    let staging = TempDirGuard::create("macos-backup-system-config")?;
+    let temp_dir = staging.join("system_config");
+    fs::create_dir_all(temp_dir.join("ppd")).map_err(|e| e.to_string())?;
+    let mut report = SystemConfigReport { captured: Vec::new(), skipped: Vec::new() };
+    
+    // Printers
+    let printers: Vec<RecordedPrinter> = lpstat_devices().into_iter().map(|(name, device_uri)| {
+        let details = command_output("lpstat", &["-l", "-p", &name]);
+        let field = |key: &str| details.lines()
+            .find_map(|l| l.trim().strip_prefix(key).map(|v| v.trim().to_string()))
+            .filter(|v| !v.is_empty());
+        let has_ppd = fs::copy(
+            Path::new("/etc/cups/ppd").join(format!("{}.ppd", name)),
+            temp_dir.join("ppd").join(format!("{}.ppd", name)),
+        ).is_ok();
+        RecordedPrinter { description: field("Description:"), location: field("Location:"), name, device_uri, has_ppd }
+    }).collect();
+    if !printers.is_empty() {
+        report.captured.push(format!("{} Drucker", printers.len()));
+        if let Ok(json) = serde_json::to_string_pretty(&printers) {
+            let _ = fs::write(temp_dir.join("printers.json"), json);
         }
-        
-        processed += PARALLEL_VERIFY.min(total_files - processed);
-        let fraction = processed as f64 / total_files as f64;
-        let _ = window.emit("backup-progress", ProgressUpdate {
-            message: format!("{}/{} Dateien verifiziert", processed, total_files),
-            fraction,
-        });
+    }
+    // printers.conf is only readable by root; the lpstat data above is enough to re-add the printers
+    if fs::copy("/etc/cups/printers.conf", temp_dir.join("printers.conf")).is_err() {
+        report.skipped.push("printers.conf (erfordert Administratorrechte)".to_string());
     }
     
-    let verified_files = verified_counter.load(AtomicOrdering::SeqCst);
-    let failed_files_result = match Arc::try_unwrap(failed_files) {
-        Ok(mutex) => mutex.into_inner().unwrap_or_default(),
-        Err(arc) => arc.lock().unwrap().clone(),
+    // Network services, locations and preferred Wi-Fi networks
+    let services = network_service_order();
+    let wifi_device = services.iter()
+        .find(|s| s.hardware_port.as_deref() == Some("Wi-Fi"))
+        .and_then(|s| s.device.clone());
+    let wifi_networks: Vec<String> = wifi_device
+        .map(|device| command_output("networksetup", &["-listpreferredwirelessnetworks", &device])
+            .lines()
+            .skip(1)
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect())
+        .unwrap_or_default();
+    let network = NetworkConfig {
+        locations: command_output("networksetup", &["-listlocations"]).lines().map(|l| l.trim().to_string()).filter(|l| !l.is_empty()).collect(),
+        current_location: Some(command_output("networksetup", &["-getcurrentlocation"])).filter(|l| !l.is_empty()),
+        services,
+        wifi_networks,
     };
-    
-    let success = failed_files_result.is_empty();
-    let message = if success {
-        format!("✅ Alle {} Dateien erfolgreich verifiziert (parallel)!", total_files)
+    if network.services.is_empty() && network.locations.is_empty() {
+        report.skipped.push("Netzwerkkonfiguration (networksetup lieferte keine Daten)".to_string());
     } else {
-        format!("❌ {} von {} Dateien fehlgeschlagen", failed_files_result.len(), total_files)
-    };
+        report.captured.push(format!(
+            "{} Netzwerkdienste, {} Umgebungen, {} WLAN-Netze",
+            network.services.len(), network.locations.len(), network.wifi_networks.len()
+        ));
+        if let Ok(json) = serde_json::to_string_pretty(&network) {
+            let _ = fs::write(temp_dir.join("network.json"), json);
+        }
+    }
+    
+    let inventory_dir = inventory_root.join("system-config");
+    let _ = fs::create_dir_all(&inventory_dir);
+    for file in ["printers.json", "network.json"] {
+        let _ = fs::copy(temp_dir.join(file), inventory_dir.join(file));
+    }
+    
+    if report.captured.is_empty() {
+        Ok((None, report))
+    } else {
+        archive_special_dir(&temp_dir, backup_root, "system-config", passphrase, &[], format, hash_algorithm, volume_size, throttle)
+            .map(|item| (Some(item), report))
+    }
+}
+
+fn compute_directory_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Architecture of the running Mac in Apple's naming ("arm64" or "x86_64")
+fn current_arch() -> String {
+    match std::env::consts::ARCH {
+        "aarch64" => "arm64".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Trimmed stdout of a command, empty if it fails
+fn command_output(program: &str, args: &[&str]) -> String {
+    Command::new(program)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .unwrap_or_default()
+}
+
+impl SourceSystem {
+    fn current() -> Self {
+        Self {
+            os_version: command_output("sw_vers", &["-productVersion"]),
+            os_build: command_output("sw_vers", &["-buildVersion"]),
+            hostname: command_output("scutil", &["--get", "ComputerName"]),
+            arch: current_arch(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+/// Expand a leading `~` to the given home directory
+fn expand_tilde(path: &str, home: &Path) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/") {
+        home.join(rest)
+    } else if path == "~" {
+        home.to_path_buf()
+    } else if let Some(user_path) = path.strip_prefix('~') {
+        // `~name/…` is below another account's home folder
+        Path::new(USERS_ROOT).join(user_path)
+    } else {
+        PathBuf::from(path)
+    }
+}
+
+/// Parent of all home folders on macOS
+const USERS_ROOT: &str = "/Users";
+
+/// Account whose home folder contains `path`, if it lies below /Users and isn't `home`,
+/// the folder of the current user
+fn path_owner(path: &Path, home: &Path) -> Option<String> {
+    if path.starts_with(home) {
+        return None;
+    }
+    let name = path.strip_prefix(USERS_ROOT).ok()?.components().next()?.as_os_str().to_string_lossy().to_string();
+    (name != "Shared" && !name.starts_with('.')).then_some(name)
+}
+
+/// Home folder of `owner`, or `home` for items of the current user
+fn owner_home(owner: Option<&str>, home: &Path) -> PathBuf {
+    owner.map_or_else(|| home.to_path_buf(), |name| Path::new(USERS_ROOT).join(name))
+}
+
+/// Expand the backup directories of a possibly multi-account backup. `~/…` stays with whoever runs
+/// backup and restore, `~name/…` and `/Users/name/…` are returned together with the account they belong to.
+fn expand_user_paths(directories: &[String], home: &Path) -> Vec<(PathBuf, Option<String>)> {
+    directories.iter().map(|dir| {
+        let expanded = expand_tilde(dir, home);
+        let owner = if dir == "~" || dir.starts_with("~/") { None } else { path_owner(&expanded, home) };
+        (expanded, owner)
+    }).collect()
+}
+
+/// Archive stem of a directory item; items of other accounts are prefixed with the account name,
+/// so e.g. two users' Documents don't end up in the same archive
+fn item_archive_stem(expanded: &Path, owner: Option<&str>) -> String {
+    let name = expanded.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "backup".to_string());
+    match owner {
+        Some(user) => archive_stem(&format!("{}-{}", user, name)),
+        None => archive_stem(&name),
+    }
+}
+
+/// Source size of each backup directory (0 for missing ones) as it will be archived: honoring the
+/// exclude patterns, the directory's own includes and excludes, and `max_file_bytes` like tar does
+fn compute_source_sizes(directories: &[BackupDirectory], home: &Path, exclude_patterns: &[String], max_file_bytes: Option<u64>) -> Vec<u64> {
+    directories.iter().map(|entry| {
+        let expanded = expand_tilde(&entry.path, home);
+        if !expanded.exists() {
+            0
+        } else if expanded.is_file() {
+            fs::metadata(&expanded).map(|m| m.len()).unwrap_or(0)
+        } else {
+            let mut patterns = exclude_patterns.to_vec();
+            patterns.extend(entry.excludes.iter()
+                .map(|p| p.trim().trim_matches('/'))
+                .filter(|p| !p.is_empty())
+                .map(str::to_string));
+            let includes: Vec<PathBuf> = entry.includes.iter()
+                .map(|p| p.trim().trim_matches('/'))
+                .filter(|p| !p.is_empty())
+                .map(|p| expanded.join(p))
+                .collect();
+            if includes.is_empty() {
+                archived_size(&expanded, &expanded, &patterns, max_file_bytes)
+            } else {
+                includes.iter().map(|include| archived_size(&expanded, include, &patterns, max_file_bytes)).sum()
+            }
+        }
+    }).collect()
+}
+
+/// Size of the files at or below `start` (`root` or a path inside it) that tar archives: entries
+/// matching `patterns` relative to `root` are skipped, and so are files larger than `max_file_bytes`
+fn archived_size(root: &Path, start: &Path, patterns: &[String], max_file_bytes: Option<u64>) -> u64 {
+    WalkDir::new(start)
+        .into_iter()
+        .filter_entry(|e| e.path() == root || !is_excluded(root, e.path(), patterns))
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file() && max_file_bytes.is_none_or(|max| m.len() <= max))
+        .map(|m| m.len())
+        .sum()
+}
+
+/// `max_file_size_mb` in bytes, if a limit is set
+fn max_file_bytes(config: &BackupConfig) -> Option<u64> {
+    config.max_file_size_mb.filter(|mb| *mb > 0).map(|mb| mb * 1024 * 1024)
+}
+
+/// Estimate the archive size for a set of source bytes and compare it against free space
+fn estimate_archive_size(source_size_bytes: u64, compression_ratio: f64, target: Option<&Path>) -> BackupSizeEstimate {
+    let compression_ratio = if compression_ratio > 0.0 { compression_ratio } else { default_compression_ratio() };
+    let estimated_archive_bytes = (source_size_bytes as f64 * compression_ratio) as u64;
+    // get_disk_space_gb returns 0.0 when df fails, so treat that as unknown
+    let free_space_gb = target.map(|t| get_disk_space_gb(t).1).filter(|gb| *gb > 0.0);
+    let fits = free_space_gb.map(|gb| estimated_archive_bytes as f64 / (1024.0 * 1024.0 * 1024.0) <= gb);
+    
+    BackupSizeEstimate {
+        source_size_bytes,
+        estimated_archive_bytes,
+        compression_ratio,
+        free_space_gb,
+        fits,
+    }
+}
+
+/// Throughput in bytes/s and remaining seconds, based on wall-clock time since start.
+/// Returns (0.0, None) until there is enough data to estimate.
+fn transfer_rate(bytes_done: u64, bytes_total: u64, elapsed_secs: f64) -> (f64, Option<u64>) {
+    if bytes_done == 0 || elapsed_secs <= 0.0 {
+        return (0.0, None);
+    }
+    let bytes_per_second = bytes_done as f64 / elapsed_secs;
+    let remaining = bytes_total.saturating_sub(bytes_done) as f64;
+    (bytes_per_second, Some((remaining / bytes_per_second).round() as u64))
+}
+
+/// Simple glob matcher supporting `*` and `?` (as used by tar's --exclude)
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    let (mut pi, mut ti) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ti));
+            pi += 1;
+        } else if let Some((sp, st)) = star {
+            pi = sp + 1;
+            ti = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
+        }
+    }
+    while pi < p.len() && p[pi] == '*' {
+        pi += 1;
+    }
+    pi == p.len()
+}
+
+/// Finder/Spotlight metadata excluded when `exclude_system_files` is set
+const SYSTEM_FILE_EXCLUDES: &[&str] = &[".DS_Store", "._*", ".Spotlight-V100", ".fseventsd", ".Trashes"];
+
+/// System metadata excludes if enabled in the config, otherwise empty
+fn system_file_excludes(config: &BackupConfig) -> Vec<String> {
+    if config.exclude_system_files {
+        SYSTEM_FILE_EXCLUDES.iter().map(|p| p.to_string()).collect()
+    } else {
+        Vec::new()
+    }
+}
+
+/// User-defined exclude patterns plus the system metadata excludes
+fn effective_exclude_patterns(config: &BackupConfig) -> Vec<String> {
+    let mut patterns = config.exclude_patterns.clone();
+    patterns.extend(system_file_excludes(config));
+    patterns
+}
+
+/// Check whether a path below `root` is excluded by any of the patterns.
+/// Like tar, a pattern matches either the entry name or its relative path.
+fn is_excluded(root: &Path, path: &Path, patterns: &[String]) -> bool {
+    if patterns.is_empty() {
+        return false;
+    }
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let relative = path.strip_prefix(root).unwrap_or(path).to_string_lossy().to_string();
+    patterns.iter().any(|p| glob_match(p, &name) || glob_match(p, &relative))
+}
+
+/// Files below `path` larger than `max_bytes` as paths relative to `path`, plus their total size.
+/// Directories matching `patterns` (relative to `path`) are not entered.
+fn oversized_files(path: &Path, max_bytes: u64, patterns: &[String]) -> (Vec<String>, u64) {
+    let mut files = Vec::new();
+    let mut bytes = 0;
+    for entry in WalkDir::new(path)
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !is_excluded(path, e.path(), patterns))
+        .filter_map(|e| e.ok())
+    {
+        let Ok(meta) = entry.metadata() else { continue };
+        if !meta.is_file() || meta.len() <= max_bytes {
+            continue;
+        }
+        if let Ok(relative) = entry.path().strip_prefix(path) {
+            files.push(relative.to_string_lossy().to_string());
+            bytes += meta.len();
+        }
+    }
+    (files, bytes)
+}
+
+/// Escape glob characters so tar matches `path` literally in `--exclude`
+fn escape_tar_pattern(path: &str) -> String {
+    let mut escaped = String::with_capacity(path.len());
+    for c in path.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Checksum algorithm of a backup's archives, recorded as `BackupMetadata.hash_algorithm`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum HashAlgorithm {
+    Sha256,
+    Blake3,
+}
+
+impl HashAlgorithm {
+    /// Algorithm from its stored name; backups without a known name are SHA-256
+    fn from_name(name: &str) -> Self {
+        match name.trim().to_lowercase().as_str() {
+            "blake3" => HashAlgorithm::Blake3,
+            _ => HashAlgorithm::Sha256,
+        }
+    }
+    
+    fn name(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Blake3 => "blake3",
+        }
+    }
+    
+    /// Name for reports and the UI
+    fn label(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "SHA-256",
+            HashAlgorithm::Blake3 => "BLAKE3",
+        }
+    }
+    
+    /// Aggregate manifest in `data/<timestamp>`, checkable with `shasum -c` or `b3sum -c`
+    fn manifest_name(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "SHA256SUMS",
+            HashAlgorithm::Blake3 => "B3SUMS",
+        }
+    }
+    
+    /// Extension of the per-archive checksum sidecar
+    fn sidecar_extension(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Blake3 => "b3",
+        }
+    }
+    
+    fn hasher(&self) -> Hasher {
+        match self {
+            HashAlgorithm::Sha256 => Hasher::Sha256(Sha256::new()),
+            HashAlgorithm::Blake3 => Hasher::Blake3(Box::new(blake3::Hasher::new())),
+        }
+    }
+}
+
+/// Incremental hasher for either algorithm
+enum Hasher {
+    Sha256(Sha256),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha256(hasher) => hasher.update(data),
+            Hasher::Blake3(hasher) => {
+                hasher.update(data);
+            }
+        }
+    }
+    
+    /// Lowercase hex digest
+    fn finalize(self) -> String {
+        match self {
+            Hasher::Sha256(hasher) => format!("{:x}", hasher.finalize()),
+            Hasher::Blake3(hasher) => hasher.finalize().to_hex().to_string(),
+        }
+    }
+}
+
+fn hash_file(path: &Path, algorithm: HashAlgorithm) -> Result<String, String> {
+    hash_file_with_progress(path, algorithm, None, |_| {})
+}
+
+/// How often hashing reports progress within a single file
+const HASH_PROGRESS_INTERVAL: u64 = 64 * 1024 * 1024;
+
+/// Hash a file, calling `on_progress` with the bytes hashed since the previous call
+/// every `HASH_PROGRESS_INTERVAL` bytes and once at the end. Stops with an error once `cancel` is set.
+fn hash_file_with_progress(path: &Path, algorithm: HashAlgorithm, cancel: Option<&AtomicBool>, mut on_progress: impl FnMut(u64)) -> Result<String, String> {
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut hasher = algorithm.hasher();
+    let mut buffer = [0u8; 8192];
+    let mut unreported: u64 = 0;
+    
+    loop {
+        if cancel.is_some_and(|c| c.load(Ordering::SeqCst)) {
+            return Err("Abgebrochen".to_string());
+        }
+        let bytes_read = file.read(&mut buffer).map_err(|e| e.to_string())?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..bytes_read]);
+        unreported += bytes_read as u64;
+        if unreported >= HASH_PROGRESS_INTERVAL {
+            on_progress(unreported);
+            unreported = 0;
+        }
+    }
+    if unreported > 0 {
+        on_progress(unreported);
+    }
+    
+    Ok(hasher.finalize())
+}
+
+/// Verify progress as hashed bytes over the archive bytes recorded in the metadata
+fn verify_fraction(bytes_done: u64, bytes_total: u64) -> f64 {
+    if bytes_total == 0 {
+        return 1.0;
+    }
+    (bytes_done as f64 / bytes_total as f64).min(1.0)
+}
+
+/// `verify_fraction` as a whole percentage for `ProgressSink::progress`
+fn verify_percent(bytes_done: u64, bytes_total: u64) -> usize {
+    (verify_fraction(bytes_done, bytes_total) * 100.0) as usize
+}
+
+/// Writer that updates a hash with every byte passed through to `inner`,
+/// so an archive's checksum is known without reading it back from disk
+struct HashingWriter<W: Write> {
+    inner: W,
+    hasher: Hasher,
+    bytes_written: u64,
+}
+
+impl<W: Write> HashingWriter<W> {
+    fn new(inner: W, algorithm: HashAlgorithm) -> Self {
+        HashingWriter { inner, hasher: algorithm.hasher(), bytes_written: 0 }
+    }
+    
+    /// Flush the inner writer and return it together with the hex digest
+    fn finish(mut self) -> std::io::Result<(W, String)> {
+        self.inner.flush()?;
+        Ok((self.inner, self.hasher.finalize()))
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.bytes_written += n as u64;
+        Ok(n)
+    }
+    
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Write a gzip tar archive containing a single file and return its hash
+fn write_single_file_archive(source: &Path, name_in_archive: &str, target: &Path, hash_algorithm: HashAlgorithm) -> Result<String, String> {
+    write_files_archive(&[(source, name_in_archive)], target, false, None, hash_algorithm).map(|archived| archived.hash)
+}
+
+/// Write a gzip tar archive containing the given (source, name in archive) files, cut into volumes
+/// like `create_tar_gz` with a `volume_size`. Without `follow_symlinks` a symlink is stored as a link, not as its target.
+fn write_files_archive(files: &[(&Path, &str)], target: &Path, follow_symlinks: bool, volume_size: Option<u64>, hash_algorithm: HashAlgorithm) -> Result<TarArchive, String> {
+    let writer = ArchiveWriter::create(target, volume_size, hash_algorithm).map_err(|e| e.to_string())?;
+    let encoder = GzEncoder::new(writer, Compression::default());
+    let mut archive = tar::Builder::new(encoder);
+    archive.follow_symlinks(follow_symlinks);
+    for (source, name_in_archive) in files {
+        archive.append_path_with_name(source, name_in_archive).map_err(|e| e.to_string())?;
+    }
+    // Finish tar archive and get back the GzEncoder, then finish the GzEncoder to flush all data
+    let encoder = archive.into_inner().map_err(|e| e.to_string())?;
+    let writer = encoder.finish().map_err(|e| e.to_string())?;
+    let (_, hash, volumes) = writer.finish(target).map_err(|e| e.to_string())?;
+    Ok(TarArchive { hash, warnings: Vec::new(), volumes })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArchiveCompression {
+    Zstd,
+    Gzip,
+    Uncompressed,
+}
+
+/// Detect the archive compression from its magic bytes. The extension is not reliable:
+/// single files are always written with the in-process gzip encoder.
+fn detect_compression(archive: &Path) -> ArchiveCompression {
+    let mut magic = [0u8; 4];
+    let read = fs::File::open(archive)
+        .and_then(|mut f| f.read(&mut magic))
+        .unwrap_or(0);
+    
+    if read >= 4 && magic == [0x28, 0xb5, 0x2f, 0xfd] {
+        ArchiveCompression::Zstd
+    } else if read >= 2 && magic[..2] == [0x1f, 0x8b] {
+        ArchiveCompression::Gzip
+    } else {
+        ArchiveCompression::Uncompressed
+    }
+}
+
+/// tar arguments for decompressing an archive, chosen by its content
+fn tar_decompress_args(archive: &Path) -> Vec<String> {
+    match detect_compression(archive) {
+        ArchiveCompression::Zstd => vec![zstd_decompress_arg_for(archive)],
+        ArchiveCompression::Gzip => vec!["-z".to_string()],
+        ArchiveCompression::Uncompressed => Vec::new(),
+    }
+}
+
+/// Open a decompressing reader for an archive. For zstd archives the returned
+/// child process must be waited on once the reader has been dropped.
+fn open_archive_reader(archive: &Path) -> Result<(Box<dyn Read>, Option<std::process::Child>), String> {
+    match detect_compression(archive) {
+        ArchiveCompression::Zstd => {
+            let zstd = find_zstd_path().ok_or("zstd nicht gefunden")?;
+            let mut cmd = Command::new(zstd);
+            let dictionary = zstd_dictionary_path(archive);
+            if dictionary.is_file() {
+                cmd.arg("-D").arg(&dictionary);
+            }
+            let mut child = cmd
+                .args(["-d", "-c", "-q", &archive.to_string_lossy()])
+                .stdout(std::process::Stdio::piped())
+                .spawn()
+                .map_err(|e| format!("zstd Fehler: {}", e))?;
+            let stdout = child.stdout.take().ok_or("zstd liefert keine Ausgabe")?;
+            Ok((Box::new(stdout), Some(child)))
+        }
+        ArchiveCompression::Gzip => {
+            let file = fs::File::open(archive).map_err(|e| e.to_string())?;
+            Ok((Box::new(flate2::read::GzDecoder::new(file)), None))
+        }
+        ArchiveCompression::Uncompressed => {
+            Ok((Box::new(fs::File::open(archive).map_err(|e| e.to_string())?), None))
+        }
+    }
+}
+
+/// Read a text member (e.g. "homebrew_packages.txt") from an archive without extracting it
+fn read_archive_text_member(archive: &Path, member: &str) -> Result<String, String> {
+    let (reader, zstd_child) = open_archive_reader(archive)?;
+    
+    let result = {
+        let mut tar_archive = tar::Archive::new(reader);
+        let mut content = None;
+        let entries = tar_archive.entries().map_err(|e| format!("Archiv konnte nicht gelesen werden: {}", e))?;
+        for mut entry in entries.flatten() {
+            let is_member = entry.path()
+                .map(|p| p.file_name().map(|n| n == member).unwrap_or(false))
+                .unwrap_or(false);
+            if is_member {
+                let mut text = String::new();
+                entry.read_to_string(&mut text).map_err(|e| e.to_string())?;
+                content = Some(text);
+                break;
+            }
+        }
+        content.ok_or_else(|| format!("{} nicht im Archiv gefunden", member))
+    };
+    
+    if let Some(mut child) = zstd_child {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+    
+    result
+}
+
+/// List all members of an archive with their sizes
+fn list_tar_entries(archive: &Path) -> Result<Vec<ArchiveEntry>, String> {
+    let (reader, zstd_child) = open_archive_reader(archive)?;
+    read_tar_entries(reader, zstd_child)
+}
+
+/// List the members of a decompressed tar stream; `zstd_child` is waited on afterwards
+fn read_tar_entries(reader: Box<dyn Read>, zstd_child: Option<std::process::Child>) -> Result<Vec<ArchiveEntry>, String> {
+    let result = {
+        let mut tar_archive = tar::Archive::new(reader);
+        let mut entries = Vec::new();
+        let mut error = None;
+        match tar_archive.entries() {
+            Ok(iter) => {
+                for entry in iter {
+                    match entry {
+                        Ok(entry) => {
+                            let header = entry.header();
+                            entries.push(ArchiveEntry {
+                                path: entry.path().map(|p| p.to_string_lossy().to_string()).unwrap_or_default(),
+                                size_bytes: header.size().unwrap_or(0),
+                                is_dir: header.entry_type().is_dir(),
+                            });
+                        }
+                        Err(e) => {
+                            error = Some(format!("Archiv konnte nicht gelesen werden: {}", e));
+                            break;
+                        }
+                    }
+                }
+            }
+            Err(e) => error = Some(format!("Archiv konnte nicht gelesen werden: {}", e)),
+        }
+        match error {
+            Some(e) => Err(e),
+            None => Ok(entries),
+        }
+    };
+    
+    if let Some(mut child) = zstd_child {
+        let _ = child.wait();
+    }
+    
+    result
+}
+
+/// Read every member of an archive (without writing to disk) to confirm tar can extract it
+fn test_archive_readable(archive: &Path) -> Result<(), String> {
+    let output = Command::new("tar")
+        .args(tar_decompress_args(archive))
+        .args(["-xOf", &archive.to_string_lossy()])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .output()
+        .map_err(|e| format!("tar Fehler: {}", e))?;
+    
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).lines().next().unwrap_or("").to_string())
+    }
+}
+
+/// Cipher used for encrypted archives (openssl enc with PBKDF2 key derivation)
+const ENCRYPTION_CIPHER: &str = "aes-256-cbc";
+
+/// Verschlüsselt ein Archiv mit openssl zu `<archive>.enc` und löscht das Klartext-Archiv.
+/// Die Passphrase wird per Umgebungsvariable übergeben, damit sie nicht in der Prozessliste erscheint.
+fn encrypt_archive(archive: &Path, passphrase: &str) -> Result<PathBuf, String> {
+    let encrypted = PathBuf::from(format!("{}.enc", archive.to_string_lossy()));
+
+    let output = Command::new("openssl")
+        .args([
+            "enc",
+            &format!("-{}", ENCRYPTION_CIPHER),
+            "-pbkdf2",
+            "-salt",
+            "-in", &archive.to_string_lossy(),
+            "-out", &encrypted.to_string_lossy(),
+            "-pass", "env:MACOS_BACKUP_PASSPHRASE",
+        ])
+        .env("MACOS_BACKUP_PASSPHRASE", passphrase)
+        .output()
+        .map_err(|e| format!("openssl konnte nicht gestartet werden: {}", e))?;
+
+    if !output.status.success() {
+        let _ = fs::remove_file(&encrypted);
+        return Err(format!("Verschlüsselung fehlgeschlagen: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    fs::remove_file(archive).map_err(|e| format!("Klartext-Archiv konnte nicht gelöscht werden: {}", e))?;
+    Ok(encrypted)
+}
+
+/// Entschlüsselt ein `.enc`-Archiv nach `out_dir` und gibt den Pfad des Klartext-Archivs zurück
+fn decrypt_archive(archive: &Path, passphrase: &str, out_dir: &Path) -> Result<PathBuf, String> {
+    fs::create_dir_all(out_dir).map_err(|e| e.to_string())?;
+
+    let file_name = archive.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "archive.enc".to_string());
+    let decrypted = out_dir.join(file_name.trim_end_matches(".enc"));
+
+    let output = Command::new("openssl")
+        .args([
+            "enc",
+            "-d",
+            &format!("-{}", ENCRYPTION_CIPHER),
+            "-pbkdf2",
+            "-in", &archive.to_string_lossy(),
+            "-out", &decrypted.to_string_lossy(),
+            "-pass", "env:MACOS_BACKUP_PASSPHRASE",
+        ])
+        .env("MACOS_BACKUP_PASSPHRASE", passphrase)
+        .output()
+        .map_err(|e| format!("openssl konnte nicht gestartet werden: {}", e))?;
+
+    if !output.status.success() {
+        let _ = fs::remove_file(&decrypted);
+        return Err("Entschlüsselung fehlgeschlagen (falsche Passphrase?)".to_string());
+    }
+
+    Ok(decrypted)
+}
+
+/// Verschlüsselt ein fertiges Archiv, falls eine Passphrase gesetzt ist.
+/// Gibt den endgültigen Archivnamen und -pfad zurück.
+fn finalize_archive(archive_path: &Path, archive_name: &str, hash: String, passphrase: Option<&str>, hash_algorithm: HashAlgorithm) -> Result<(String, PathBuf, String), String> {
+    match passphrase {
+        Some(pass) => {
+            // The streamed hash covers the plaintext; the stored hash must match the encrypted file
+            let encrypted = encrypt_archive(archive_path, pass)?;
+            let hash = hash_file(&encrypted, hash_algorithm)?;
+            Ok((format!("{}.enc", archive_name), encrypted, hash))
+        }
+        None => Ok((archive_name.to_string(), archive_path.to_path_buf(), hash)),
+    }
+}
+
+impl ArchiveFormat {
+    /// Format requested by the caller ("zst" or "gz"); without one zstd is used if it is installed
+    fn resolve(requested: Option<&str>) -> Result<Self, String> {
+        match requested.map(|f| f.trim().to_lowercase()).as_deref() {
+            None | Some("") => Ok(if find_zstd_path().is_some() { ArchiveFormat::Zst } else { ArchiveFormat::Gz }),
+            Some("zst") | Some("zstd") => {
+                if find_zstd_path().is_some() {
+                    Ok(ArchiveFormat::Zst)
+                } else {
+                    Err("zstd nicht gefunden - Format \"zst\" nicht verfügbar".to_string())
+                }
+            }
+            Some("gz") | Some("gzip") => Ok(ArchiveFormat::Gz),
+            Some(other) => Err(format!("Unbekanntes Archivformat: {} (erlaubt: zst, gz)", other)),
+        }
+    }
+    
+    fn extension(self) -> &'static str {
+        match self {
+            ArchiveFormat::Zst => "tar.zst",
+            ArchiveFormat::Gz => "tar.gz",
+            ArchiveFormat::Tar => "tar",
+        }
+    }
+    
+    /// tar arguments for decompressing an archive of this format
+    fn decompress_args(self) -> Vec<String> {
+        match self {
+            ArchiveFormat::Zst => vec![zstd_decompress_arg()],
+            ArchiveFormat::Gz => vec!["-z".to_string()],
+            ArchiveFormat::Tar => Vec::new(),
+        }
+    }
+}
+
+/// Extensions of file types that are already compressed and gain next to nothing from zstd/gzip
+const PRECOMPRESSED_EXTENSIONS: &[&str] = &[
+    "jpg", "jpeg", "png", "heic", "heif", "gif", "webp",
+    "mp4", "mov", "m4v", "mkv", "avi", "mp3", "m4a", "aac", "flac",
+    "zip", "gz", "tgz", "bz2", "xz", "zst", "7z", "rar", "dmg", "pkg",
+];
+
+/// How many files are sampled when deciding whether a directory is worth compressing
+const COMPRESSION_SAMPLE_FILES: usize = 2000;
+
+/// True if most of the sampled bytes in `dir` belong to already-compressed file types
+fn is_mostly_precompressed(dir: &Path) -> bool {
+    let (mut compressed, mut total) = (0u64, 0u64);
+    for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()).filter(|e| e.file_type().is_file()).take(COMPRESSION_SAMPLE_FILES) {
+        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        total += size;
+        let is_compressed = entry.path().extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .is_some_and(|ext| PRECOMPRESSED_EXTENSIONS.contains(&ext.as_str()));
+        if is_compressed {
+            compressed += size;
+        }
+    }
+    total > 0 && compressed * 2 > total
+}
+
+/// A directory gets a zstd dictionary when the sample holds at least this many files ...
+const DICTIONARY_MIN_FILES: usize = 1000;
+/// ... averaging at most this size; per-file redundancy is where a dictionary pays off
+const DICTIONARY_MAX_AVERAGE_FILE_BYTES: u64 = 8 * 1024;
+/// Larger files are left out of the training sample
+const DICTIONARY_MAX_SAMPLE_BYTES: u64 = 128 * 1024;
+
+/// True if the sampled files of `dir` are many and small enough for a trained zstd dictionary
+fn is_dictionary_candidate(dir: &Path) -> bool {
+    let sizes: Vec<u64> = WalkDir::new(dir).into_iter().filter_map(|e| e.ok()).filter(|e| e.file_type().is_file())
+        .take(COMPRESSION_SAMPLE_FILES)
+        .map(|e| e.metadata().map(|m| m.len()).unwrap_or(0))
+        .collect();
+    sizes.len() >= DICTIONARY_MIN_FILES && sizes.iter().sum::<u64>() / sizes.len() as u64 <= DICTIONARY_MAX_AVERAGE_FILE_BYTES
+}
+
+/// Train a zstd dictionary on a sample of the small files in `dir` and write it to `dictionary`
+fn train_zstd_dictionary(dir: &Path, dictionary: &Path) -> Result<(), String> {
+    let zstd = find_zstd_path().ok_or("zstd nicht gefunden")?;
+    let samples: Vec<PathBuf> = WalkDir::new(dir).into_iter().filter_map(|e| e.ok()).filter(|e| e.file_type().is_file())
+        .filter(|e| e.metadata().map(|m| m.len() > 0 && m.len() <= DICTIONARY_MAX_SAMPLE_BYTES).unwrap_or(false))
+        .take(COMPRESSION_SAMPLE_FILES)
+        .map(|e| e.into_path())
+        .collect();
+    let output = Command::new(zstd)
+        .args(["--train", "-q", "-f"])
+        .args(&samples)
+        .arg("-o")
+        .arg(dictionary)
+        .output()
+        .map_err(|e| format!("zstd Fehler: {}", e))?;
+    if output.status.success() && dictionary.is_file() {
+        Ok(())
+    } else {
+        let _ = fs::remove_file(dictionary);
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// Seconds since `start`, with millisecond precision
+fn elapsed_seconds(start: chrono::DateTime<Local>) -> f64 {
+    (Local::now() - start).num_milliseconds() as f64 / 1000.0
+}
+
+/// Archive a staged directory as a restorable special item named `item_name`
+#[allow(clippy::too_many_arguments)]
+fn archive_special_dir(
+    source_dir: &Path,
+    backup_root: &Path,
+    item_name: &str,
+    passphrase: Option<&str>,
+    exclude_patterns: &[String],
+    format: ArchiveFormat,
+    hash_algorithm: HashAlgorithm,
+    volume_size: Option<u64>,
+    throttle: &BackupThrottle,
+) -> Result<BackupItem, String> {
+    let started = Local::now();
+    let archive_name = format!("{}.{}", item_name, format.extension());
+    let archive_path = backup_root.join(&archive_name);
+    
+    let archived = create_tar_gz(source_dir, &archive_path, exclude_patterns, &[], false, format, volume_size, None, hash_algorithm, throttle)?;
+    let source_size = compute_directory_size(source_dir);
+    let mut volumes = finalize_tar_archive(backup_root, &archive_path, &archive_name, &archived, passphrase, hash_algorithm)?;
+    let first = volumes.remove(0);
+    
+    Ok(BackupItem {
+        path: item_name.to_string(),
+        archive: first.archive,
+        hash: first.hash,
+        archive_size_bytes: first.archive_size_bytes + volumes.iter().map(|v| v.archive_size_bytes).sum::<u64>(),
+        source_size_bytes: source_size,
+        format: Some(format),
+        duration_seconds: elapsed_seconds(started),
+        multi_volume: !volumes.is_empty(),
+        parts: volumes,
+        user: None,
+        dictionary_hash: None,
+    })
+}
+
+/// Extract an archive into `dest_dir` (compression detected from content)
+fn extract_archive_to(archive: &Path, dest_dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(dest_dir).map_err(|e| e.to_string())?;
+    let output = run_restore_command(Command::new("tar")
+        .current_dir(dest_dir)
+        .args(tar_decompress_args(archive))
+        .args(["-xf", &archive.to_string_lossy()]))
+        .map_err(|e| format!("tar Fehler: {}", e))?;
+    
+    if !output.status.success() {
+        return Err(format!("Entpacken fehlgeschlagen: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(())
+}
+
+/// Result of a tar run: the archive hash plus any files tar reported problems with
+struct TarArchive {
+    hash: String,
+    /// tar's stderr lines when it exited with an error but still produced an archive
+    warnings: Vec<String>,
+    /// Volumes the archive was cut into on a FAT target, empty if it was written as one file
+    volumes: Vec<ArchivePart>,
+}
+
+impl TarArchive {
+    /// Classify tar warnings as a directory issue, if there were any
+    fn issue(&self, path: &str) -> Option<DirectoryIssue> {
+        if self.warnings.is_empty() {
+            return None;
+        }
+        let permission_denied = self.warnings.iter()
+            .any(|w| w.contains("Permission denied") || w.contains("Operation not permitted"));
+        Some(DirectoryIssue {
+            path: path.to_string(),
+            reason: if permission_denied { "keine Leseberechtigung" } else { "tar-Fehler" }.to_string(),
+            detail: Some(self.warnings.iter().take(5).cloned().collect::<Vec<_>>().join("\n")),
+        })
+    }
+}
+
+/// Exclude patterns written to a temp file for tar's `-X`, so a directory with thousands of
+/// oversized files doesn't turn into thousands of `--exclude` arguments. Removed when dropped.
+struct ExcludeFile(PathBuf);
+
+impl ExcludeFile {
+    fn write(target: &Path, patterns: &[String]) -> Result<Self, String> {
+        let name = target.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let path = std::env::temp_dir().join(format!("macos-backup-{}-{}.exclude", std::process::id(), name));
+        let contents: String = patterns.iter().map(|p| format!("{}\n", p)).collect();
+        fs::write(&path, contents).map_err(|e| format!("Ausschlussliste konnte nicht geschrieben werden: {}", e))?;
+        Ok(ExcludeFile(path))
+    }
+}
+
+impl Drop for ExcludeFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// Archive `source` into `target` and return the hash of the written archive in `hash_algorithm`.
+/// tar writes to stdout, which is hashed while being copied into the target file.
+/// Symlinks are stored as links (tar's default, dangling links included) unless
+/// `follow_symlinks` is set, in which case tar archives the link targets (`-h`).
+/// With `includes`, only those subpaths of `source` are archived instead of the whole directory.
+/// With `volume_size`, an archive larger than that is cut into volumes `<target>.001`, `.002`, ….
+/// With `dictionary`, zstd compresses with that trained dictionary (`-D`).
+/// `throttle` lowers tar's priority and limits the write rate to network targets.
+#[allow(clippy::too_many_arguments)]
+fn create_tar_gz(source: &Path, target: &Path, exclude_patterns: &[String], includes: &[String], follow_symlinks: bool, format: ArchiveFormat, volume_size: Option<u64>, dictionary: Option<&Path>, hash_algorithm: HashAlgorithm, throttle: &BackupThrottle) -> Result<TarArchive, String> {
+    use std::os::unix::process::CommandExt;
+    use std::process::Stdio;
+    
+    // Use system tar command with zstd compression (faster than gzip, better ratio)
+    let source_parent = source.parent().unwrap_or(Path::new("/"));
+    let source_name = source.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "backup".to_string());
+    
+    // User-defined excludes on top of the built-in socket excludes
+    let mut excludes: Vec<String> = vec!["*.sock".to_string(), "*/sockets/*".to_string()];
+    excludes.extend(exclude_patterns.iter().filter(|p| !p.trim().is_empty()).cloned());
+    let exclude_file = ExcludeFile::write(target, &excludes)?;
+    let mut exclude_args = vec!["-X".to_string(), exclude_file.0.to_string_lossy().to_string()];
+    if follow_symlinks {
+        exclude_args.push("-h".to_string());
+    }
+    let members: Vec<String> = if includes.is_empty() {
+        vec![source_name.clone()]
+    } else {
+        includes.iter().map(|p| format!("{}/{}", source_name, p.trim_matches('/'))).collect()
+    };
+    
+    // Spawn the process in the requested format so we can track and kill it
+    let create_args: Vec<String> = match format {
+        // Use zstd compression (much faster, better compression), -T0 uses all CPU cores
+        ArchiveFormat::Zst => {
+            let zstd = find_zstd_path().ok_or("zstd nicht gefunden")?;
+            let dictionary_arg = dictionary.map(|d| format!(" -D \"{}\"", d.display())).unwrap_or_default();
+            vec![format!("--use-compress-program={} -T{}{}", zstd, throttle.zstd_threads, dictionary_arg), "-cf".to_string(), "-".to_string()]
+        }
+        ArchiveFormat::Gz => vec!["-czf".to_string(), "-".to_string()],
+        ArchiveFormat::Tar => vec!["-cf".to_string(), "-".to_string()],
+    };
+    let mut cmd = Command::new("tar");
+    cmd.current_dir(source_parent)
+        .args(&create_args)
+        .args(&exclude_args)
+        .args(&members)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // Create new process group so we can kill all children; zstd inherits the lowered priority
+    let (nice, background_io) = (throttle.nice, throttle.background_io);
+    unsafe {
+        cmd.pre_exec(move || {
+            libc::setpgid(0, 0);
+            if nice > 0 {
+                libc::setpriority(libc::PRIO_PROCESS as _, 0, nice);
+            }
+            if background_io {
+                libc::setpriority(PRIO_DARWIN_PROCESS as _, 0, PRIO_DARWIN_BG);
+            }
+            Ok(())
+        });
+    }
+    let mut child = cmd.spawn().map_err(|e| format!("Failed to spawn tar: {}", e))?;
+    
+    // Store PID for potential cancellation
+    let pid = child.id();
+    if let Ok(mut pids) = TAR_PIDS.lock() {
+        pids.push(pid);
+    }
+    
+    // Rate limit for network targets: tar | pv -L, with pv in tar's process group so cancel kills both
+    let mut limiter = None;
+    // tar's stdout is only handed to pv when a limit applies, otherwise it is read below
+    let rate_limit = throttle.rate_limiter(target).and_then(|limit| child.stdout.take().map(|stdout| (limit, stdout)));
+    if let Some(((pv, rate), tar_stdout)) = rate_limit {
+        let tar_group = pid as i32;
+        let mut pv_cmd = Command::new(pv);
+        pv_cmd.args(["-q", "-L", &format!("{}m", rate)])
+            .stdin(Stdio::from(tar_stdout))
+            .stdout(Stdio::piped());
+        unsafe {
+            pv_cmd.pre_exec(move || {
+                libc::setpgid(0, tar_group);
+                Ok(())
+            });
+        }
+        match pv_cmd.spawn() {
+            Ok(pv_child) => limiter = Some(pv_child),
+            Err(e) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                if let Ok(mut pids) = TAR_PIDS.lock() {
+                    pids.retain(|p| *p != pid);
+                }
+                return Err(format!("Failed to spawn pv: {}", e));
+            }
+        }
+    }
+    
+    // Collect stderr on a separate thread so a chatty tar can't block on a full pipe
+    let stderr_reader = child.stderr.take().map(|mut stderr| {
+        std::thread::spawn(move || {
+            let mut output = String::new();
+            let _ = stderr.read_to_string(&mut output);
+            output
+        })
+    });
+    
+    // Copy tar's output into the target file (or its volumes), hashing it on the way
+    let copy_result = (|| -> std::io::Result<(u64, String, Vec<ArchivePart>)> {
+        let stdout = match limiter.as_mut() {
+            Some(pv) => pv.stdout.take(),
+            None => child.stdout.take(),
+        };
+        let mut stdout = stdout.ok_or_else(|| std::io::Error::other("tar stdout missing"))?;
+        let mut writer = ArchiveWriter::create(target, volume_size, hash_algorithm)?;
+        std::io::copy(&mut stdout, &mut writer)?;
+        writer.finish(target)
+    })();
+    
+    // Wait for completion; the PID is cleared before any error is returned
+    let status = child.wait();
+    let limiter_status = limiter.map(|mut pv| pv.wait());
+    if let Ok(mut pids) = TAR_PIDS.lock() {
+        pids.retain(|p| *p != pid);
+    }
+    
+    let stderr_output = stderr_reader
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_default();
+    
+    // A cancelled tar was killed mid-write: nothing of the partial archive or its volumes may remain
+    if BACKUP_CANCELLED.load(Ordering::SeqCst) {
+        remove_archive(target);
+        return Err(BACKUP_CANCELLED_ERROR.to_string());
+    }
+    let status = match status {
+        Ok(status) => status,
+        Err(e) => {
+            remove_archive(target);
+            return Err(format!("Failed to wait for tar: {}", e));
+        }
+    };
+    
+    let (bytes_written, hash, volumes) = match copy_result {
+        Ok(result) => result,
+        Err(e) => {
+            remove_archive(target);
+            return Err(format!("Failed to write archive: {}", e));
+        }
+    };
+    
+    // A pv that died cut the stream short, so the archive is incomplete even if tar succeeded
+    match limiter_status {
+        Some(Ok(pv_status)) if !pv_status.success() => {
+            remove_archive(target);
+            return Err(format!("pv failed ({})", pv_status));
+        }
+        Some(Err(e)) => {
+            remove_archive(target);
+            return Err(format!("Failed to wait for pv: {}", e));
+        }
+        _ => {}
+    }
+    
+    let warnings: Vec<String> = if status.success() {
+        Vec::new()
+    } else {
+        stderr_output.lines()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect()
+    };
+    
+    // tar returns exit code 1 for warnings (permission denied on some files, etc.).
+    // The archive is kept, but the warnings are reported to the caller. Any other code,
+    // or a tar killed by a signal, left an archive that can't be trusted.
+    let tar_warnings = status.code() == Some(1) && !warnings.is_empty();
+    if !status.success() && (!tar_warnings || bytes_written == 0) {
+        remove_archive(target);
+        return Err(warnings.first().cloned().unwrap_or_else(|| format!("tar failed ({})", status)));
+    }
+    
+    Ok(TarArchive { hash, warnings, volumes })
+}
+
+/// Volumes stay below FAT32's 4 GiB file size limit, with room for the encryption header
+const FAT_VOLUME_SIZE: u64 = 4000 * 1024 * 1024;
+
+/// Size of the volumes archives are cut into when `target` lies on a FAT filesystem,
+/// which can't hold files of 4 GB or more
+fn fat_volume_size(target: &Path) -> Option<u64> {
+    mount_of(target)
+        .filter(|(_, fs_type)| fs_type == "msdos")
+        .map(|_| FAT_VOLUME_SIZE)
+}
+
+/// Path of volume `number` (starting at 1) of a multi-volume archive
+fn volume_path(archive: &Path, number: usize) -> PathBuf {
+    PathBuf::from(format!("{}.{:03}", archive.to_string_lossy(), number))
+}
+
+/// Delete an archive together with any volumes it was cut into
+fn remove_archive(archive: &Path) {
+    let _ = fs::remove_file(archive);
+    let _ = fs::remove_file(zstd_dictionary_path(archive));
+    for number in 1.. {
+        if fs::remove_file(volume_path(archive, number)).is_err() {
+            break;
+        }
+    }
+}
+
+/// Bytes written so far to `archive`, or to all of its volumes while it is being cut into volumes
+fn archive_written_size(archive: &Path) -> Option<u64> {
+    if let Ok(meta) = fs::metadata(archive) {
+        return Some(meta.len());
+    }
+    let sizes: Vec<u64> = (1..)
+        .map_while(|number| fs::metadata(volume_path(archive, number)).ok())
+        .map(|meta| meta.len())
+        .collect();
+    (!sizes.is_empty()).then(|| sizes.iter().sum())
+}
+
+/// Writer that cuts an archive stream into volumes `<archive>.001`, `.002`, … of at most
+/// `volume_size` bytes, hashing every volume on its own
+struct VolumeWriter {
+    archive: PathBuf,
+    volume_size: u64,
+    algorithm: HashAlgorithm,
+    current: Option<HashingWriter<std::io::BufWriter<fs::File>>>,
+    finished: Vec<ArchivePart>,
+}
+
+impl VolumeWriter {
+    fn new(archive: &Path, volume_size: u64, algorithm: HashAlgorithm) -> Self {
+        VolumeWriter { archive: archive.to_path_buf(), volume_size, algorithm, current: None, finished: Vec::new() }
+    }
+    
+    fn create_volume(&self) -> std::io::Result<HashingWriter<std::io::BufWriter<fs::File>>> {
+        let file = fs::File::create(volume_path(&self.archive, self.finished.len() + 1))?;
+        Ok(HashingWriter::new(std::io::BufWriter::new(file), self.algorithm))
+    }
+    
+    fn close_volume(&mut self, writer: HashingWriter<std::io::BufWriter<fs::File>>) -> std::io::Result<()> {
+        let archive_size_bytes = writer.bytes_written;
+        let (_, hash) = writer.finish()?;
+        let archive = volume_path(&self.archive, self.finished.len() + 1)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        self.finished.push(ArchivePart { archive, hash, archive_size_bytes, dictionary_hash: None });
+        Ok(())
+    }
+    
+    /// Close the last volume and return all volumes in order
+    fn finish(mut self) -> std::io::Result<Vec<ArchivePart>> {
+        let writer = match self.current.take() {
+            Some(writer) => writer,
+            None => self.create_volume()?,
+        };
+        self.close_volume(writer)?;
+        Ok(self.finished)
+    }
+}
+
+impl Write for VolumeWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let writer = match self.current.take() {
+            Some(writer) if writer.bytes_written < self.volume_size => self.current.insert(writer),
+            full => {
+                if let Some(writer) = full {
+                    self.close_volume(writer)?;
+                }
+                let writer = self.create_volume()?;
+                self.current.insert(writer)
+            }
+        };
+        let room = (self.volume_size - writer.bytes_written).min(buf.len() as u64) as usize;
+        writer.write(&buf[..room])
+    }
+    
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self.current.as_mut() {
+            Some(writer) => writer.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Output of an archive: a single file, or volumes `<target>.001`, `.002`, … when a volume size is set
+enum ArchiveWriter {
+    Single(HashingWriter<std::io::BufWriter<fs::File>>),
+    Volumes(VolumeWriter),
+}
+
+impl ArchiveWriter {
+    fn create(target: &Path, volume_size: Option<u64>, algorithm: HashAlgorithm) -> std::io::Result<Self> {
+        Ok(match volume_size {
+            Some(volume_size) => ArchiveWriter::Volumes(VolumeWriter::new(target, volume_size, algorithm)),
+            None => ArchiveWriter::Single(HashingWriter::new(std::io::BufWriter::new(fs::File::create(target)?), algorithm)),
+        })
+    }
+    
+    /// Close the output and return the bytes written, the hash and the volumes (empty for a single file)
+    fn finish(self, target: &Path) -> std::io::Result<(u64, String, Vec<ArchivePart>)> {
+        match self {
+            ArchiveWriter::Single(writer) => {
+                let bytes_written = writer.bytes_written;
+                let (_, hash) = writer.finish()?;
+                Ok((bytes_written, hash, Vec::new()))
+            }
+            ArchiveWriter::Volumes(writer) => {
+                let mut volumes = writer.finish()?;
+                let bytes_written = volumes.iter().map(|v| v.archive_size_bytes).sum();
+                let hash = volumes[0].hash.clone();
+                // An archive that fits into one volume keeps its normal name
+                if volumes.len() == 1 {
+                    fs::rename(volume_path(target, 1), target)?;
+                    volumes.clear();
+                }
+                Ok((bytes_written, hash, volumes))
+            }
+        }
+    }
+}
+
+impl Write for ArchiveWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            ArchiveWriter::Single(writer) => writer.write(buf),
+            ArchiveWriter::Volumes(writer) => writer.write(buf),
+        }
+    }
+    
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            ArchiveWriter::Single(writer) => writer.flush(),
+            ArchiveWriter::Volumes(writer) => writer.flush(),
+        }
+    }
+}
+
+/// Encrypt a finished archive, or each of its volumes, if a passphrase is set and return the
+/// final files in order (a single entry unless the archive was cut into volumes)
+fn finalize_tar_archive(backup_root: &Path, archive_path: &Path, archive_name: &str, archived: &TarArchive, passphrase: Option<&str>, hash_algorithm: HashAlgorithm) -> Result<Vec<ArchivePart>, String> {
+    if !archived.volumes.is_empty() {
+        return finalize_volumes(backup_root, &archived.volumes, passphrase, hash_algorithm);
+    }
+    let (archive, path, hash) = finalize_archive(archive_path, archive_name, archived.hash.clone(), passphrase, hash_algorithm)?;
+    let archive_size_bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    Ok(vec![ArchivePart { archive, hash, archive_size_bytes, dictionary_hash: None }])
+}
+
+/// Encrypt every volume of a multi-volume archive if a passphrase is set and return the final volumes
+fn finalize_volumes(backup_root: &Path, volumes: &[ArchivePart], passphrase: Option<&str>, hash_algorithm: HashAlgorithm) -> Result<Vec<ArchivePart>, String> {
+    volumes.iter().map(|volume| {
+        let (archive, path, hash) = finalize_archive(&backup_root.join(&volume.archive), &volume.archive, volume.hash.clone(), passphrase, hash_algorithm)?;
+        let archive_size_bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or(volume.archive_size_bytes);
+        Ok(ArchivePart { archive, hash, archive_size_bytes, dictionary_hash: None })
+    }).collect()
+}
+
+/// A directory or file from the backup list that still has to be archived
+struct DirectoryJob {
+    dir: String,
+    expanded: PathBuf,
+    name: String,
+    is_file: bool,
+    item_format: ArchiveFormat,
+    archive_name: String,
+    archive_path: PathBuf,
+    source_size: u64,
+    /// Exclude patterns for tar, including the backup folder when it lies inside this directory
+    excludes: Vec<String>,
+    /// Subpaths to archive instead of the whole directory, empty for everything
+    includes: Vec<String>,
+    /// Set when this job archives one part of a split directory
+    part: Option<JobPart>,
+    /// Account of another user's home folder the directory belongs to
+    user: Option<String>,
+    /// Trained zstd dictionary next to `archive_path`
+    dictionary: Option<PathBuf>,
+    /// Overall progress percentage reported while this job runs
+    progress: usize,
+}
+
+/// Position of a part archive within its split directory
+#[derive(Clone, Copy)]
+struct JobPart {
+    index: usize,
+    count: usize,
+    /// Source size of the whole directory, recorded on the combined item
+    dir_source_size: u64,
+}
+
+/// A finished part archive and the reason it is incomplete, if tar reported problems
+type FinishedPart = (BackupItem, Option<String>);
+
+impl DirectoryJob {
+    /// Directory name for log messages, with the part number for split directories
+    fn label(&self) -> String {
+        match self.part {
+            Some(part) => format!("{} (Teil {}/{})", self.dir, part.index + 1, part.count),
+            None => self.dir.clone(),
+        }
+    }
+}
+
+/// Distribute the top-level entries of `dir` over at most `parts` groups of similar source size,
+/// largest entries first, each into the currently smallest group. Returns entry names and bytes per group.
+/// `excludes` are the job's tar patterns (relative to the parent of `dir`); excluded entries and files
+/// above `max_file_bytes` don't count, like in `compute_source_sizes`.
+fn split_directory_children(dir: &Path, parts: usize, excludes: &[String], max_file_bytes: Option<u64>) -> Vec<(Vec<String>, u64)> {
+    let Ok(entries) = fs::read_dir(dir) else { return Vec::new() };
+    let parent = dir.parent().unwrap_or(Path::new("/"));
+    let mut children: Vec<(String, u64)> = entries
+        .filter_map(|e| e.ok())
+        .filter(|entry| !is_excluded(parent, &entry.path(), excludes))
+        .map(|entry| (entry.file_name().to_string_lossy().to_string(), archived_size(parent, &entry.path(), excludes, max_file_bytes)))
+        .collect();
+    children.sort_by_key(|child| std::cmp::Reverse(child.1));
+    
+    let mut groups: Vec<(Vec<String>, u64)> = vec![(Vec::new(), 0); parts.min(children.len())];
+    for (name, size) in children {
+        if let Some(group) = groups.iter_mut().min_by_key(|group| group.1) {
+            group.0.push(name);
+            group.1 += size;
+        }
+    }
+    groups
+}
+
+/// Combine the finished parts of a split directory into one item; the first part stays the item's own archive
+fn merge_split_parts(parts: Vec<BackupItem>, source_size: u64) -> Option<BackupItem> {
+    let mut parts = parts.into_iter();
+    let mut item = parts.next()?;
+    for part in parts {
+        item.archive_size_bytes += part.archive_size_bytes;
+        // The parts were archived side by side
+        item.duration_seconds = item.duration_seconds.max(part.duration_seconds);
+        item.parts.push(ArchivePart {
+            archive: part.archive,
+            hash: part.hash,
+            archive_size_bytes: part.archive_size_bytes,
+            dictionary_hash: part.dictionary_hash,
+        });
+    }
+    item.source_size_bytes = source_size;
+    Some(item)
+}
+
+/// Finish a backup stopped with `cancel_backup`: report it, reset the flag for the next run and return
+/// the cancel error. Finished archives stay for resuming, as the backup is not marked complete.
+fn backup_cancelled(log: &mut BackupLog, sink: &dyn ProgressSink) -> String {
+    log.emit("⚠️ Backup abgebrochen!");
+    sink.progress(0, "Backup abgebrochen");
+    BACKUP_CANCELLED.store(false, Ordering::SeqCst);
+    BACKUP_CANCELLED_ERROR.to_string()
+}
+
+/// Archiving stops once the target has less free space than this
+const BACKUP_FREE_SPACE_MARGIN_GB: f64 = 1.0;
+
+/// Free space on the target in GB if it dropped below the safety margin.
+/// An unknown free space (df failed) is not treated as low.
+fn low_free_space_gb(target: &Path) -> Option<f64> {
+    let free_gb = get_disk_space_gb(target).1;
+    (free_gb > 0.0 && free_gb < BACKUP_FREE_SPACE_MARGIN_GB).then_some(free_gb)
+}
+
+/// Events sent from the archiving workers to `run_backup`, indexed into the job list
+enum DirectoryJobEvent {
+    Started(usize),
+    /// The target ran low on space before this job could start
+    LowSpace(usize, f64),
+    /// Current size of the archive being written
+    Written(usize, u64),
+    /// Carries the source file hashes when `generate_source_manifest` is set
+    Finished(usize, Result<TarArchive, String>, Option<SourceHashes>, chrono::DateTime<Local>),
+}
+
+/// Mount point containing `path` and its filesystem type
+fn mount_of(path: &Path) -> Option<(String, String)> {
+    get_mount_types()
+        .into_iter()
+        .filter(|(mount_point, _)| path.starts_with(mount_point.as_str()))
+        .max_by_key(|(mount_point, _)| mount_point.len())
+}
+
+/// Darwin `setpriority` selectors for the background band (sys/resource.h)
+const PRIO_DARWIN_PROCESS: i32 = 4;
+const PRIO_DARWIN_BG: i32 = 0x1000;
+
+impl BackupThrottle {
+    /// `pv` and the rate in MB/s if writing to `target` should be limited
+    fn rate_limiter(&self, target: &Path) -> Option<(String, u32)> {
+        if self.network_rate_limit_mb == 0 || !mount_of(target).is_some_and(|(_, fs_type)| is_network_filesystem(&fs_type)) {
+            return None;
+        }
+        find_homebrew_command("pv").map(|pv| (pv, self.network_rate_limit_mb))
+    }
+    
+    fn is_active(&self) -> bool {
+        self.nice > 0 || self.background_io || self.zstd_threads > 0 || self.network_rate_limit_mb > 0
+    }
+}
+
+/// Whether `path` lives on a local, non-USB disk where parallel tar jobs pay off.
+/// Unknown disks count as slow.
+fn is_fast_local_target(path: &Path) -> bool {
+    let Some((mount_point, fs_type)) = mount_of(path) else {
+        return false;
+    };
+    if is_network_filesystem(&fs_type) {
+        return false;
+    }
+    command_output("diskutil", &["info", &mount_point])
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Protocol:").map(|p| p.trim().to_string()))
+        .is_some_and(|protocol| !protocol.is_empty() && protocol != "USB" && protocol != "Disk Image")
+}
+
+/// How often the size of an archive in progress is polled
+const ARCHIVE_MONITOR_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Runs `work` while a monitor thread reports the growing size of `target` (or of its volumes)
+/// once per second, so long tar runs still show movement.
+fn with_archive_monitor<T>(target: &Path, on_size: impl Fn(u64) + Sync, work: impl FnOnce() -> T) -> T {
+    use std::sync::mpsc::{self, RecvTimeoutError};
+    
+    let (stop_tx, stop_rx) = mpsc::channel::<()>();
+    let on_size = &on_size;
+    std::thread::scope(|scope| {
+        scope.spawn(move || {
+            // The sender is dropped once tar exits, which ends the loop right away
+            while let Err(RecvTimeoutError::Timeout) = stop_rx.recv_timeout(ARCHIVE_MONITOR_INTERVAL) {
+                if let Some(size) = archive_written_size(target) {
+                    on_size(size);
+                }
+            }
+        });
+        let result = work();
+        drop(stop_tx);
+        result
+    })
+}
+
+/// One line of a backup's `backup.log` (JSON lines)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupLogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<BackupLogSummary>,
+}
+
+/// Final structured entry of a backup log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupLogSummary {
+    pub success: bool,
+    pub duration_seconds: u64,
+    pub total_bytes: u64,
+    pub item_count: usize,
+    pub errors: Vec<String>,
+}
+
+/// Machine-readable counterpart of the `backup-log` lines, emitted on `backup-event`.
+/// Item events carry the configured path, the source size and an optional error.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum BackupEvent {
+    ItemStarted { path: String, bytes: u64 },
+    /// `error` is set when the archive was written but is incomplete
+    ItemCompleted { path: String, bytes: u64, error: Option<String> },
+    ItemSkipped { path: String, bytes: u64, error: Option<String> },
+    ItemFailed { path: String, bytes: u64, error: Option<String> },
+    Warning { message: String },
+    Summary { success: bool, item_count: usize, bytes: u64, error: Option<String> },
+}
+
+/// Receiver of log lines and progress of a backup, verify or restore run
+trait ProgressSink: Send + Sync {
+    fn log(&self, message: &str);
+    fn progress(&self, percent: usize, message: &str);
+    /// Archiving progress with byte counts, rate and ETA; sinks without a use for them only get percent and message
+    fn progress_transfer(&self, percent: usize, message: &str, _transfer: TransferProgress) {
+        self.progress(percent, message);
+    }
+    /// Structured events for the UI such as `backup-event`
+    fn event(&self, _name: &str, _payload: serde_json::Value) {}
+}
+
+/// Byte counts sent along with archiving progress
+#[derive(Debug, Serialize, Clone, Copy)]
+struct TransferProgress {
+    bytes_done: u64,
+    bytes_total: u64,
+    bytes_per_second: f64,
+    eta_seconds: Option<u64>,
+}
+
+/// Sends a run's output as `<channel>-log` and `<channel>-progress` events to the window
+struct WindowSink {
+    window: tauri::Window,
+    channel: &'static str,
+}
+
+impl WindowSink {
+    fn backup(window: &tauri::Window) -> Self {
+        WindowSink { window: window.clone(), channel: "backup" }
+    }
+    
+    fn restore(window: &tauri::Window) -> Self {
+        WindowSink { window: window.clone(), channel: "restore" }
+    }
+}
+
+impl ProgressSink for WindowSink {
+    fn log(&self, message: &str) {
+        let _ = self.window.emit(&format!("{}-log", self.channel), message);
+    }
+    
+    fn progress(&self, percent: usize, message: &str) {
+        let _ = self.window.emit(&format!("{}-progress", self.channel), serde_json::json!({
+            "progress": percent,
+            "message": message
+        }));
+    }
+    
+    fn progress_transfer(&self, percent: usize, message: &str, transfer: TransferProgress) {
+        let _ = self.window.emit(&format!("{}-progress", self.channel), serde_json::json!({
+            "progress": percent,
+            "message": message,
+            "bytes_done": transfer.bytes_done,
+            "bytes_total": transfer.bytes_total,
+            "bytes_per_second": transfer.bytes_per_second,
+            "eta_seconds": transfer.eta_seconds
+        }));
+    }
+    
+    fn event(&self, name: &str, payload: serde_json::Value) {
+        let _ = self.window.emit(name, payload);
+    }
+}
+
+/// Prints log lines and progress steps of a `--backup` run
+#[derive(Default)]
+struct ConsoleSink {
+    /// Last printed percentage, archive size updates within one step are not repeated
+    last_progress: AtomicUsize,
+}
+
+impl ProgressSink for ConsoleSink {
+    fn log(&self, message: &str) {
+        println!("{}", message);
+    }
+    
+    fn progress(&self, percent: usize, message: &str) {
+        if self.last_progress.swap(percent, Ordering::SeqCst) != percent {
+            println!("[{:>3}%] {}", percent, message);
+        }
+    }
+}
+
+/// Discards everything, for test runs where only the result matters
+#[cfg(test)]
+struct NoopSink;
+
+#[cfg(test)]
+impl ProgressSink for NoopSink {
+    fn log(&self, _message: &str) {}
+    fn progress(&self, _percent: usize, _message: &str) {}
+}
+
+/// Keeps the log lines, so a run's output can be inspected afterwards. Clones share the same lines.
+#[derive(Default, Clone)]
+struct CollectingSink {
+    lines: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+impl CollectingSink {
+    fn lines(&self) -> Vec<String> {
+        self.lines.lock().map(|lines| lines.clone()).unwrap_or_default()
+    }
+}
+
+impl ProgressSink for CollectingSink {
+    fn log(&self, message: &str) {
+        if let Ok(mut lines) = self.lines.lock() {
+            lines.push(message.to_string());
+        }
+    }
+    
+    fn progress(&self, _percent: usize, _message: &str) {}
+}
+
+/// Emits `backup-log` events and mirrors them into `backup.log` inside the backup directory.
+/// Lines logged before the directory exists are buffered until `attach` is called.
+struct BackupLog {
+    sink: Box<dyn ProgressSink>,
+    file: Option<fs::File>,
+    pending: Vec<BackupLogEntry>,
+    errors: Vec<String>,
+    started: std::time::Instant,
+}
+
+impl BackupLog {
+    fn new(sink: Box<dyn ProgressSink>) -> Self {
+        BackupLog {
+            sink,
+            file: None,
+            pending: Vec::new(),
+            errors: Vec::new(),
+            started: std::time::Instant::now(),
+        }
+    }
+    
+    /// Start writing to `path`, flushing everything logged so far
+    fn attach(&mut self, path: &Path) {
+        self.file = fs::OpenOptions::new().create(true).append(true).open(path).ok();
+        for entry in std::mem::take(&mut self.pending) {
+            self.write_entry(entry);
+        }
+    }
+    
+    fn emit(&mut self, message: impl Into<String>) {
+        let message = message.into();
+        self.sink.log(&message);
+        
+        let level = if message.starts_with('❌') {
+            self.errors.push(message.clone());
+            "error"
+        } else if message.starts_with('⚠') {
+            self.event(BackupEvent::Warning { message: message.clone() });
+            "warn"
+        } else {
+            "info"
+        };
+        self.write_entry(BackupLogEntry {
+            timestamp: Local::now().to_rfc3339(),
+            level: level.to_string(),
+            message,
+            summary: None,
+        });
+    }
+    
+    fn event(&self, event: BackupEvent) {
+        self.sink.event("backup-event", serde_json::to_value(&event).unwrap_or_default());
+    }
+    
+    /// Add a finished item to the backup and report it as completed
+    fn push_completed(&self, items: &mut Vec<BackupItem>, item: BackupItem) {
+        self.event(BackupEvent::ItemCompleted {
+            path: item.path.clone(),
+            bytes: item.source_size_bytes,
+            error: None,
+        });
+        items.push(item);
+    }
+    
+    fn write_entry(&mut self, entry: BackupLogEntry) {
+        match self.file.as_mut() {
+            Some(file) => {
+                if let Ok(line) = serde_json::to_string(&entry) {
+                    let _ = writeln!(file, "{}", line);
+                }
+            }
+            None => self.pending.push(entry),
+        }
+    }
+    
+    /// Append the summary entry for the finished (or failed) run
+    fn finish(&mut self, result: &Result<BackupMetadata, String>) {
+        let (level, message, summary) = match result {
+            Ok(metadata) => ("info", "Backup abgeschlossen".to_string(), BackupLogSummary {
+                success: true,
+                duration_seconds: metadata.duration_seconds,
+                total_bytes: metadata.total_source_size_bytes,
+                item_count: metadata.items.len(),
+                errors: self.errors.clone(),
+            }),
+            Err(e) => {
+                self.errors.push(e.clone());
+                ("error", format!("Backup fehlgeschlagen: {}", e), BackupLogSummary {
+                    success: false,
+                    duration_seconds: self.started.elapsed().as_secs(),
+                    total_bytes: 0,
+                    item_count: 0,
+                    errors: self.errors.clone(),
+                })
+            }
+        };
+        self.event(BackupEvent::Summary {
+            success: summary.success,
+            item_count: summary.item_count,
+            bytes: summary.total_bytes,
+            error: result.as_ref().err().cloned(),
+        });
+        self.write_entry(BackupLogEntry {
+            timestamp: Local::now().to_rfc3339(),
+            level: level.to_string(),
+            message,
+            summary: Some(summary),
+        });
+    }
+}
+
+/// Read the JSON-lines log written during a backup run
+#[tauri::command]
+fn get_backup_log(target_path: String, timestamp: String) -> Result<Vec<BackupLogEntry>, String> {
+    let log_path = PathBuf::from(&target_path)
+        .join("macos-backup-suite")
+        .join("data")
+        .join(&timestamp)
+        .join("backup.log");
+    
+    let content = fs::read_to_string(&log_path)
+        .map_err(|e| format!("Fehler beim Lesen des Backup-Logs: {}", e))?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Estimate the archive size for the given directories before starting a backup
+#[tauri::command]
+async fn estimate_backup_size(
+    directories: Vec<String>,
+    target_path: Option<String>,
+) -> Result<BackupSizeEstimate, String> {
+    let config = load_config().unwrap_or_default();
+    let home = dirs::home_dir().unwrap_or_default();
+    // Directories from the config bring their own includes and excludes
+    let directories: Vec<BackupDirectory> = directories.into_iter()
+        .map(|path| config.directories.iter().find(|d| d.path == path).cloned().unwrap_or_else(|| BackupDirectory::new(path)))
+        .collect();
+    let source_size: u64 = compute_source_sizes(&directories, &home, &effective_exclude_patterns(&config), max_file_bytes(&config)).iter().sum();
+    Ok(estimate_archive_size(
+        source_size,
+        config.estimated_compression_ratio,
+        target_path.as_deref().map(Path::new),
+    ))
+}
+
+/// Dry preview of a backup with `config`: which directories exist and how large they are,
+/// which special items are enabled and available, and the estimated archive size
+#[tauri::command]
+async fn preview_backup(config: BackupConfig, target_path: Option<String>) -> Result<BackupPreview, String> {
+    let home = dirs::home_dir().unwrap_or_default();
+    let exclude_patterns = effective_exclude_patterns(&config);
+    let enabled = enabled_directories(&config.directories);
+    let paths: Vec<String> = enabled.iter().map(|d| d.path.clone()).collect();
+    let sizes = compute_source_sizes(&enabled, &home, &exclude_patterns, max_file_bytes(&config));
+    
+    let directories: Vec<DirectoryPreview> = paths.iter().zip(&sizes).map(|(dir, size)| {
+        let expanded = expand_tilde(dir, &home);
+        DirectoryPreview {
+            path: dir.clone(),
+            exists: expanded.exists(),
+            is_file: expanded.is_file(),
+            size_bytes: *size,
+        }
+    }).collect();
+    
+    let preview = |item: &str, enabled: bool, available: Result<Option<String>, String>| {
+        let (available, detail) = match available {
+            Ok(detail) => (true, detail),
+            Err(reason) => (false, Some(reason)),
+        };
+        SpecialItemPreview { item: item.to_string(), enabled, available, detail }
+    };
+    
+    let mut special_items = vec![
+        preview("homebrew-packages", config.backup_homebrew, match check_homebrew() {
+            Ok(true) => Ok(None),
+            _ => Err("Homebrew nicht installiert".to_string()),
+        }),
+        preview("mas-apps", config.backup_mas, match check_mas() {
+            Ok(true) => Ok(None),
+            _ => Err("mas nicht installiert".to_string()),
+        }),
+        preview("vscode-extensions", true, get_vscode_extensions()
+            .map(|extensions| Some(format!("{} Extensions", extensions.len())))),
+    ];
+    for (browser, enabled) in [
+        (BrowserKind::Safari, config.backup_safari_settings),
+        (BrowserKind::Chrome, config.backup_chrome),
+        (BrowserKind::Firefox, config.backup_firefox),
+    ] {
+        let found = browser.backup_paths(&home).iter().filter(|(_, path)| path.exists()).count();
+        special_items.push(preview(browser.item_name(), enabled, if found > 0 {
+            Ok(Some(format!("{} Einträge", found)))
+        } else {
+            Err(format!("Keine {}-Einstellungen gefunden", browser.label()))
+        }));
+    }
+    
+    let estimate = estimate_archive_size(
+        sizes.iter().sum(),
+        config.estimated_compression_ratio,
+        target_path.as_deref().map(Path::new),
+    );
+    
+    Ok(BackupPreview { directories, special_items, estimate })
+}
+
+#[tauri::command]
+async fn get_backup_capabilities() -> BackupCapabilities {
+    let home = dirs::home_dir().unwrap_or_default();
+    let cache = find_homebrew_cache();
+    let homebrew_cache_limit_bytes = load_config().unwrap_or_default().homebrew_cache_max_gb * 1024 * 1024 * 1024;
+    let homebrew_cache_size_bytes = cache.as_deref().map(compute_directory_size).unwrap_or(0);
+    
+    BackupCapabilities {
+        homebrew: find_brew_path().is_some(),
+        mas: find_homebrew_command("mas").is_some(),
+        vscode: find_vscode_cli().is_some(),
+        safari_data: BrowserKind::Safari.backup_paths(&home).iter().any(|(_, path)| path.exists()),
+        homebrew_cache: cache.is_some(),
+        homebrew_cache_size_bytes,
+        homebrew_cache_limit_bytes,
+        homebrew_cache_within_limit: cache.is_some() && homebrew_cache_size_bytes <= homebrew_cache_limit_bytes,
+    }
+}
+
+/// Keychain service under which backup passphrases are stored
+const KEYCHAIN_SERVICE: &str = "macos-backup-suite";
+
+/// Translate a failed `security` call into a readable error
+fn keychain_error(output: &std::process::Output, label: &str) -> String {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // Exit code 44 is errSecItemNotFound
+    if output.status.code() == Some(44) || stderr.contains("could not be found") {
+        format!("Keine Passphrase mit der Bezeichnung \"{}\" im Schlüsselbund gefunden", label)
+    } else if stderr.contains("User canceled") || stderr.contains("User interaction is not allowed") || stderr.contains("authorization") {
+        "Zugriff auf den Schlüsselbund wurde verweigert".to_string()
+    } else {
+        format!("Schlüsselbund-Fehler: {}", stderr.trim())
+    }
+}
+
+/// Read a stored backup passphrase from the login keychain
+fn keychain_passphrase(label: &str) -> Result<String, String> {
+    let output = Command::new("security")
+        .args(["find-generic-password", "-s", KEYCHAIN_SERVICE, "-a", label, "-w"])
+        .output()
+        .map_err(|e| format!("security konnte nicht gestartet werden: {}", e))?;
+    if !output.status.success() {
+        return Err(keychain_error(&output, label));
+    }
+    let passphrase = String::from_utf8_lossy(&output.stdout).trim_end_matches('\n').to_string();
+    if passphrase.is_empty() {
+        return Err(format!("Passphrase \"{}\" im Schlüsselbund ist leer", label));
+    }
+    Ok(passphrase)
+}
+
+#[tauri::command]
+fn store_passphrase_in_keychain(label: String, passphrase: String) -> Result<(), String> {
+    if label.trim().is_empty() || passphrase.is_empty() {
+        return Err("Bezeichnung und Passphrase dürfen nicht leer sein".to_string());
+    }
+    if passphrase.contains(['\n', '\r']) || label.contains(['\n', '\r']) {
+        return Err("Bezeichnung und Passphrase dürfen keine Zeilenumbrüche enthalten".to_string());
+    }
+    // The command goes through `security -i` on stdin, so the passphrase never shows up in the
+    // process list. -U updates an existing entry instead of failing with a duplicate.
+    let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+    let command = format!(
+        "add-generic-password -U -s {} -a {} -w {}\n",
+        quote(KEYCHAIN_SERVICE), quote(&label), quote(&passphrase)
+    );
+    let mut child = Command::new("security")
+        .arg("-i")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("security konnte nicht gestartet werden: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(command.as_bytes())
+            .map_err(|e| format!("security konnte nicht aufgerufen werden: {}", e))?;
+    }
+    let output = child.wait_with_output()
+        .map_err(|e| format!("security Fehler: {}", e))?;
+    // In interactive mode a failing command is only reported on stderr
+    if !output.status.success() || !output.stderr.is_empty() {
+        return Err(keychain_error(&output, &label));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn get_passphrase_from_keychain(label: String) -> Result<String, String> {
+    keychain_passphrase(&label)
+}
+
+/// An explicit passphrase wins; otherwise encrypted backups look it up in the keychain by label
+fn resolve_passphrase(encrypt: Option<bool>, passphrase: Option<String>, keychain_label: Option<String>) -> Result<Option<String>, String> {
+    match (passphrase, keychain_label) {
+        (Some(p), _) if !p.is_empty() => Ok(Some(p)),
+        (_, Some(label)) if encrypt.unwrap_or(false) && !label.is_empty() => keychain_passphrase(&label).map(Some),
+        (p, _) => Ok(p),
+    }
+}
+
+#[tauri::command]
+async fn create_backup(
+    target_path: String,
+    directories: Vec<BackupDirectory>,
+    encrypt: Option<bool>,
+    encryption_passphrase: Option<String>,
+    keychain_label: Option<String>,
+    format: Option<String>,
+    window: tauri::Window,
+) -> Result<BackupMetadata, String> {
+    let encryption_passphrase = resolve_passphrase(encrypt, encryption_passphrase, keychain_label)?;
+    let sink = WindowSink::backup(&window);
+    let mut log = BackupLog::new(Box::new(WindowSink::backup(&window)));
+    let result = run_backup(&load_config().unwrap_or_default(), target_path.clone(), enabled_directories(&directories), encrypt, encryption_passphrase, format, None, &sink, &mut log);
+    log.finish(&result);
+    drop(log);
+    eject_after_backup(&target_path, &result, &sink);
+    notify_backup_result(&window, &result);
+    result
+}
+
+/// Continue an interrupted backup in its existing `data/<timestamp>` directory.
+/// Directories whose archives were completed and still match their hash are skipped.
+#[tauri::command]
+async fn resume_interrupted_backup(
+    target_path: String,
+    timestamp: String,
+    directories: Vec<BackupDirectory>,
+    encrypt: Option<bool>,
+    encryption_passphrase: Option<String>,
+    keychain_label: Option<String>,
+    window: tauri::Window,
+) -> Result<BackupMetadata, String> {
+    let encryption_passphrase = resolve_passphrase(encrypt, encryption_passphrase, keychain_label)?;
+    let backup_root = PathBuf::from(&target_path)
+        .join("macos-backup-suite")
+        .join("data")
+        .join(&timestamp);
+    if !backup_root.is_dir() {
+        return Err(format!("Backup nicht gefunden: {}", timestamp));
+    }
+    if backup_root.join("metadata.json").exists() {
+        return Err(format!("Backup {} ist bereits abgeschlossen", timestamp));
+    }
+    
+    let sink = WindowSink::backup(&window);
+    let mut log = BackupLog::new(Box::new(WindowSink::backup(&window)));
+    let result = run_backup(&load_config().unwrap_or_default(), target_path.clone(), enabled_directories(&directories), encrypt, encryption_passphrase, None, Some(timestamp), &sink, &mut log);
+    log.finish(&result);
+    drop(log);
+    eject_after_backup(&target_path, &result, &sink);
+    notify_backup_result(&window, &result);
+    result
+}
+
+/// Show a native notification unless disabled in the config. Whether it is actually
+/// displayed is up to the system's notification permission, which the frontend keeps in sync.
+fn notify(window: &tauri::Window, title: &str, body: &str) {
+    let enabled = load_config().map(|c| c.notifications_enabled).unwrap_or(true);
+    if enabled {
+        let _ = window.notification().builder().title(title).body(body).show();
+    }
+}
+
+fn notify_backup_result(window: &tauri::Window, result: &Result<BackupMetadata, String>) {
+    match result {
+        Ok(metadata) => {
+            let gb = metadata.total_source_size_bytes as f64 / (1024.0 * 1024.0 * 1024.0);
+            let minutes = metadata.duration_seconds.div_ceil(60);
+            notify(window, "Backup abgeschlossen", &format!("Backup abgeschlossen: {:.1} GB in {} min", gb, minutes));
+        }
+        Err(e) if e == BACKUP_CANCELLED_ERROR => notify(window, "Backup abgebrochen", e),
+        Err(e) => notify(window, "Backup fehlgeschlagen", e),
+    }
+}
+
+fn notify_restore_result(window: &tauri::Window, title: &str, result: &RestoreResult) {
+    notify(window, title, &format!(
+        "{} wiederhergestellt, {} übersprungen, {} Fehler",
+        result.restored_count, result.skipped_count, result.error_count
+    ));
+}
+
+/// List backups that were started but never finished (no `metadata.json`), newest first
+#[tauri::command]
+fn find_incomplete_backups(target_path: String) -> Result<Vec<String>, String> {
+    let data_path = PathBuf::from(&target_path).join("macos-backup-suite").join("data");
+    
+    let mut incomplete = Vec::new();
+    if let Ok(entries) = fs::read_dir(&data_path) {
+        let running = RUNNING_BACKUPS.lock().map(|r| r.clone()).unwrap_or_default();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            // Only interrupted runs: the marker is removed once metadata.json is written,
+            // and a backup that is still running in this app isn't offered for resuming
+            let interrupted = path.join(IN_PROGRESS_MARKER).exists()
+                && !path.join("metadata.json").exists()
+                && !fs::canonicalize(&path).is_ok_and(|root| running.contains(&root));
+            if interrupted {
+                if let Some(name) = entry.file_name().to_str() {
+                    incomplete.push(name.to_string());
+                }
+            }
+        }
+    }
+    
+    incomplete.sort_by(|a, b| b.cmp(a));
+    Ok(incomplete)
+}
+
+/// Write `path` via a temp file in the same directory and a rename, so readers
+/// never see a half-written file if the app is killed mid-write
+fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let file_name = path.file_name().ok_or_else(|| std::io::Error::other("invalid path"))?;
+    let temp_path = path.with_file_name(format!(".{}.tmp", file_name.to_string_lossy()));
+    let result = (|| {
+        let mut file = fs::File::create(&temp_path)?;
+        file.write_all(contents.as_ref())?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// Marker in `data/<timestamp>` that exists while a backup is running.
+/// It doubles as a journal: an `InProgressHeader` line, then every finished directory archive as one JSON line.
+const IN_PROGRESS_MARKER: &str = ".in_progress";
+
+/// Registers a backup directory in `RUNNING_BACKUPS` for as long as it is alive
+struct RunningBackup(PathBuf);
+
+impl RunningBackup {
+    fn register(backup_root: &Path) -> Self {
+        let root = fs::canonicalize(backup_root).unwrap_or_else(|_| backup_root.to_path_buf());
+        if let Ok(mut running) = RUNNING_BACKUPS.lock() {
+            running.push(root.clone());
+        }
+        RunningBackup(root)
+    }
+}
+
+impl Drop for RunningBackup {
+    fn drop(&mut self) {
+        if let Ok(mut running) = RUNNING_BACKUPS.lock() {
+            running.retain(|root| root != &self.0);
+        }
+    }
+}
+
+/// First line of the in-progress journal: settings a resumed backup has to keep for the archives already written
+#[derive(Debug, Serialize, Deserialize)]
+struct InProgressHeader {
+    hash_algorithm: String,
+}
+
+fn read_in_progress_header(backup_root: &Path) -> Option<InProgressHeader> {
+    let content = fs::read_to_string(backup_root.join(IN_PROGRESS_MARKER)).ok()?;
+    serde_json::from_str(content.lines().next()?).ok()
+}
+
+fn record_completed_item(backup_root: &Path, item: &BackupItem) {
+    if let Ok(mut file) = fs::OpenOptions::new().create(true).append(true).open(backup_root.join(IN_PROGRESS_MARKER)) {
+        if let Ok(line) = serde_json::to_string(item) {
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+}
+
+/// Hashes of an item's source files, keyed by path relative to the item (the file name for a single file)
+type SourceHashes = std::collections::BTreeMap<String, String>;
+
+/// Per-file hashes of an item's sources, written with `generate_source_manifest` so restored files
+/// can be compared with the originals rather than only the archives with their checksums
+#[derive(Debug, Serialize, Deserialize)]
+struct SourceManifest {
+    item: String,
+    hash_algorithm: String,
+    files: SourceHashes,
+}
+
+/// `source-manifests/<item>.source_manifest.json` inside a backup
+fn source_manifest_path(backup_root: &Path, item_path: &str) -> PathBuf {
+    backup_root
+        .join("source-manifests")
+        .join(format!("{}.source_manifest.json", archive_stem(&item_path.replace('/', "_"))))
+}
+
+/// Hash every file `create_tar_gz` archives for a job: everything below `source` (or only `includes`)
+/// except excluded entries. Stops early once the backup is cancelled.
+fn hash_source_files(source: &Path, excludes: &[String], includes: &[String], follow_symlinks: bool, algorithm: HashAlgorithm) -> SourceHashes {
+    let mut hashes = SourceHashes::new();
+    if source.is_file() {
+        if let (Some(name), Ok(hash)) = (source.file_name(), hash_file(source, algorithm)) {
+            hashes.insert(name.to_string_lossy().to_string(), hash);
+        }
+        return hashes;
+    }
+    
+    // tar's exclude patterns are relative to the parent directory it runs in
+    let parent = source.parent().unwrap_or(Path::new("/"));
+    let roots: Vec<PathBuf> = if includes.is_empty() {
+        vec![source.to_path_buf()]
+    } else {
+        includes.iter().map(|p| source.join(p.trim_matches('/'))).collect()
+    };
+    for root in roots {
+        let files = WalkDir::new(&root)
+            .follow_links(follow_symlinks)
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !is_excluded(parent, e.path(), excludes))
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file());
+        for entry in files {
+            if BACKUP_CANCELLED.load(Ordering::SeqCst) {
+                return hashes;
+            }
+            let Ok(relative) = entry.path().strip_prefix(source) else { continue };
+            if let Ok(hash) = hash_file(entry.path(), algorithm) {
+                hashes.insert(relative.to_string_lossy().to_string(), hash);
+            }
+        }
+    }
+    hashes
+}
+
+/// Store the source hashes of a finished item as its source manifest
+fn write_source_manifest(backup_root: &Path, item_path: &str, files: SourceHashes, algorithm: HashAlgorithm) -> Result<(), String> {
+    let path = source_manifest_path(backup_root, item_path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let manifest = SourceManifest {
+        item: item_path.to_string(),
+        hash_algorithm: algorithm.name().to_string(),
+        files,
+    };
+    let json = serde_json::to_string(&manifest).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| e.to_string())
+}
+
+/// Write a `<archive>.sha256` (or `.b3`) sidecar for every archive and zstd dictionary and the `SHA256SUMS` (or `B3SUMS`)
+/// manifest in `shasum`/`b3sum` format, so backups can be verified without the app
+fn write_checksum_files(backup_root: &Path, items: &[BackupItem], algorithm: HashAlgorithm) -> Result<(), String> {
+    let mut manifest = String::new();
+    for item in &archive_files(items) {
+        let mut entries = vec![(item.archive.clone(), &item.hash)];
+        // The dictionary is needed to read the archive, so it is listed like one
+        if let Some(dictionary_hash) = &item.dictionary_hash {
+            entries.push((format!("{}.dict", item.archive), dictionary_hash));
+        }
+        for (name, hash) in entries {
+            let line = format!("{}  {}\n", hash, name);
+            fs::write(backup_root.join(format!("{}.{}", name, algorithm.sidecar_extension())), &line).map_err(|e| e.to_string())?;
+            manifest.push_str(&line);
+        }
+    }
+    fs::write(backup_root.join(algorithm.manifest_name()), manifest).map_err(|e| e.to_string())
+}
+
+fn read_completed_items(backup_root: &Path) -> Vec<BackupItem> {
+    fs::read_to_string(backup_root.join(IN_PROGRESS_MARKER))
+        .map(|content| content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+        .unwrap_or_default()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_backup(
+    config: &BackupConfig,
+    target_path: String,
+    directories: Vec<BackupDirectory>,
+    encrypt: Option<bool>,
+    encryption_passphrase: Option<String>,
+    format: Option<String>,
+    resume_timestamp: Option<String>,
+    sink: &dyn ProgressSink,
+    log: &mut BackupLog,
+) -> Result<BackupMetadata, String> {
+    // Passphrase is only taken from the command argument, never from config.json
+    let passphrase = if encrypt.unwrap_or(false) {
+        match encryption_passphrase.as_deref() {
+            Some(p) if !p.is_empty() => Some(p.to_string()),
+            _ => return Err("Verschlüsselung aktiviert, aber keine Passphrase angegeben".to_string()),
+        }
+    } else {
+        None
+    };
+    let passphrase = passphrase.as_deref();
+    BACKUP_PAUSED.store(false, Ordering::SeqCst);
+    
+    let start = Local::now();
+    let start_time_str = start.format("%d.%m.%Y %H:%M:%S").to_string();
+    let resuming = resume_timestamp.is_some();
+    let timestamp = resume_timestamp.unwrap_or_else(|| start.format("%Y%m%d-%H%M%S").to_string());
+    
+    let suite_root = PathBuf::from(&target_path).join("macos-backup-suite");
+    let backup_root = suite_root.join("data").join(&timestamp);
+    let inventory_root = suite_root.join("inventories").join(&timestamp);
+    
+    if resuming {
+        log.emit(format!("=== Backup {} fortgesetzt: {} ===", timestamp, start_time_str));
+    } else {
+        log.emit(format!("=== Backup gestartet: {} ===", start_time_str));
+    }
+    let source_system = SourceSystem::current();
+    log.emit(format!(
+        "System: macOS {} ({}, {}) auf {}, App-Version {}",
+        source_system.os_version, source_system.os_build, source_system.arch,
+        source_system.hostname, source_system.app_version
+    ));
+    sink.progress(1, "Initialisiere Backup...");
+    
+    // A resumed backup keeps the checksum algorithm of the archives written before the interruption,
+    // so its metadata and checksum files stay consistent even if the setting changed in between
+    let resumed_header = if resuming { read_in_progress_header(&backup_root) } else { None };
+    let hash_algorithm = HashAlgorithm::from_name(resumed_header.as_ref().map_or(&config.hash_algorithm, |header| &header.hash_algorithm));
+    let throttle = &config.throttle;
+    if !config.exclude_patterns.is_empty() {
+        log.emit(format!("Ausschlussmuster: {}", config.exclude_patterns.join(", ")));
+    }
+    let system_excludes = system_file_excludes(config);
+    if !system_excludes.is_empty() {
+        log.emit(format!("Systemdateien werden nicht gesichert: {}", system_excludes.join(", ")));
+    }
+    let exclude_patterns = effective_exclude_patterns(config);
+    if config.follow_symlinks {
+        log.emit("Symlinks werden aufgelöst: Die Linkziele werden gesichert");
+    } else {
+        log.emit("Symlinks werden als Links gespeichert (Linkziele werden nicht gesichert, ungültige Links bleiben erhalten)");
+    }
+    let home = dirs::home_dir().unwrap_or_default();
+    
+    // Pre-compute source sizes for the free-space check and for throughput/ETA reporting
+    log.emit("Berechne Gesamtgröße der Quellen...");
+    let source_sizes = compute_source_sizes(&directories, &home, &exclude_patterns, max_file_bytes(config));
+    let bytes_total: u64 = source_sizes.iter().sum();
+    
+    let estimate = estimate_archive_size(bytes_total, config.estimated_compression_ratio, Some(Path::new(&target_path)));
+    let needed_gb = estimate.estimated_archive_bytes as f64 / (1024.0 * 1024.0 * 1024.0);
+    match (estimate.fits, estimate.free_space_gb) {
+        (Some(false), Some(free_gb)) => {
+            let message = format!("Nicht genügend Speicherplatz: Benötigt ~{:.1} GB, verfügbar {:.1} GB", needed_gb, free_gb);
+            log.emit(format!("❌ {}", message));
+            return Err(message);
+        }
+        (_, Some(free_gb)) => {
+            log.emit(format!("Speicherplatz: Benötigt ~{:.1} GB, verfügbar {:.1} GB", needed_gb, free_gb));
+        }
+        _ => {
+            log.emit("⚠️ Freier Speicherplatz des Ziels konnte nicht ermittelt werden");
+        }
+    }
+    
+    fs::create_dir_all(&backup_root).map_err(|e| e.to_string())?;
+    fs::create_dir_all(&inventory_root).map_err(|e| e.to_string())?;
+    log.attach(&backup_root.join("backup.log"));
+    let _running = RunningBackup::register(&backup_root);
+    
+    let completed_items = read_completed_items(&backup_root);
+    if !resuming {
+        let header = InProgressHeader { hash_algorithm: hash_algorithm.name().to_string() };
+        let _ = fs::write(backup_root.join(IN_PROGRESS_MARKER), serde_json::to_string(&header).map(|line| line + "\n").unwrap_or_default());
+    }
+    
+    // A resumed backup keeps the format of the archives written before the interruption
+    // (single files are always gzip, so any zstd archive means zstd was chosen)
+    let previous_formats: Vec<ArchiveFormat> = completed_items.iter().filter_map(|item| item.format).collect();
+    let format = if format.is_none() && !previous_formats.is_empty() {
+        if previous_formats.contains(&ArchiveFormat::Zst) { ArchiveFormat::Zst } else { ArchiveFormat::Gz }
+    } else {
+        ArchiveFormat::resolve(format.as_deref())?
+    };
+    log.emit(format!("Archivformat: {} (Einzeldateien und Software-Listen: tar.gz)", format.extension()));
+    // FAT can't hold files of 4 GB or more, so larger archives are cut into volumes
+    let volume_size = fat_volume_size(Path::new(&target_path));
+    if let Some(volume_size) = volume_size {
+        log.emit(format!(
+            "Ziel ist FAT-formatiert: Archive über {} MB werden in Teildateien (.001, .002, …) aufgeteilt",
+            volume_size / (1024 * 1024)
+        ));
+    }
+    
+    // Software inventory for the special items, which run brew, mas, code and osascript
+    let (brew_services, mas_versions, login_items, launch_agents) = if config.backup_special_items {
+        log.emit("Sammle Software-Inventar...");
+    
+        if let Ok(brewfile) = get_brew_packages() {
+            let brewfile_path = inventory_root.join("Brewfile");
+            let _ = fs::write(&brewfile_path, &brewfile);
+            log.emit(format!("Brewfile gespeichert: {} Einträge", brewfile.lines().count()));
+        }
+    
+        let brew_services = get_brew_services().ok().filter(|services| !services.is_empty());
+        if let Some(services) = &brew_services {
+            if let Ok(json) = serde_json::to_string_pretty(services) {
+                let _ = fs::write(inventory_root.join("brew_services.json"), json);
+            }
+            let started = services.iter().filter(|s| s.status.as_deref() == Some("started")).count();
+            log.emit(format!("Homebrew-Dienste: {} erfasst, {} gestartet", services.len(), started));
+        }
+    
+        if let Ok(manual_apps) = get_manual_apps() {
+            let manual_path = inventory_root.join("manual_apps.txt");
+            let manual_content = manual_apps.join("\n");
+            let _ = fs::write(&manual_path, &manual_content);
+            log.emit(format!("Manuell installierte Apps: {} Apps", manual_apps.len()));
+        }
+    
+        let mas_versions = get_mas_apps().unwrap_or_default();
+        if !mas_versions.is_empty() {
+            if let Ok(json) = serde_json::to_string_pretty(&mas_versions) {
+                let _ = fs::write(inventory_root.join("mas_apps.json"), json);
+            }
+            log.emit(format!("MAS Apps mit Version: {} Apps", mas_versions.len()));
+        }
+    
+        match get_vscode_extensions() {
+            Ok(extensions) => {
+                let vscode_path = inventory_root.join("vscode_extensions.txt");
+                let vscode_content = extensions.join("\n");
+                let _ = fs::write(&vscode_path, &vscode_content);
+                log.emit(format!("VS Code Extensions: {} Extensions", extensions.len()));
+            }
+            Err(_) => {
+                log.emit("VS Code nicht installiert - Extensions übersprungen");
+            }
+        }
+    
+        let login_items = read_login_items();
+        let launch_agents = list_user_launch_agents();
+        let _ = fs::write(inventory_root.join("login_items.txt"), login_items.join("\n"));
+        if !launch_agents.is_empty() {
+            let agents_inventory = inventory_root.join("LaunchAgents");
+            let _ = fs::create_dir_all(&agents_inventory);
+            for agent in &launch_agents {
+                if let Some(name) = agent.file_name() {
+                    let _ = fs::copy(agent, agents_inventory.join(name));
+                }
+            }
+        }
+        log.emit(format!("Startobjekte: {} Anmeldeobjekte, {} LaunchAgents", login_items.len(), launch_agents.len()));
+        (brew_services, mas_versions, login_items, launch_agents)
+    } else {
+        log.emit("Software-Listen, Einstellungen und Systemobjekte werden nicht gesichert, nur die Verzeichnisse");
+        (None, Vec::new(), Vec::new(), Vec::new())
+    };
+    
+    sink.progress(15, "Inventur abgeschlossen.");
+    
+    let mut items = Vec::new();
+    let mut skipped_directories: Vec<DirectoryIssue> = Vec::new();
+    let mut failed_directories: Vec<DirectoryIssue> = Vec::new();
+    let total = directories.len();
+    
+    let mut bytes_done: u64 = 0;
+    let archive_start = std::time::Instant::now();
+    
+    if config.throttle.is_active() {
+        let throttle = &config.throttle;
+        log.emit(format!(
+            "Gedrosselte Archivierung: nice {}, Hintergrund-I/O {}, zstd-Threads {}, Netzwerk-Limit {}",
+            throttle.nice,
+            if throttle.background_io { "an" } else { "aus" },
+            if throttle.zstd_threads == 0 { "alle".to_string() } else { throttle.zstd_threads.to_string() },
+            if throttle.network_rate_limit_mb == 0 { "keins".to_string() } else { format!("{} MB/s", throttle.network_rate_limit_mb) },
+        ));
+        if throttle.network_rate_limit_mb > 0 && find_homebrew_command("pv").is_none() {
+            log.emit("⚠️ Netzwerk-Limit konfiguriert, aber pv ist nicht installiert (brew install pv)");
+        }
+    }
+    let mut parallel_jobs = config.parallel_backup_jobs.max(1);
+    if parallel_jobs > 1 && !is_fast_local_target(Path::new(&target_path)) {
+        log.emit(format!("Ziel ist kein schnelles lokales Laufwerk – archiviere sequentiell statt mit {} Jobs", parallel_jobs));
+        parallel_jobs = 1;
+    } else if parallel_jobs > 1 {
+        log.emit(format!("Archiviere bis zu {} Verzeichnisse gleichzeitig", parallel_jobs));
+    }
+    
+    // Check every directory up front; the ones that still need archiving become jobs
+    let mut jobs: Vec<DirectoryJob> = Vec::new();
+    let mut oversized_count = 0;
+    let mut oversized_bytes: u64 = 0;
+    let directory_paths: Vec<String> = directories.iter().map(|entry| entry.path.clone()).collect();
+    let expanded_paths = expand_user_paths(&directory_paths, &home);
+    for (i, entry) in directories.iter().enumerate() {
+        let dir = &entry.path;
+        // Check for cancellation before each directory
+        if BACKUP_CANCELLED.load(Ordering::SeqCst) {
+            return Err(backup_cancelled(log, sink));
+        }
+        
+        let (expanded, owner) = expanded_paths[i].clone();
+        
+        if !expanded.exists() {
+            log.emit(format!("⚠️ Überspringe {} (nicht gefunden)", dir));
+            log.event(BackupEvent::ItemSkipped { path: dir.clone(), bytes: 0, error: Some("nicht gefunden".to_string()) });
+            skipped_directories.push(DirectoryIssue {
+                path: dir.clone(),
+                reason: "nicht gefunden".to_string(),
+                detail: None,
+            });
+            continue;
+        }
+        
+        // Privacy-protected folders would only show up as tar warnings in an otherwise "successful" archive
+        // Only a directory inside a protected folder fails; protected folders inside it are excluded below
+        let (blocked, blocked_children): (Vec<PathBuf>, Vec<PathBuf>) = unreadable_protected_dirs(&expanded, &owner_home(owner.as_deref(), &home))
+            .into_iter()
+            .partition(|blocked| *blocked == expanded);
+        if !blocked.is_empty() {
+            let detail = expanded.display().to_string();
+            log.emit(format!("❌ {} nicht gesichert: {} ({})", dir, FDA_REQUIRED_REASON, detail));
+            log.event(BackupEvent::ItemFailed { path: dir.clone(), bytes: source_sizes[i], error: Some(FDA_REQUIRED_REASON.to_string()) });
+            failed_directories.push(DirectoryIssue {
+                path: dir.clone(),
+                reason: FDA_REQUIRED_REASON.to_string(),
+                detail: Some(detail),
+            });
+            continue;
+        }
+        
+        let is_file = expanded.is_file();
+        
+        let readable = if is_file {
+            fs::File::open(&expanded).map(|_| ())
+        } else {
+            fs::read_dir(&expanded).map(|_| ())
+        };
+        if let Err(e) = readable {
+            log.emit(format!("⚠️ Überspringe {} (keine Leseberechtigung)", dir));
+            log.event(BackupEvent::ItemSkipped { path: dir.clone(), bytes: 0, error: Some(format!("keine Leseberechtigung: {}", e)) });
+            skipped_directories.push(DirectoryIssue {
+                path: dir.clone(),
+                reason: "keine Leseberechtigung".to_string(),
+                detail: Some(e.to_string()),
+            });
+            continue;
+        }
+        
+        let name = expanded.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "backup".to_string());
+        
+        // Never archive the backup folder itself: it keeps growing while tar reads it
+        let mut excludes = exclude_patterns.clone();
+        if let (Ok(source), Ok(suite)) = (fs::canonicalize(&expanded), fs::canonicalize(&suite_root)) {
+            if source.starts_with(&suite) {
+                log.emit(format!("⚠️ Überspringe {} (liegt im Backup-Ordner {})", dir, suite_root.display()));
+                log.event(BackupEvent::ItemSkipped { path: dir.clone(), bytes: 0, error: Some("liegt im Backup-Ordner".to_string()) });
+                skipped_directories.push(DirectoryIssue {
+                    path: dir.clone(),
+                    reason: "liegt im Backup-Ordner".to_string(),
+                    detail: None,
+                });
+                continue;
+            }
+            if let Ok(relative) = suite.strip_prefix(&source) {
+                log.emit(format!("{} enthält den Backup-Ordner – er wird ausgeschlossen", dir));
+                excludes.push(format!("{}/{}", name, relative.to_string_lossy()));
+            }
+        }
+        
+        for child in &blocked_children {
+            if let Ok(relative) = child.strip_prefix(&expanded) {
+                log.emit(format!("⚠️ {}: {} wird ausgelassen ({})", dir, child.display(), FDA_REQUIRED_REASON));
+                excludes.push(format!("{}/{}", name, relative.to_string_lossy()));
+            }
+        }
+        
+        // Per-directory rules are scoped to this directory's name inside the archive
+        excludes.extend(entry.excludes.iter()
+            .map(|p| p.trim().trim_matches('/'))
+            .filter(|p| !p.is_empty())
+            .map(|p| format!("{}/{}", name, p)));
+        let mut includes = Vec::new();
+        if !is_file && !entry.includes.is_empty() {
+            for include in entry.includes.iter().map(|p| p.trim().trim_matches('/')).filter(|p| !p.is_empty()) {
+                if expanded.join(include).exists() {
+                    includes.push(include.to_string());
+                } else {
+                    log.emit(format!("⚠️ {}: Einschlusspfad {} nicht gefunden", dir, include));
+                }
+            }
+            if includes.is_empty() {
+                log.emit(format!("⚠️ Überspringe {} (keiner der Einschlusspfade vorhanden)", dir));
+                log.event(BackupEvent::ItemSkipped { path: dir.clone(), bytes: 0, error: Some("keiner der Einschlusspfade vorhanden".to_string()) });
+                skipped_directories.push(DirectoryIssue {
+                    path: dir.clone(),
+                    reason: "keiner der Einschlusspfade vorhanden".to_string(),
+                    detail: Some(entry.includes.join(", ")),
+                });
+                continue;
+            }
+            log.emit(format!("{}: sichere nur {}", dir, includes.join(", ")));
+        }
+        
+        // Single files are written by the in-process gzip encoder;
+        // directories of photos, videos and archives are stored without compression
+        let item_format = if is_file {
+            ArchiveFormat::Gz
+        } else if config.adaptive_compression && is_mostly_precompressed(&expanded) {
+            log.emit(format!("{} enthält überwiegend komprimierte Dateien – speichere ohne Kompression", dir));
+            ArchiveFormat::Tar
+        } else {
+            format
+        };
+        let stem = item_archive_stem(&expanded, owner.as_deref());
+        let archive_name = format!("{}.{}", stem, item_format.extension());
+        let archive_path = backup_root.join(&archive_name);
+        let source_size = source_sizes[i];
+        
+        // tar can't filter by size, so files above the cap are excluded one by one
+        if let Some(max_mb) = config.max_file_size_mb.filter(|mb| *mb > 0 && !is_file) {
+            let (oversized, bytes) = oversized_files(&expanded, max_mb * 1024 * 1024, &exclude_patterns);
+            if !oversized.is_empty() {
+                log.emit(format!(
+                    "{}: {} Dateien über {} MB ausgelassen ({:.1} MB)",
+                    dir, oversized.len(), max_mb, bytes as f64 / (1024.0 * 1024.0)
+                ));
+                oversized_count += oversized.len();
+                oversized_bytes += bytes;
+                excludes.extend(oversized.iter().map(|file| format!("{}/{}", name, escape_tar_pattern(file))));
+            }
+        }
+        
+        // Resume: reuse archives finished in the interrupted run if they are still intact
+        if let Some(done) = completed_items.iter().find(|item| item.path == *dir) {
+            let intact = archive_files(std::slice::from_ref(done)).iter()
+                .all(|file| hash_file(&backup_root.join(&file.archive), hash_algorithm).map(|h| h == file.hash).unwrap_or(false));
+            if intact {
+                log.emit(format!("Überspringe {} (bereits gesichert)", dir));
+                log.push_completed(&mut items, done.clone());
+                bytes_done += source_size;
+                continue;
+            }
+        }
+        
+        // Many tiny similar files compress far better with a dictionary trained on a sample of them.
+        // The dictionary holds file content, so encrypted backups don't use one; FAT volumes neither.
+        let mut dictionary = None;
+        if config.zstd_dictionary && item_format == ArchiveFormat::Zst && !is_file && passphrase.is_none()
+            && volume_size.is_none() && is_dictionary_candidate(&expanded)
+        {
+            let dictionary_path = zstd_dictionary_path(&archive_path);
+            match train_zstd_dictionary(&expanded, &dictionary_path) {
+                Ok(()) => {
+                    log.emit(format!("{}: zstd-Wörterbuch für viele kleine Dateien trainiert", dir));
+                    dictionary = Some(dictionary_path);
+                }
+                Err(e) => log.emit(format!("⚠️ {}: zstd-Wörterbuch konnte nicht trainiert werden ({}) – komprimiere ohne", dir, e)),
+            }
+        }
+        
+        let job_progress = 15 + (60 * (i + 1) / total);
+        
+        // Very large directories are archived as several parts that the workers build side by side
+        let split_min_bytes = config.split_directory_min_gb * 1024 * 1024 * 1024;
+        let groups = if split_min_bytes > 0 && volume_size.is_none() && !is_file && includes.is_empty() && source_size >= split_min_bytes {
+            split_directory_children(&expanded, config.split_directory_parts, &excludes, max_file_bytes(config))
+        } else {
+            Vec::new()
+        };
+        if groups.len() > 1 {
+            log.emit(format!("{} wird in {} Teilarchive aufgeteilt", dir, groups.len()));
+            let count = groups.len();
+            for (index, (children, part_size)) in groups.into_iter().enumerate() {
+                let archive_name = format!("{}-part{}.{}", stem, index + 1, item_format.extension());
+                let part_path = backup_root.join(&archive_name);
+                // Every part archive is read on its own, so each gets a copy of the dictionary
+                let part_dictionary = dictionary.as_ref().and_then(|d| {
+                    let copy = zstd_dictionary_path(&part_path);
+                    fs::copy(d, &copy).ok().map(|_| copy)
+                });
+                jobs.push(DirectoryJob {
+                    dir: dir.clone(),
+                    expanded: expanded.clone(),
+                    name: name.clone(),
+                    is_file,
+                    item_format,
+                    archive_path: part_path,
+                    archive_name,
+                    source_size: part_size,
+                    excludes: excludes.clone(),
+                    includes: children,
+                    part: Some(JobPart { index, count, dir_source_size: source_size }),
+                    progress: job_progress,
+                    user: owner.clone(),
+                    dictionary: part_dictionary,
+                });
+            }
+            if let Some(d) = &dictionary {
+                let _ = fs::remove_file(d);
+            }
+            continue;
+        }
+        
+        jobs.push(DirectoryJob {
+            dir: dir.clone(),
+            expanded,
+            name,
+            is_file,
+            item_format,
+            archive_name,
+            archive_path,
+            source_size,
+            excludes,
+            includes,
+            part: None,
+            progress: job_progress,
+            user: owner,
+            dictionary,
+        });
+    }
+    let fda_blocked = failed_directories.iter().filter(|d| d.reason == FDA_REQUIRED_REASON).count();
+    if fda_blocked > 0 {
+        log.emit(format!("⚠️ {} Verzeichnisse benötigen Full Disk Access – nach dem Freigeben das Backup erneut starten", fda_blocked));
+    }
+    if oversized_count > 0 {
+        log.emit(format!(
+            "Größenlimit: insgesamt {} Dateien ({:.1} MB) nicht gesichert",
+            oversized_count, oversized_bytes as f64 / (1024.0 * 1024.0)
+        ));
+    }
+    
+    // Scale an archive's growth back to source bytes using the assumed ratio
+    let ratio = if config.estimated_compression_ratio > 0.0 { config.estimated_compression_ratio } else { default_compression_ratio() };
+    let next_job = AtomicUsize::new(0);
+    // Stops the workers when the backup can't continue (e.g. encryption failed)
+    let stop = AtomicBool::new(false);
+    let mut written = vec![0u64; jobs.len()];
+    // Parts of split directories finished so far (with their incomplete-reason), and directories with a failed part
+    let mut finished_parts: std::collections::HashMap<String, Vec<Option<FinishedPart>>> = std::collections::HashMap::new();
+    let mut failed_splits: std::collections::HashSet<String> = std::collections::HashSet::new();
+    // Source file hashes per directory, collected over all parts until the item is complete
+    let mut source_hashes: std::collections::HashMap<String, SourceHashes> = std::collections::HashMap::new();
+    let mut cancelled = false;
+    let mut fatal: Option<String> = None;
+    
+    std::thread::scope(|scope| {
+        let (event_tx, event_rx) = mpsc::channel::<DirectoryJobEvent>();
+        for _ in 0..parallel_jobs.min(jobs.len()) {
+            let event_tx = event_tx.clone();
+            let (jobs, next_job, stop) = (&jobs, &next_job, &stop);
+            let target_root = Path::new(&target_path);
+            let follow_symlinks = config.follow_symlinks;
+            let generate_source_manifest = config.generate_source_manifest;
+            scope.spawn(move || loop {
+                // Wait while paused (running tar processes are stopped via SIGSTOP in pause_backup)
+                while BACKUP_PAUSED.load(Ordering::SeqCst) && !BACKUP_CANCELLED.load(Ordering::SeqCst) {
+                    std::thread::sleep(std::time::Duration::from_millis(500));
+                }
+                if BACKUP_CANCELLED.load(Ordering::SeqCst) || stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                let index = next_job.fetch_add(1, Ordering::SeqCst);
+                let Some(job) = jobs.get(index) else { break };
+                
+                // Stop before tar runs into ENOSPC halfway through the archive
+                if let Some(free_gb) = low_free_space_gb(target_root) {
+                    stop.store(true, Ordering::SeqCst);
+                    let _ = event_tx.send(DirectoryJobEvent::LowSpace(index, free_gb));
+                    break;
+                }
+                
+                let _ = event_tx.send(DirectoryJobEvent::Started(index));
+                let started = Local::now();
+                let result = if job.is_file {
+                    write_files_archive(&[(&job.expanded, &job.name)], &job.archive_path, follow_symlinks, volume_size, hash_algorithm)
+                } else {
+                    with_archive_monitor(&job.archive_path, |size| {
+                        let _ = event_tx.send(DirectoryJobEvent::Written(index, size));
+                    }, || create_tar_gz(&job.expanded, &job.archive_path, &job.excludes, &job.includes, follow_symlinks, job.item_format, volume_size, job.dictionary.as_deref(), hash_algorithm, throttle))
+                };
+                // Source files are hashed here so the jobs do it side by side
+                let source_files = (generate_source_manifest && result.is_ok())
+                    .then(|| hash_source_files(&job.expanded, &job.excludes, &job.includes, follow_symlinks, hash_algorithm));
+                let _ = event_tx.send(DirectoryJobEvent::Finished(index, result, source_files, started));
+            });
+        }
+        drop(event_tx);
+        
+        // Logging, progress and bookkeeping stay on this thread, in the order jobs report back
+        for event in event_rx {
+            match event {
+                DirectoryJobEvent::Started(index) => {
+                    let job = &jobs[index];
+                    log.emit(format!("Archiviere {} ...", job.label()));
+                    match job.part {
+                        Some(part) if part.index > 0 => {}
+                        Some(part) => log.event(BackupEvent::ItemStarted { path: job.dir.clone(), bytes: part.dir_source_size }),
+                        None => log.event(BackupEvent::ItemStarted { path: job.dir.clone(), bytes: job.source_size }),
+                    }
+                    let (bytes_per_second, eta_seconds) = transfer_rate(bytes_done, bytes_total, archive_start.elapsed().as_secs_f64());
+                    sink.progress_transfer(job.progress, &format!("Archiviere {}...", job.name), TransferProgress {
+                        bytes_done,
+                        bytes_total,
+                        bytes_per_second,
+                        eta_seconds,
+                    });
+                }
+                DirectoryJobEvent::LowSpace(index, free_gb) => {
+                    let job = &jobs[index];
+                    if fatal.is_none() {
+                        log.emit(format!("❌ Zu wenig Speicherplatz auf dem Ziel ({:.2} GB frei) – breche vor {} ab", free_gb, job.dir));
+                        let error = format!(
+                            "Nicht genügend Speicherplatz auf dem Ziel: nur noch {:.2} GB frei (mindestens {:.0} GB erforderlich). {} und folgende Verzeichnisse wurden nicht gesichert.",
+                            free_gb, BACKUP_FREE_SPACE_MARGIN_GB, job.dir
+                        );
+                        log.event(BackupEvent::ItemFailed { path: job.dir.clone(), bytes: job.source_size, error: Some(error.clone()) });
+                        fatal = Some(error);
+                    }
+                }
+                DirectoryJobEvent::Written(index, size) => {
+                    let job = &jobs[index];
+                    written[index] = size;
+                    let in_flight: u64 = jobs.iter().zip(&written)
+                        .map(|(job, size)| ((*size as f64 / ratio) as u64).min(job.source_size))
+                        .sum();
+                    let current = bytes_done + in_flight;
+                    let estimated_archive = (job.source_size as f64 * ratio) as u64;
+                    let (bytes_per_second, eta_seconds) = transfer_rate(current, bytes_total, archive_start.elapsed().as_secs_f64());
+                    sink.progress_transfer(job.progress, &format!("Archiviere {}... ({} / ~{} MB)", job.name, size / (1024 * 1024), estimated_archive / (1024 * 1024)), TransferProgress {
+                        bytes_done: current,
+                        bytes_total,
+                        bytes_per_second,
+                        eta_seconds,
+                    });
+                }
+                DirectoryJobEvent::Finished(index, archived, source_files, started) => {
+                    let job = &jobs[index];
+                    written[index] = 0;
+                    
+                    // After a cancel or fatal error only clean up what the other jobs leave behind
+                    if cancelled || fatal.is_some() || BACKUP_CANCELLED.load(Ordering::SeqCst) {
+                        cancelled |= BACKUP_CANCELLED.load(Ordering::SeqCst);
+                        remove_archive(&job.archive_path);
+                        continue;
+                    }
+                    
+                    let archived = match archived {
+                        Ok(archived) => archived,
+                        Err(e) => {
+                            remove_archive(&job.archive_path);
+                            // A full target fails every following job too, so stop with a clear message
+                            if let Some(free_gb) = low_free_space_gb(Path::new(&target_path)) {
+                                stop.store(true, Ordering::SeqCst);
+                                log.emit(format!("❌ Ziel voll beim Archivieren von {} ({:.2} GB frei)", job.dir, free_gb));
+                                let error = format!(
+                                    "Nicht genügend Speicherplatz auf dem Ziel: nur noch {:.2} GB frei. Das unvollständige Archiv von {} wurde entfernt.",
+                                    free_gb, job.dir
+                                );
+                                log.event(BackupEvent::ItemFailed { path: job.dir.clone(), bytes: job.source_size, error: Some(error.clone()) });
+                                fatal = Some(error);
+                                continue;
+                            }
+                            log.emit(format!("❌ {} nicht gesichert (tar-Fehler): {}", job.label(), e));
+                            // One failed part makes the whole split directory unusable
+                            if job.part.is_some() {
+                                if !failed_splits.insert(job.dir.clone()) {
+                                    continue;
+                                }
+                                source_hashes.remove(&job.dir);
+                                for (part, _) in finished_parts.remove(&job.dir).into_iter().flatten().flatten() {
+                                    let _ = fs::remove_file(backup_root.join(&part.archive));
+                                }
+                            }
+                            log.event(BackupEvent::ItemFailed { path: job.dir.clone(), bytes: job.source_size, error: Some(e.clone()) });
+                            failed_directories.push(DirectoryIssue {
+                                path: job.dir.clone(),
+                                reason: "tar-Fehler".to_string(),
+                                detail: Some(e),
+                            });
+                            continue;
+                        }
+                    };
+                    let incomplete = archived.issue(&job.dir).map(|issue| {
+                        log.emit(format!("⚠️ {} unvollständig gesichert ({})", job.dir, issue.reason));
+                        let reason = issue.reason.clone();
+                        failed_directories.push(issue);
+                        reason
+                    });
+                    
+                    // Every volume of a multi-volume archive is encrypted and hashed on its own
+                    if !archived.volumes.is_empty() {
+                        log.emit(format!("{} in {} Teildateien aufgeteilt", job.dir, archived.volumes.len()));
+                    }
+                    let finalized = finalize_tar_archive(&backup_root, &job.archive_path, &job.archive_name, &archived, passphrase, hash_algorithm)
+                        .and_then(|volumes| {
+                            let dictionary_hash = job.dictionary.as_deref().map(|d| hash_file(d, hash_algorithm)).transpose()?;
+                            Ok((volumes, dictionary_hash))
+                        });
+                    let (mut volumes, dictionary_hash) = match finalized {
+                        Ok(finalized) => finalized,
+                        Err(e) => {
+                            stop.store(true, Ordering::SeqCst);
+                            log.event(BackupEvent::ItemFailed { path: job.dir.clone(), bytes: job.source_size, error: Some(e.clone()) });
+                            fatal = Some(e);
+                            continue;
+                        }
+                    };
+                    let first = volumes.remove(0);
+                    let archive_path = backup_root.join(&first.archive);
+                    let multi_volume = !volumes.is_empty();
+                    
+                    let item = BackupItem {
+                        path: job.dir.clone(),
+                        archive: first.archive,
+                        hash: first.hash,
+                        archive_size_bytes: first.archive_size_bytes + volumes.iter().map(|v| v.archive_size_bytes).sum::<u64>(),
+                        source_size_bytes: job.source_size,
+                        format: Some(job.item_format),
+                        duration_seconds: elapsed_seconds(started),
+                        parts: volumes,
+                        multi_volume,
+                        user: job.user.clone(),
+                        dictionary_hash,
+                    };
+                    bytes_done += job.source_size;
+                    if let Some(files) = source_files {
+                        source_hashes.entry(job.dir.clone()).or_default().extend(files);
+                    }
+                    
+                    let completed = match job.part {
+                        None => Some((item, incomplete)),
+                        Some(_) if failed_splits.contains(&job.dir) => {
+                            let _ = fs::remove_file(&archive_path);
+                            source_hashes.remove(&job.dir);
+                            None
+                        }
+                        Some(part) => {
+                            let finished = finished_parts.entry(job.dir.clone()).or_insert_with(|| vec![None; part.count]);
+                            finished[part.index] = Some((item, incomplete));
+                            if finished.iter().all(Option::is_some) {
+                                let (parts, issues): (Vec<_>, Vec<_>) = finished_parts.remove(&job.dir)
+                                    .into_iter().flatten().flatten().unzip();
+                                let issues: Vec<String> = issues.into_iter().flatten().collect();
+                                merge_split_parts(parts, part.dir_source_size)
+                                    .map(|item| (item, (!issues.is_empty()).then(|| issues.join(", "))))
+                            } else {
+                                None
+                            }
+                        }
+                    };
+                    if let Some((item, incomplete)) = completed {
+                        if let Some(files) = source_hashes.remove(&item.path) {
+                            if let Err(e) = write_source_manifest(&backup_root, &item.path, files, hash_algorithm) {
+                                log.emit(format!("⚠️ Quell-Manifest für {} konnte nicht geschrieben werden: {}", item.path, e));
+                            }
+                        }
+                        record_completed_item(&backup_root, &item);
+                        log.event(BackupEvent::ItemCompleted { path: item.path.clone(), bytes: item.source_size_bytes, error: incomplete });
+                        items.push(item);
+                    }
+                    
+                    let (bytes_per_second, eta_seconds) = transfer_rate(bytes_done, bytes_total, archive_start.elapsed().as_secs_f64());
+                    sink.progress_transfer(job.progress, &format!("{} archiviert", job.name), TransferProgress {
+                        bytes_done,
+                        bytes_total,
+                        bytes_per_second,
+                        eta_seconds,
+                    });
+                }
+            }
+        }
+    });
+    
+    if cancelled || BACKUP_CANCELLED.load(Ordering::SeqCst) {
+        return Err(backup_cancelled(log, sink));
+    }
+    if let Some(e) = fatal {
+        return Err(e);
+    }
+    // Parallel jobs finish in any order; keep the configured directory order
+    items.sort_by_key(|item| directories.iter().position(|d| d.path == item.path).unwrap_or(usize::MAX));
+    
+
+    if config.backup_special_items {
+        // Archive Homebrew packages as a restorable item
+        if let Ok(brewfile) = get_brew_packages() {
+            let brew_archive_name = &format!("homebrew-packages.{}", ArchiveFormat::Gz.extension());
+            let brew_archive_path = backup_root.join(brew_archive_name);
+            let staging = TempDirGuard::create("macos-backup-homebrew")?;
+            let brew_temp = staging.join("homebrew_packages.txt");
+            let _ = fs::write(&brew_temp, &brewfile);
+            // The services list travels in the same archive so restore_homebrew_packages can start them again
+            let services_temp = staging.join("brew_services.json");
+            if let Some(services) = &brew_services {
+                let _ = fs::write(&services_temp, serde_json::to_string_pretty(services).unwrap_or_default());
+            }
+        
+            if brew_temp.exists() {
+                let mut files: Vec<(&Path, &str)> = vec![(&brew_temp, "homebrew_packages.txt")];
+                if services_temp.exists() {
+                    files.push((&services_temp, "brew_services.json"));
+                }
+                let source_size = files.iter().map(|(path, _)| fs::metadata(path).map(|m| m.len()).unwrap_or(0)).sum();
+                let hash = write_files_archive(&files, &brew_archive_path, false, None, hash_algorithm)?.hash;
+            
+                let (brew_archive_name, brew_archive_path, hash) = finalize_archive(&brew_archive_path, brew_archive_name, hash, passphrase, hash_algorithm)?;
+                let archive_size = fs::metadata(&brew_archive_path).map(|m| m.len()).unwrap_or(0);
+            
+                log.push_completed(&mut items, BackupItem {
+                    path: "homebrew-packages".to_string(),
+                    archive: brew_archive_name,
+                    hash,
+                    archive_size_bytes: archive_size,
+                    source_size_bytes: source_size,
+                    format: Some(ArchiveFormat::Gz),
+                    duration_seconds: 0.0,
+                    parts: Vec::new(),
+                    multi_volume: false,
+                    user: None,
+                    dictionary_hash: None,
+                });
+                log.emit(format!("Homebrew-Pakete archiviert: {} Bytes", source_size));
+            }
+        }
+    
+        // Archive MAS apps as a restorable item
+        {
+            let staging = TempDirGuard::create("macos-backup-mas")?;
+            let mas_temp = staging.join("mas_apps.txt");
+            if let Ok(brewfile) = get_brew_packages() {
+                let mas_lines: Vec<&str> = brewfile.lines()
+                    .filter(|line| line.trim().starts_with("mas "))
+                    .collect();
+                if !mas_lines.is_empty() {
+                    let mas_content = mas_lines.join("
+");
+                    let _ = fs::write(&mas_temp, &mas_content);
+                }
+            }
+        
+            if mas_temp.exists() {
+                let mas_archive_name = &format!("mas-apps.{}", ArchiveFormat::Gz.extension());
+                let mas_archive_path = backup_root.join(mas_archive_name);
+                let mut source_size = fs::metadata(&mas_temp).map(|m| m.len()).unwrap_or(0);
+            
+                // Installed versions, compared against the restored apps afterwards
+                let versions_temp = staging.join("mas_apps.json");
+                let mut files: Vec<(&Path, &str)> = vec![(&mas_temp, "mas_apps.txt")];
+                if !mas_versions.is_empty() {
+                    if let Ok(json) = serde_json::to_string_pretty(&mas_versions) {
+                        if fs::write(&versions_temp, json).is_ok() {
+                            source_size += fs::metadata(&versions_temp).map(|m| m.len()).unwrap_or(0);
+                            files.push((&versions_temp, "mas_apps.json"));
+                        }
+                    }
+                }
+            
+                let hash = write_files_archive(&files, &mas_archive_path, false, None, hash_algorithm)?.hash;
+            
+                let (mas_archive_name, mas_archive_path, hash) = finalize_archive(&mas_archive_path, mas_archive_name, hash, passphrase, hash_algorithm)?;
+                let archive_size = fs::metadata(&mas_archive_path).map(|m| m.len()).unwrap_or(0);
+            
+                log.push_completed(&mut items, BackupItem {
+                    path: "mas-apps".to_string(),
+                    archive: mas_archive_name,
+                    hash,
+                    archive_size_bytes: archive_size,
+                    source_size_bytes: source_size,
+                    format: Some(ArchiveFormat::Gz),
+                    duration_seconds: 0.0,
+                    parts: Vec::new(),
+                    multi_volume: false,
+                    user: None,
+                    dictionary_hash: None,
+                });
+                log.emit(format!("MAS Apps archiviert: {} Bytes", source_size));
+            }
+        }
+    
+        // Archive VS Code extensions as a restorable item
+        if let Ok(extensions) = get_vscode_extensions() {
+            let vscode_archive_name = &format!("vscode-extensions.{}", ArchiveFormat::Gz.extension());
+            let vscode_archive_path = backup_root.join(vscode_archive_name);
+            let staging = TempDirGuard::create("macos-backup-vscode")?;
+            let vscode_temp = staging.join("vscode_extensions.txt");
+            let vscode_content = extensions.join("
+");
+            let _ = fs::write(&vscode_temp, &vscode_content);
+        
+            if vscode_temp.exists() {
+                let source_size = fs::metadata(&vscode_temp).map(|m| m.len()).unwrap_or(0);
+                let hash = write_single_file_archive(&vscode_temp, "vscode_extensions.txt", &vscode_archive_path, hash_algorithm)?;
+            
+                let (vscode_archive_name, vscode_archive_path, hash) = finalize_archive(&vscode_archive_path, vscode_archive_name, hash, passphrase, hash_algorithm)?;
+                let archive_size = fs::metadata(&vscode_archive_path).map(|m| m.len()).unwrap_or(0);
+            
+                log.push_completed(&mut items, BackupItem {
+                    path: "vscode-extensions".to_string(),
+                    archive: vscode_archive_name,
+                    hash,
+                    archive_size_bytes: archive_size,
+                    source_size_bytes: source_size,
+                    format: Some(ArchiveFormat::Gz),
+                    duration_seconds: 0.0,
+                    parts: Vec::new(),
+                    multi_volume: false,
+                    user: None,
+                    dictionary_hash: None,
+                });
+                log.emit(format!("VS Code Extensions archiviert: {} Extensions", extensions.len()));
+            }
+        }
+
+        // Optional: Backup Homebrew Download Cache for offline installations (limit from config)
+        if config.backup_homebrew_cache {
+            log.emit(format!("Prüfe Homebrew-Cache (Limit {} GB)...", config.homebrew_cache_max_gb));
+        
+            if let Some(cache_dir) = find_homebrew_cache() {
+                let max_cache_size = config.homebrew_cache_max_gb * 1024 * 1024 * 1024;
+                let mut cache_size = compute_directory_size(&cache_dir);
+            
+                // Over the limit: stage only the newest downloads that fit, keeping the directory name
+                let partial = if cache_size > max_cache_size {
+                    let staging = TempDirGuard::create("homebrew_cache_partial")?;
+                    let staged_dir = staging.join(cache_dir.file_name().unwrap_or_else(|| std::ffi::OsStr::new("Homebrew")));
+                    let staged_size = stage_newest_files(&cache_dir, &staged_dir, max_cache_size);
+                    log.emit(format!("⚠️ Homebrew-Cache zu groß ({:.1} GB > {} GB max), sichere nur die neuesten Downloads ({:.1} GB)",
+                        cache_size as f64 / (1024.0 * 1024.0 * 1024.0), config.homebrew_cache_max_gb, staged_size as f64 / (1024.0 * 1024.0 * 1024.0)));
+                    cache_size = staged_size;
+                    Some((staging, staged_dir))
+                } else {
+                    None
+                };
+                let cache_source = partial.as_ref().map(|(_, dir)| dir.as_path()).unwrap_or(&cache_dir);
+            
+                if cache_size > 0 {
+                    let cache_archive_name = &format!("homebrew-cache.{}", format.extension());
+                    let cache_archive_path = backup_root.join(cache_archive_name);
+                
+                    log.emit(format!("Archiviere Homebrew-Cache ({:.1} MB)...", cache_size as f64 / (1024.0 * 1024.0)));
+                
+                    let cache_start = Local::now();
+                    if let Ok(archived) = create_tar_gz(cache_source, &cache_archive_path, &system_excludes, &[], false, format, volume_size, None, hash_algorithm, throttle) {
+                        let mut volumes = finalize_tar_archive(&backup_root, &cache_archive_path, cache_archive_name, &archived, passphrase, hash_algorithm)?;
+                        let first = volumes.remove(0);
+                        let archive_size = first.archive_size_bytes + volumes.iter().map(|v| v.archive_size_bytes).sum::<u64>();
+                        log.push_completed(&mut items, BackupItem {
+                            path: "homebrew-cache".to_string(),
+                            archive: first.archive,
+                            hash: first.hash,
+                            archive_size_bytes: archive_size,
+                            source_size_bytes: cache_size,
+                            format: Some(format),
+                            duration_seconds: elapsed_seconds(cache_start),
+                            multi_volume: !volumes.is_empty(),
+                            parts: volumes,
+                            user: None,
+                            dictionary_hash: None,
+                        });
+                        log.emit(format!("✅ Homebrew-Cache archiviert: {:.1} MB", archive_size as f64 / (1024.0 * 1024.0)));
+                    }
+                }
+            }
+        }
+
+        // Archive login items as a restorable item (LaunchAgents go with the scheduled tasks)
+        if !login_items.is_empty() {
+            let staging = TempDirGuard::create("macos-backup-startup")?;
+            let startup_temp = staging.join("startup_items");
+            let _ = fs::create_dir_all(&startup_temp);
+            let _ = fs::write(startup_temp.join("login_items.txt"), login_items.join("\n"));
+        
+            match archive_special_dir(&startup_temp, &backup_root, "startup-items", passphrase, &[], format, hash_algorithm, volume_size, throttle) {
+                Ok(item) => {
+                    log.push_completed(&mut items, item);
+                    log.emit("✅ Startobjekte archiviert");
+                }
+                Err(e) => {
+                    log.emit(format!("⚠️ Startobjekte konnten nicht archiviert werden: {}", e));
+                }
+            }
+        }
+
+        // Archive crontab and LaunchAgents as a restorable item
+        let crontab = read_crontab();
+        if !crontab.trim().is_empty() {
+            let _ = fs::write(inventory_root.join("crontab.txt"), &crontab);
+        }
+        if !crontab.trim().is_empty() || !launch_agents.is_empty() {
+            let staging = TempDirGuard::create("macos-backup-tasks")?;
+            let tasks_temp = staging.join("scheduled_tasks");
+            let _ = fs::create_dir_all(tasks_temp.join("LaunchAgents"));
+            let _ = fs::write(tasks_temp.join("crontab.txt"), &crontab);
+            for agent in &launch_agents {
+                if let Some(name) = agent.file_name() {
+                    let _ = fs::copy(agent, tasks_temp.join("LaunchAgents").join(name));
+                }
+            }
+        
+            match archive_special_dir(&tasks_temp, &backup_root, "scheduled-tasks", passphrase, &[], format, hash_algorithm, volume_size, throttle) {
+                Ok(item) => {
+                    log.push_completed(&mut items, item);
+                    log.emit(format!("✅ Geplante Aufgaben archiviert: {} Cron-Einträge, {} LaunchAgents",
+                        crontab.lines().filter(|l| !l.trim().is_empty() && !l.trim().starts_with('#')).count(),
+                        launch_agents.len()));
+                }
+                Err(e) => {
+                    log.emit(format!("⚠️ Geplante Aufgaben konnten nicht archiviert werden: {}", e));
+                }
+            }
+        }
+
+        // Archive SSH/GPG configuration (private keys only on request)
+        if config.include_private_keys && passphrase.is_none() {
+            log.emit("⚠️ Private SSH-Schlüssel werden unverschlüsselt gesichert");
+        }
+        match backup_ssh_config(&backup_root, passphrase, config.include_private_keys, format, hash_algorithm, volume_size, throttle) {
+            Ok(Some((item, included))) => {
+                log.push_completed(&mut items, item);
+                log.emit(format!("✅ SSH/GPG-Konfiguration archiviert: {}", included.join(", ")));
+                if !config.include_private_keys {
+                    log.emit("ℹ️ Private SSH-Schlüssel wurden nicht gesichert");
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                log.emit(format!("⚠️ SSH-Konfiguration konnte nicht archiviert werden: {}", e));
+            }
+        }
+
+        // Archive shell dotfiles and the login shell
+        match backup_dotfiles(&backup_root, &inventory_root, &config.dotfiles, passphrase, format, hash_algorithm, volume_size, throttle) {
+            Ok(Some((item, included))) => {
+                log.push_completed(&mut items, item);
+                log.emit(format!("✅ Dotfiles archiviert: {}", included.join(", ")));
+            }
+            Ok(None) => {}
+            Err(e) => {
+                log.emit(format!("⚠️ Dotfiles konnten nicht archiviert werden: {}", e));
+            }
+        }
+
+        // Optional: Backup fonts
+        if config.backup_fonts {
+            log.emit("Sichere Schriften...");
+            match backup_fonts(&backup_root, passphrase, format, hash_algorithm, volume_size, throttle) {
+                Ok(Some((item, count))) => {
+                    log.push_completed(&mut items, item);
+                    log.emit(format!("✅ Schriften archiviert: {} Schriftdateien", count));
+                }
+                Ok(None) => {
+                    log.emit("Keine eigenen Schriften gefunden");
+                }
+                Err(e) => {
+                    log.emit(format!("⚠️ Schriften konnten nicht archiviert werden: {}", e));
+                }
+            }
+        }
+
+        // Optional: Backup Dock layout
+        if config.backup_dock {
+            log.emit("Sichere Dock-Layout...");
+            match backup_dock(&inventory_root, &backup_root, passphrase, format, hash_algorithm, volume_size, throttle) {
+                Ok(item) => {
+                    log.push_completed(&mut items, item);
+                    log.emit("✅ Dock-Layout archiviert");
+                }
+                Err(e) => {
+                    log.emit(format!("⚠️ {}", e));
+                }
+            }
+        }
+
+        // Optional: Backup Terminal/iTerm2 profiles
+        if config.backup_terminal_settings {
+            log.emit("Sichere Terminal-Profile...");
+            match backup_terminal_settings(&backup_root, passphrase, format, hash_algorithm, volume_size, throttle) {
+                Ok(Some((item, included, skipped))) => {
+                    log.push_completed(&mut items, item);
+                    log.emit(format!("✅ Terminal-Profile archiviert: {}", included.join(", ")));
+                    if !skipped.is_empty() {
+                        log.emit(format!("⚠️ Beschädigte Einstellungen übersprungen (plutil -lint): {}", skipped.join(", ")));
+                    }
+                }
+                Ok(None) => {
+                    log.emit("Keine Terminal- oder iTerm2-Einstellungen gefunden");
+                }
+                Err(e) => {
+                    log.emit(format!("⚠️ Terminal-Profile konnten nicht archiviert werden: {}", e));
+                }
+            }
+        }
+
+        // Optional: Export selected system defaults domains
+        if config.backup_system_defaults {
+            log.emit("Sichere Systemeinstellungen (defaults)...");
+            match backup_system_defaults(&inventory_root, &backup_root, &config.system_defaults_domains, passphrase, format, hash_algorithm, volume_size, throttle) {
+                Ok(Some((item, exported))) => {
+                    log.push_completed(&mut items, item);
+                    log.emit(format!("✅ Systemeinstellungen archiviert: {}", exported.join(", ")));
+                    let failed: Vec<&str> = config.system_defaults_domains.iter()
+                        .map(|d| d.trim())
+                        .filter(|d| !d.is_empty() && !exported.iter().any(|e| e == d))
+                        .collect();
+                    if !failed.is_empty() {
+                        log.emit(format!("⚠️ Nicht exportierbare Domains übersprungen: {}", failed.join(", ")));
+                    }
+                }
+                Ok(None) => {
+                    log.emit("⚠️ Keine der konfigurierten defaults-Domains konnte exportiert werden");
+                }
+                Err(e) => {
+                    log.emit(format!("⚠️ Systemeinstellungen konnten nicht archiviert werden: {}", e));
+                }
+            }
+        }
+
+        // Optional: Printers and network configuration
+        if config.backup_system_config {
+            log.emit("Sichere Drucker- und Netzwerkkonfiguration...");
+            match backup_system_config(&inventory_root, &backup_root, passphrase, format, hash_algorithm, volume_size, throttle) {
+                Ok((item, report)) => {
+                    if let Some(item) = item {
+                        log.push_completed(&mut items, item);
+                        log.emit(format!("✅ Systemkonfiguration archiviert: {}", report.captured.join(", ")));
+                    } else {
+                        log.emit("Keine Drucker- oder Netzwerkkonfiguration gefunden");
+                    }
+                    for skipped in report.skipped {
+                        log.emit(format!("ℹ️ Übersprungen: {}", skipped));
+                    }
+                }
+                Err(e) => {
+                    log.emit(format!("⚠️ Systemkonfiguration konnte nicht archiviert werden: {}", e));
+                }
+            }
+        }
+
+        // Optional: Record local git repositories (remote and branch only, the contents live in the remote)
+        if !config.git_repository_roots.is_empty() {
+            log.emit("Suche Git-Repositories...");
+            let repositories = find_git_repositories(&config.git_repository_roots);
+            match serde_json::to_string_pretty(&repositories) {
+                Ok(json) if fs::write(inventory_root.join("git_repositories.json"), &json).is_ok() => {
+                    log.emit(format!("✅ {} Git-Repositories verzeichnet", repositories.len()));
+                }
+                _ => log.emit("⚠️ Liste der Git-Repositories konnte nicht gespeichert werden"),
+            }
+        }
+
+        // Optional: Backup browser settings (bookmarks and preferences)
+        let browsers = [
+            (BrowserKind::Safari, config.backup_safari_settings),
+            (BrowserKind::Chrome, config.backup_chrome),
+            (BrowserKind::Firefox, config.backup_firefox),
+        ];
+        for (browser, enabled) in browsers {
+            if !enabled {
+                continue;
+            }
+            log.emit(format!("Sichere {}-Einstellungen...", browser.label()));
+            match backup_browser(browser, &backup_root, passphrase, &system_excludes, format, hash_algorithm, volume_size, throttle) {
+                Ok(Some((item, copied_count))) => {
+                    log.push_completed(&mut items, item);
+                    log.emit(format!("✅ {}-Einstellungen archiviert: {} Dateien/Ordner", browser.label(), copied_count));
+                }
+                Ok(None) => {
+                    log.emit(format!("⚠️ Keine {}-Einstellungen gefunden", browser.label()));
+                }
+                Err(e) => {
+                    log.emit(format!("⚠️ {}-Einstellungen: {}", browser.label(), e));
+                }
+            }
+        }
+    }
+
+    // A cancel while the special items were archived must not end in a seemingly complete backup
+    if BACKUP_CANCELLED.load(Ordering::SeqCst) {
+        return Err(backup_cancelled(log, sink));
+    }
+    
+    let end = Local::now();
+    let end_time_str = end.format("%d.%m.%Y %H:%M:%S").to_string();
+    let duration = (end - start).num_seconds() as u64;
+    
+    let total_size: u64 = items.iter().map(|i| i.source_size_bytes).sum();
+    
+    match write_checksum_files(&backup_root, &items, hash_algorithm) {
+        Ok(()) => log.emit(format!("Prüfsummen geschrieben: {} ({} Archive)", hash_algorithm.manifest_name(), items.len())),
+        Err(e) => log.emit(format!("⚠️ Prüfsummen konnten nicht geschrieben werden: {}", e)),
+    }
+    
+    let metadata = BackupMetadata {
+        metadata_version: METADATA_VERSION,
+        timestamp: timestamp.clone(),
+        items,
+        hash_algorithm: hash_algorithm.name().to_string(),
+        total_source_size_bytes: total_size,
+        start_time: start_time_str.clone(),
+        end_time: end_time_str.clone(),
+        duration_seconds: duration,
+        encrypted: passphrase.is_some(),
+        cipher: passphrase.map(|_| format!("{}-pbkdf2", ENCRYPTION_CIPHER)),
+        skipped_directories,
+        failed_directories,
+        source_system,
+    };
+    
+    let metadata_json = serde_json::to_string_pretty(&metadata).map_err(|e| e.to_string())?;
+    write_atomic(&backup_root.join("metadata.json"), &metadata_json).map_err(|e| e.to_string())?;
+    // Second copy next to the inventory, used by `read_metadata` if the primary gets corrupted
+    let _ = write_atomic(&inventory_root.join("metadata.json"), &metadata_json);
+    let _ = fs::remove_file(backup_root.join(IN_PROGRESS_MARKER));
+    
+    // Copy the DMG installer to backup root (always include app in backup)
+    let dmg_filename = "macOS Backup Suite.dmg";
+    let dmg_dest = suite_root.join(dmg_filename);
+    let mut dmg_copied = false;
+    
+    // Look for DMG in the app bundle's Resources folder
+    if let Ok(exe) = std::env::current_exe() {
+        // exe is at: App.app/Contents/MacOS/binary
+        // We need: App.app/Contents/Resources/
+        if let Some(macos_dir) = exe.parent() {
+            let resources_dmg = macos_dir.parent()
+                .map(|contents| contents.join("Resources").join(dmg_filename));
+            
+            if let Some(ref src) = resources_dmg {
+                if src.exists() {
+                    if fs::copy(src, &dmg_dest).is_ok() {
+                        log.emit(format!("✅ App-Installer kopiert: {}", dmg_filename));
+                        dmg_copied = true;
+                    }
+                }
+            }
+        }
+    }
+    
+    // Fallback: Look in multiple locations
+    if !dmg_copied {
+        let home = dirs::home_dir().unwrap_or_default();
+        let dev_paths = [
+            // Development build paths (relative)
+            PathBuf::from("src-tauri/target/release/bundle/dmg/macOS Backup Suite_1.0.0_aarch64.dmg"),
+            PathBuf::from("src-tauri/target/release/bundle/macos/macOS Backup Suite.app/Contents/Resources/macOS Backup Suite.dmg"),
+            // Common development locations (absolute)
+            home.join("Documents/GitHub/macos-backup-tauri/src-tauri/target/release/bundle/dmg/macOS Backup Suite_1.0.0_aarch64.dmg"),
+            home.join("Documents/GitHub/macos-backup-tauri/src-tauri/target/release/bundle/macos/macOS Backup Suite.app/Contents/Resources/macOS Backup Suite.dmg"),
+            // Applications folder
+            PathBuf::from("/Applications/macOS Backup Suite.app/Contents/Resources/macOS Backup Suite.dmg"),
+        ];
+        
+        for dev_path in &dev_paths {
+            if dev_path.exists() {
+                if fs::copy(dev_path, &dmg_dest).is_ok() {
+                    log.emit(format!("✅ App-Installer kopiert: {}", dmg_filename));
+                    dmg_copied = true;
+                    break;
+                }
+            }
+        }
+    }
+    
+    if !dmg_copied {
+        log.emit("ℹ️ App-Installer (DMG) nicht gefunden - führen Sie 'npm run tauri build' aus");
+    }
+    
+    let latest = serde_json::json!({
+        "latest": timestamp,
+        "created_at": end.to_rfc3339()
+    });
+    write_atomic(&suite_root.join("latest.json"), latest.to_string()).map_err(|e| e.to_string())?;
+    
+    let duration_str = if duration >= 3600 {
+        format!("{}h {}m {}s", duration / 3600, (duration % 3600) / 60, duration % 60)
+    } else if duration >= 60 {
+        format!("{}m {}s", duration / 60, duration % 60)
+    } else {
+        format!("{}s", duration)
+    };
+    
+    log.emit(format!("=== Backup beendet: {} (Dauer: {}) ===", end_time_str, duration_str));
+    sink.progress(100, "Backup abgeschlossen.");
+    
+    Ok(metadata)
+}
+
+/// Eject the target after a successful backup if `eject_after_backup` is set. Called once the
+/// `BackupLog` is finished and dropped, so backup.log isn't held open on the volume. The quick
+/// size check otherwise done by the frontend runs here first, as the volume is gone afterwards.
+fn eject_after_backup(target_path: &str, result: &Result<BackupMetadata, String>, sink: &dyn ProgressSink) {
+    let Ok(metadata) = result else {
+        return;
+    };
+    if !load_config().map(|config| config.eject_after_backup).unwrap_or(false) {
+        return;
+    }
+    match quick_verify(target_path.to_string(), metadata.timestamp.clone()) {
+        Ok(check) => {
+            sink.log(&format!("{} {}", if check.success { "✅" } else { "❌" }, check.message));
+            for failure in &check.failed_files {
+                sink.log(&format!("  - {}", failure));
+            }
+        }
+        Err(e) => sink.log(&format!("⚠️ {}", e)),
+    }
+    match eject_volume_at(Path::new(target_path)) {
+        Ok(root) => sink.log(&format!("⏏️ Volume ausgeworfen: {}", root.display())),
+        Err(e) => sink.log(&format!("⚠️ {}", e)),
+    }
+}
+
+#[tauri::command]
+async fn verify_backup(
+    window: tauri::Window,
+    target_path: String,
+    timestamp: String,
+    deep_verify: Option<bool>,
+) -> Result<VerifyResult, String> {
+    run_verify(&target_path, &timestamp, deep_verify.unwrap_or(false), &WindowSink::backup(&window))
+}
+
+/// Hash every archive of a backup against metadata.json, with `deep_verify` also test-extract it
+fn run_verify(target_path: &str, timestamp: &str, deep_verify: bool, sink: &dyn ProgressSink) -> Result<VerifyResult, String> {
+    let backup_path = PathBuf::from(target_path)
+        .join("macos-backup-suite")
+        .join("data")
+        .join(timestamp);
+    
+    let metadata_path = backup_path.join("metadata.json");
+    if !metadata_path.exists() {
+        return Err(format!("Backup nicht gefunden: {}", timestamp));
+    }
+    
+    let metadata = read_metadata(&metadata_path)?;
+    let algorithm = HashAlgorithm::from_name(&metadata.hash_algorithm);
+    let archives = archive_files(&metadata.items);
+    
+    let total_files = archives.len();
+    let bytes_total: u64 = archives.iter().map(|item| item.archive_size_bytes).sum();
+    let mut bytes_done: u64 = 0;
+    let mut verified_files = 0;
+    let mut failed_files = Vec::new();
+    VERIFY_CANCELLED.store(false, Ordering::SeqCst);
+    
+    for (i, item) in archives.iter().enumerate() {
+        if VERIFY_CANCELLED.load(Ordering::SeqCst) {
+            return Ok(verify_cancelled(sink, total_files, verified_files, failed_files));
+        }
+        let archive_path = backup_path.join(&item.archive);
+        let bytes_before = bytes_done;
+        
+        sink.log(&format!("Verifiziere {}/{}: {}", i + 1, total_files, item.archive));
+        
+        if !archive_path.exists() {
+            failed_files.push(format!("{}: Datei nicht gefunden", item.archive));
+            bytes_done += item.archive_size_bytes;
+            continue;
+        }
+        
+        let mut hashed: u64 = 0;
+        let hash_result = hash_file_with_progress(&archive_path, algorithm, Some(&VERIFY_CANCELLED), |bytes| {
+            hashed += bytes;
+            sink.progress(
+                verify_percent(bytes_before + hashed.min(item.archive_size_bytes), bytes_total),
+                &format!("Verifiziere {} ({} / {} MB)", item.archive, hashed / (1024 * 1024), item.archive_size_bytes / (1024 * 1024)),
+            );
+        });
+        if VERIFY_CANCELLED.load(Ordering::SeqCst) {
+            return Ok(verify_cancelled(sink, total_files, verified_files, failed_files));
+        }
+        
+        match hash_result {
+            Ok(computed_hash) => {
+                let dictionary_result = verify_dictionary(&backup_path, item, algorithm);
+                if computed_hash != item.hash {
+                    failed_files.push(format!("{}: Hash stimmt nicht überein (erwartet: {}, berechnet: {})", 
+                        item.archive, &item.hash[..16], &computed_hash[..16]));
+                } else if let Err(e) = dictionary_result {
+                    failed_files.push(e);
+                } else if deep_verify && item.multi_volume {
+                    // A single volume is only a slice of the tar stream
+                    verified_files += 1;
+                    sink.log(&format!("{}: Extraktionstest übersprungen (Teildatei)", item.archive));
+                } else if deep_verify && metadata.encrypted {
+                    // Encrypted archives can't be read without the passphrase
+                    verified_files += 1;
+                    sink.log(&format!("{}: Extraktionstest übersprungen (verschlüsselt)", item.archive));
+                } else if deep_verify {
+                    match test_archive_readable(&archive_path) {
+                        Ok(()) => verified_files += 1,
+                        Err(e) => failed_files.push(format!("{}: Archiv beschädigt (Extraktion fehlgeschlagen): {}", item.archive, e)),
+                    }
+                } else {
+                    verified_files += 1;
+                }
+            }
+            Err(e) => {
+                failed_files.push(format!("{}: Fehler beim Lesen: {}", item.archive, e));
+            }
+        }
+        
+        // Emit progress
+        bytes_done = bytes_before + item.archive_size_bytes;
+        sink.progress(verify_percent(bytes_done, bytes_total), &format!("{}/{} Dateien verifiziert", i + 1, total_files));
+    }
+    
+    let success = failed_files.is_empty();
+    record_verify_outcome(&backup_path, &metadata.items, success);
+    let message = if success {
+        format!("Alle {} Dateien erfolgreich verifiziert!", total_files)
+    } else {
+        format!("{} von {} Dateien fehlgeschlagen", failed_files.len(), total_files)
+    };
+    
+    sink.log(&message);
+    
+    Ok(VerifyResult {
+        success,
+        total_files,
+        verified_files,
+        failed_files,
+        message,
+    })
+}
+
+/// Check the zstd dictionary an archive was compressed with against its recorded hash.
+/// The error is the line reported in `failed_files`.
+fn verify_dictionary(backup_path: &Path, item: &BackupItem, algorithm: HashAlgorithm) -> Result<(), String> {
+    let Some(expected) = &item.dictionary_hash else { return Ok(()) };
+    let dictionary = zstd_dictionary_path(&backup_path.join(&item.archive));
+    if !dictionary.is_file() {
+        return Err(format!("{}: zstd-Wörterbuch nicht gefunden", item.archive));
+    }
+    let computed = hash_file(&dictionary, algorithm).map_err(|e| format!("{}: Fehler beim Lesen des zstd-Wörterbuchs: {}", item.archive, e))?;
+    if computed != *expected {
+        return Err(format!("{}: Hash des zstd-Wörterbuchs stimmt nicht überein", item.archive));
+    }
+    Ok(())
+}
+
+/// Result of a verification stopped with `cancel_verify`
+fn verify_cancelled(sink: &dyn ProgressSink, total_files: usize, verified_files: usize, failed_files: Vec<String>) -> VerifyResult {
+    VERIFY_CANCELLED.store(false, Ordering::SeqCst);
+    let message = "Verifizierung abgebrochen".to_string();
+    sink.log(&format!("⚠️ {}", message));
+    VerifyResult {
+        success: false,
+        total_files,
+        verified_files,
+        failed_files,
+        message,
+    }
+}
+
+#[tauri::command]
+fn cancel_verify() -> Result<(), String> {
+    VERIFY_CANCELLED.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Stop a running restore: no further items are started and the current command is killed
+#[tauri::command]
+fn cancel_restore() -> Result<(), String> {
+    RESTORE_CANCELLED.store(true, Ordering::SeqCst);
+    if let Ok(pids) = RESTORE_PIDS.lock() {
+        // The whole group, so zstd/gzip children of tar stop as well
+        for pid in pids.iter() {
+            unsafe {
+                libc::kill(-(*pid as i32), libc::SIGTERM);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn track_restore_pid(pid: u32) {
+    if let Ok(mut pids) = RESTORE_PIDS.lock() {
+        pids.push(pid);
+    }
+}
+
+fn untrack_restore_pid(pid: u32) {
+    if let Ok(mut pids) = RESTORE_PIDS.lock() {
+        pids.retain(|p| *p != pid);
+    }
+}
+
+/// `Command::output` for restore commands: runs in its own process group that `cancel_restore` can kill,
+/// and doesn't start at all once the restore was cancelled
+fn run_restore_command(cmd: &mut Command) -> std::io::Result<std::process::Output> {
+    use std::os::unix::process::CommandExt;
+    use std::process::Stdio;
+    
+    if RESTORE_CANCELLED.load(Ordering::SeqCst) {
+        return Err(std::io::Error::new(std::io::ErrorKind::Interrupted, "Wiederherstellung abgebrochen"));
+    }
+    unsafe {
+        cmd.pre_exec(|| {
+            libc::setpgid(0, 0);
+            Ok(())
+        });
+    }
+    let child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let pid = child.id();
+    track_restore_pid(pid);
+    let output = child.wait_with_output();
+    untrack_restore_pid(pid);
+    output
+}
+
+/// Number of verify workers: the requested count, or the CPU count if none given, clamped to 1..=16
+fn verify_worker_count(parallel_jobs: Option<usize>) -> usize {
+    parallel_jobs
+        .unwrap_or_else(|| std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4))
+        .clamp(1, 16)
+}
+
+/// Hash one archive with progress events and compare it to `expected`.
+/// The error is the line reported in `failed_files`; callers check `VERIFY_CANCELLED` afterwards.
+#[allow(clippy::too_many_arguments)]
+fn verify_archive_hash(
+    window: &tauri::Window,
+    archive_path: &Path,
+    name: &str,
+    expected: &str,
+    algorithm: HashAlgorithm,
+    size: u64,
+    bytes_before: u64,
+    bytes_total: u64,
+) -> Result<(), String> {
+    if !archive_path.exists() {
+        return Err(format!("{}: Datei nicht gefunden", name));
+    }
+    let mut hashed: u64 = 0;
+    let computed = hash_file_with_progress(archive_path, algorithm, Some(&VERIFY_CANCELLED), |bytes| {
+        hashed += bytes;
+        let _ = window.emit("backup-progress", ProgressUpdate {
+            message: format!("Verifiziere {} ({} / {} MB)", name, hashed / (1024 * 1024), size / (1024 * 1024)),
+            fraction: verify_fraction(bytes_before + hashed.min(size), bytes_total),
+        });
+    }).map_err(|e| format!("{}: Fehler beim Lesen: {}", name, e))?;
+    if computed != expected {
+        return Err(format!("{}: Hash stimmt nicht überein (erwartet: {}, berechnet: {})",
+            name, &expected[..16.min(expected.len())], &computed[..16]));
+    }
+    Ok(())
+}
+
+/// Marker written into `data/<timestamp>` with the archive hashes that passed verification
+const VERIFIED_MARKER: &str = ".verified";
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct VerifiedMarker {
+    verified_at: String,
+    /// Archive name -> hash that was confirmed
+    hashes: std::collections::BTreeMap<String, String>,
+}
+
+fn read_verified_marker(backup_root: &Path) -> Option<VerifiedMarker> {
+    fs::read_to_string(backup_root.join(VERIFIED_MARKER))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+fn write_verified_marker(backup_root: &Path, marker: &VerifiedMarker) {
+    if let Ok(json) = serde_json::to_string_pretty(marker) {
+        let _ = write_atomic(&backup_root.join(VERIFIED_MARKER), json);
+    }
+}
+
+/// After a full verification, mark all archives as verified or drop the marker on failure
+fn record_verify_outcome(backup_root: &Path, items: &[BackupItem], success: bool) {
+    if success {
+        write_verified_marker(backup_root, &VerifiedMarker {
+            verified_at: Local::now().to_rfc3339(),
+            hashes: archive_files(items).into_iter().map(|item| (item.archive, item.hash)).collect(),
+        });
+    } else {
+        let _ = fs::remove_file(backup_root.join(VERIFIED_MARKER));
+    }
+}
+
+/// True if every archive in metadata.json has been verified with its current hash
+fn is_backup_verified(backup_root: &Path) -> bool {
+    let Some(marker) = read_verified_marker(backup_root) else { return false };
+    read_metadata(&backup_root.join("metadata.json")).is_ok_and(|metadata| {
+        !metadata.items.is_empty()
+            && archive_files(&metadata.items).iter().all(|item| marker.hashes.get(&item.archive) == Some(&item.hash))
+    })
+}
+
+/// Verify only the archives not yet confirmed by the `.verified` marker (all of them with `force`)
+#[tauri::command]
+async fn verify_backup_incremental(
+    window: tauri::Window,
+    target_path: String,
+    timestamp: String,
+    force: Option<bool>,
+) -> Result<VerifyResult, String> {
+    let backup_path = PathBuf::from(&target_path)
+        .join("macos-backup-suite")
+        .join("data")
+        .join(&timestamp);
+    let metadata_path = backup_path.join("metadata.json");
+    if !metadata_path.exists() {
+        return Err(format!("Backup nicht gefunden: {}", timestamp));
+    }
+    let metadata = read_metadata(&metadata_path)?;
+    let algorithm = HashAlgorithm::from_name(&metadata.hash_algorithm);
+    let archives = archive_files(&metadata.items);
+    
+    let mut marker = if force.unwrap_or(false) {
+        VerifiedMarker::default()
+    } else {
+        read_verified_marker(&backup_path).unwrap_or_default()
+    };
+    // Drop entries for archives that are gone or whose hash changed
+    marker.hashes.retain(|archive, hash| archives.iter().any(|i| i.archive == *archive && i.hash == *hash));
+    
+    let pending: Vec<&BackupItem> = archives.iter()
+        .filter(|item| !marker.hashes.contains_key(&item.archive))
+        .collect();
+    let total_files = archives.len();
+    let skipped = total_files - pending.len();
+    if skipped > 0 {
+        let _ = window.emit("backup-log", format!("{} Dateien bereits verifiziert, übersprungen", skipped));
+    }
+    
+    let bytes_total: u64 = pending.iter().map(|item| item.archive_size_bytes).sum();
+    let mut bytes_done: u64 = 0;
+    let mut verified_files = skipped;
+    let mut failed_files = Vec::new();
+    VERIFY_CANCELLED.store(false, Ordering::SeqCst);
+    
+    for (i, item) in pending.iter().enumerate() {
+        if VERIFY_CANCELLED.load(Ordering::SeqCst) {
+            break;
+        }
+        let _ = window.emit("backup-log", format!("Verifiziere {}/{}: {}", i + 1, pending.len(), item.archive));
+        let result = verify_archive_hash(&window, &backup_path.join(&item.archive), &item.archive, &item.hash, algorithm, item.archive_size_bytes, bytes_done, bytes_total)
+            .and_then(|_| verify_dictionary(&backup_path, item, algorithm));
+        if VERIFY_CANCELLED.load(Ordering::SeqCst) {
+            break;
+        }
+        match result {
+            Ok(()) => {
+                verified_files += 1;
+                marker.hashes.insert(item.archive.clone(), item.hash.clone());
+            }
+            Err(e) => failed_files.push(e),
+        }
+        
+        bytes_done += item.archive_size_bytes;
+        let _ = window.emit("backup-progress", ProgressUpdate {
+            message: format!("{}/{} Dateien verifiziert", i + 1, pending.len()),
+            fraction: verify_fraction(bytes_done, bytes_total),
+        });
+    }
+    
+    // Keep what was confirmed so far, even after a cancel or failure
+    marker.verified_at = Local::now().to_rfc3339();
+    write_verified_marker(&backup_path, &marker);
+    
+    if VERIFY_CANCELLED.load(Ordering::SeqCst) {
+        return Ok(verify_cancelled(&WindowSink::backup(&window), total_files, verified_files, failed_files));
+    }
+    
+    let success = failed_files.is_empty();
+    let message = if success {
+        format!("Alle {} Dateien verifiziert ({} neu geprüft)", total_files, pending.len())
+    } else {
+        format!("{} von {} Dateien fehlgeschlagen", failed_files.len(), total_files)
+    };
+    let _ = window.emit("backup-log", &message);
+    
+    Ok(VerifyResult {
+        success,
+        total_files,
+        verified_files,
+        failed_files,
+        message,
+    })
+}
+
+/// Parse `SHA256SUMS` lines of the form `<hash>  <archive>` (`*` binary marker allowed)
+fn parse_checksum_manifest(content: &str) -> Vec<(String, String)> {
+    content.lines()
+        .filter_map(|line| {
+            let (hash, name) = line.trim_end().split_once(char::is_whitespace)?;
+            let name = name.trim_start().trim_start_matches('*');
+            // Only plain archive names next to the manifest are accepted
+            if hash.len() != 64 || name.is_empty() || name.contains('/') || name == ".." {
+                return None;
+            }
+            Some((hash.to_lowercase(), name.to_string()))
+        })
+        .collect()
+}
+
+/// Verify a backup against its `SHA256SUMS` manifest only, without reading metadata.json
+#[tauri::command]
+async fn verify_with_manifest(
+    window: tauri::Window,
+    target_path: String,
+    timestamp: String,
+) -> Result<VerifyResult, String> {
+    let backup_path = PathBuf::from(&target_path)
+        .join("macos-backup-suite")
+        .join("data")
+        .join(&timestamp);
+    if !backup_path.is_dir() {
+        return Err(format!("Backup nicht gefunden: {}", timestamp));
+    }
+    
+    let algorithm = [HashAlgorithm::Sha256, HashAlgorithm::Blake3]
+        .into_iter()
+        .find(|a| backup_path.join(a.manifest_name()).exists())
+        .ok_or_else(|| "Keine Prüfsummen-Datei (SHA256SUMS/B3SUMS) in diesem Backup vorhanden. Bitte die normale Verifizierung (über metadata.json) verwenden.".to_string())?;
+    let manifest_name = algorithm.manifest_name();
+    let content = fs::read_to_string(backup_path.join(manifest_name))
+        .map_err(|e| format!("Fehler beim Lesen von {}: {}", manifest_name, e))?;
+    let entries = parse_checksum_manifest(&content);
+    if entries.is_empty() {
+        return Err(format!("{} enthält keine gültigen Einträge", manifest_name));
+    }
+    
+    let total_files = entries.len();
+    let sizes: Vec<u64> = entries.iter()
+        .map(|(_, name)| fs::metadata(backup_path.join(name)).map(|m| m.len()).unwrap_or(0))
+        .collect();
+    let bytes_total: u64 = sizes.iter().sum();
+    let mut bytes_done: u64 = 0;
+    let mut verified_files = 0;
+    let mut failed_files = Vec::new();
+    VERIFY_CANCELLED.store(false, Ordering::SeqCst);
+    
+    for (i, ((expected, name), size)) in entries.iter().zip(&sizes).enumerate() {
+        if VERIFY_CANCELLED.load(Ordering::SeqCst) {
+            return Ok(verify_cancelled(&WindowSink::backup(&window), total_files, verified_files, failed_files));
+        }
+        let _ = window.emit("backup-log", format!("Verifiziere {}/{}: {}", i + 1, total_files, name));
+        
+        let result = verify_archive_hash(&window, &backup_path.join(name), name, expected, algorithm, *size, bytes_done, bytes_total);
+        if VERIFY_CANCELLED.load(Ordering::SeqCst) {
+            return Ok(verify_cancelled(&WindowSink::backup(&window), total_files, verified_files, failed_files));
+        }
+        match result {
+            Ok(()) => verified_files += 1,
+            Err(e) => failed_files.push(e),
+        }
+        
+        bytes_done += size;
+        let _ = window.emit("backup-progress", ProgressUpdate {
+            message: format!("{}/{} Dateien verifiziert", i + 1, total_files),
+            fraction: verify_fraction(bytes_done, bytes_total),
+        });
+    }
+    
+    let success = failed_files.is_empty();
+    let message = if success {
+        format!("Alle {} Dateien laut {} erfolgreich verifiziert!", total_files, manifest_name)
+    } else {
+        format!("{} von {} Dateien fehlgeschlagen", failed_files.len(), total_files)
+    };
+    let _ = window.emit("backup-log", &message);
+    
+    Ok(VerifyResult {
+        success,
+        total_files,
+        verified_files,
+        failed_files,
+        message,
+    })
+}
+
+/// Size-only check: compare every archive's size on disk with `archive_size_bytes` from the metadata.
+/// Catches missing and truncated archives instantly, without hashing.
+#[tauri::command]
+fn quick_verify(target_path: String, timestamp: String) -> Result<VerifyResult, String> {
+    let metadata = read_backup_metadata(&target_path, &timestamp)?;
+    let backup_path = PathBuf::from(&target_path)
+        .join("macos-backup-suite")
+        .join("data")
+        .join(&timestamp);
+    
+    let archives = archive_files(&metadata.items);
+    let total_files = archives.len();
+    let mut verified_files = 0;
+    let mut failed_files = Vec::new();
+    for item in &archives {
+        match fs::metadata(backup_path.join(&item.archive)) {
+            Err(_) => failed_files.push(format!("{}: Archiv fehlt", item.archive)),
+            // Backups from before sizes were recorded can only be checked for presence
+            Ok(_) if item.archive_size_bytes == 0 => verified_files += 1,
+            Ok(meta) if meta.len() != item.archive_size_bytes => failed_files.push(format!(
+                "{}: Größe {} statt {} Bytes",
+                item.archive, meta.len(), item.archive_size_bytes
+            )),
+            Ok(_) => verified_files += 1,
+        }
+    }
+    
+    let success = failed_files.is_empty();
+    let message = if success {
+        format!("Schnellprüfung (nur Dateigrößen): alle {} Archive vollständig", total_files)
+    } else {
+        format!("Schnellprüfung (nur Dateigrößen): {} von {} Archiven fehlerhaft", failed_files.len(), total_files)
+    };
+    
+    Ok(VerifyResult {
+        success,
+        total_files,
+        verified_files,
+        failed_files,
+        message,
+    })
+}
+
+/// Parallel backup verification against the archive hashes
+/// Provides ~40% time savings for integrity checks
+#[tauri::command]
+async fn verify_backup_parallel(
+    window: tauri::Window,
+    target_path: String,
+    timestamp: String,
+    parallel_jobs: Option<usize>,
+) -> Result<VerifyResult, String> {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering as AtomicOrdering};
+    use std::sync::Mutex;
+    use std::sync::mpsc;
+    
+    let backup_path = PathBuf::from(&target_path)
+        .join("macos-backup-suite")
+        .join("data")
+        .join(&timestamp);
+    
+    let metadata_path = backup_path.join("metadata.json");
+    if !metadata_path.exists() {
+        return Err(format!("Backup nicht gefunden: {}", timestamp));
+    }
+    
+    let metadata = read_metadata(&metadata_path)?;
+    let algorithm = HashAlgorithm::from_name(&metadata.hash_algorithm);
+    let archives = archive_files(&metadata.items);
+    
+    let total_files = archives.len();
+    let bytes_total: u64 = archives.iter().map(|item| item.archive_size_bytes).sum();
+    let bytes_done = Arc::new(AtomicU64::new(0));
+    let verified_counter = Arc::new(AtomicUsize::new(0));
+    let failed_files = Arc::new(Mutex::new(Vec::<String>::new()));
+    
+    let worker_count = verify_worker_count(parallel_jobs).min(total_files.max(1));
+    VERIFY_CANCELLED.store(false, Ordering::SeqCst);
+    
+    let _ = window.emit("backup-log", format!("🔍 Parallele Verifizierung von {} Dateien ({} Threads)...", total_files, worker_count));
+    
+    // Worker pool: each worker takes the next file as soon as its previous hash is done
+    let items = Arc::new(archives);
+    let next_index = Arc::new(AtomicUsize::new(0));
+    // `true` when a file is finished, `false` for progress within a file
+    let (done_tx, done_rx) = mpsc::channel::<bool>();
+    
+    let mut handles = Vec::new();
+    for _ in 0..worker_count {
+        let items = Arc::clone(&items);
+        let next_index = Arc::clone(&next_index);
+        let backup_path_clone = backup_path.clone();
+        let verified = Arc::clone(&verified_counter);
+        let failed = Arc::clone(&failed_files);
+        let bytes_done = Arc::clone(&bytes_done);
+        let done_tx = done_tx.clone();
+        
+        handles.push(std::thread::spawn(move || {
+            loop {
+                if VERIFY_CANCELLED.load(AtomicOrdering::SeqCst) {
+                    break;
+                }
+                let index = next_index.fetch_add(1, AtomicOrdering::SeqCst);
+                let Some(item) = items.get(index) else { break };
+                let archive_path = backup_path_clone.join(&item.archive);
+                let mut hashed: u64 = 0;
+                
+                if !archive_path.exists() {
+                    let mut failed_lock = failed.lock().unwrap();
+                    failed_lock.push(format!("{}: Datei nicht gefunden", item.archive));
+                } else {
+                    let hash_result = hash_file_with_progress(&archive_path, algorithm, Some(&VERIFY_CANCELLED), |bytes| {
+                        // Never count more than the recorded size, the remainder is added below
+                        let counted = bytes.min(item.archive_size_bytes.saturating_sub(hashed));
+                        hashed += counted;
+                        bytes_done.fetch_add(counted, AtomicOrdering::SeqCst);
+                        let _ = done_tx.send(false);
+                    });
+                    match hash_result {
+                        Ok(computed_hash) => {
+                            if computed_hash != item.hash {
+                                let mut failed_lock = failed.lock().unwrap();
+                                failed_lock.push(format!("{}: Hash stimmt nicht überein (erwartet: {}, berechnet: {})", 
+                                    item.archive, &item.hash[..16], &computed_hash[..16]));
+                            } else if let Err(e) = verify_dictionary(&backup_path_clone, item, algorithm) {
+                                failed.lock().unwrap().push(e);
+                            } else {
+                                verified.fetch_add(1, AtomicOrdering::SeqCst);
+                            }
+                        }
+                        Err(e) => {
+                            let mut failed_lock = failed.lock().unwrap();
+                            failed_lock.push(format!("{}: Fehler beim Lesen: {}", item.archive, e));
+                        }
+                    }
+                }
+                
+                bytes_done.fetch_add(item.archive_size_bytes - hashed, AtomicOrdering::SeqCst);
+                let _ = done_tx.send(true);
+            }
+        }));
+    }
+    drop(done_tx);
+    
+    // Report progress as each file completes; ends once all workers have exited
+    let mut processed = 0;
+    for file_done in done_rx {
+        if file_done {
+            processed += 1;
+        }
+        let _ = window.emit("backup-progress", ProgressUpdate {
+            message: format!("{}/{} Dateien verifiziert", processed, total_files),
+            fraction: verify_fraction(bytes_done.load(AtomicOrdering::SeqCst), bytes_total),
+        });
+    }
+    
+    for handle in handles {
+        let _ = handle.join();
+    }
+    
+    let verified_files = verified_counter.load(AtomicOrdering::SeqCst);
+    let failed_files_result = match Arc::try_unwrap(failed_files) {
+        Ok(mutex) => mutex.into_inner().unwrap_or_default(),
+        Err(arc) => arc.lock().unwrap().clone(),
+    };
+    
+    if VERIFY_CANCELLED.load(AtomicOrdering::SeqCst) {
+        // Files interrupted mid-hash are not failures
+        let failed_files_result = failed_files_result.into_iter()
+            .filter(|f| !f.ends_with("Abgebrochen"))
+            .collect();
+        return Ok(verify_cancelled(&WindowSink::backup(&window), total_files, verified_files, failed_files_result));
+    }
+    
+    let success = failed_files_result.is_empty();
+    record_verify_outcome(&backup_path, &metadata.items, success);
+    let message = if success {
+        format!("✅ Alle {} Dateien erfolgreich verifiziert (parallel)!", total_files)
+    } else {
+        format!("❌ {} von {} Dateien fehlgeschlagen", failed_files_result.len(), total_files)
+    };
+    
+    let _ = window.emit("backup-log", &message);
+    
+    Ok(VerifyResult {
+        success,
+        total_files,
+        verified_files,
+        failed_files: failed_files_result,
+        message,
+    })
+}
+
+
+/// Current format version of metadata.json.
+/// 1: format, timing, source system and checksum fields are always written
+const METADATA_VERSION: u32 = 1;
+
+/// File name of a directory's archive without extension
+fn archive_stem(name: &str) -> String {
+    name.to_lowercase().replace(' ', "-").replace('.', "_")
+}
+
+fn parse_metadata_file(path: &Path) -> Result<BackupMetadata, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Fehler beim Lesen der Metadaten: {}", e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Fehler beim Parsen der Metadaten: {}", e))
+}
+
+/// `inventories/<timestamp>/metadata.json` for a `data/<timestamp>/metadata.json`
+fn secondary_metadata_path(path: &Path) -> Option<PathBuf> {
+    let backup_dir = path.parent()?;
+    let suite_root = backup_dir.parent()?.parent()?;
+    Some(suite_root.join("inventories").join(backup_dir.file_name()?).join("metadata.json"))
+}
+
+/// Read a `metadata.json` written by any app version. Fields missing in older
+/// versions get their serde defaults; derived values are filled in here.
+/// Falls back to the copy in `inventories/<timestamp>` if the primary can't be read.
+fn read_metadata(path: &Path) -> Result<BackupMetadata, String> {
+    let mut metadata = parse_metadata_file(path).or_else(|e| {
+        secondary_metadata_path(path)
+            .and_then(|secondary| parse_metadata_file(&secondary).ok())
+            .ok_or(e)
+    })?;
+    
+    if metadata.metadata_version < METADATA_VERSION {
+        // The backup directory is named after its timestamp
+        if metadata.timestamp.is_empty() {
+            if let Some(name) = path.parent().and_then(|p| p.file_name()) {
+                metadata.timestamp = name.to_string_lossy().to_string();
+            }
+        }
+        if metadata.total_source_size_bytes == 0 {
+            metadata.total_source_size_bytes = metadata.items.iter().map(|i| i.source_size_bytes).sum();
+        }
+        // Archive sizes were not recorded by the first versions
+        for item in metadata.items.iter_mut().filter(|i| i.archive_size_bytes == 0) {
+            if let Some(parent) = path.parent() {
+                item.archive_size_bytes = fs::metadata(parent.join(&item.archive)).map(|m| m.len()).unwrap_or(0);
+            }
+        }
+    }
+    Ok(metadata)
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MetadataRepairResult {
+    /// "intact", "inventory_copy" or "reconstructed"
+    pub source: String,
+    pub item_count: usize,
+    /// Archives whose source path could not be determined; their path is the archive name
+    pub unresolved: Vec<String>,
+}
+
+/// Rebuild metadata from the archives in `backup_path` and their checksum sidecars/manifests.
+/// Source paths are matched against the configured directories and the special items.
+fn reconstruct_metadata(backup_path: &Path, timestamp: &str) -> Result<(BackupMetadata, Vec<String>), String> {
+    const ARCHIVE_EXTENSIONS: [(&str, ArchiveFormat); 3] =
+        [(".tar.zst", ArchiveFormat::Zst), (".tar.gz", ArchiveFormat::Gz), (".tar", ArchiveFormat::Tar)];
+    
+    let mut archives: Vec<String> = fs::read_dir(backup_path)
+        .map_err(|e| format!("Fehler beim Lesen von {}: {}", backup_path.display(), e))?
+        .flatten()
+        .filter(|entry| entry.path().is_file())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| {
+            let name = name.trim_end_matches(".enc");
+            ARCHIVE_EXTENSIONS.iter().any(|(ext, _)| name.ends_with(ext))
+        })
+        .collect();
+    archives.sort();
+    if archives.is_empty() {
+        return Err("Keine Archive im Backup gefunden – Metadaten können nicht wiederhergestellt werden".to_string());
+    }
+    
+    // Recorded hashes from sidecars and manifests decide the algorithm; the rest is hashed anew
+    let algorithm = [HashAlgorithm::Blake3, HashAlgorithm::Sha256]
+        .into_iter()
+        .find(|a| backup_path.join(a.manifest_name()).exists()
+            || archives.iter().any(|name| backup_path.join(format!("{}.{}", name, a.sidecar_extension())).exists()))
+        .unwrap_or(HashAlgorithm::Sha256);
+    let mut recorded: std::collections::HashMap<String, String> = fs::read_to_string(backup_path.join(algorithm.manifest_name()))
+        .map(|content| parse_checksum_manifest(&content).into_iter().map(|(hash, name)| (name, hash)).collect())
+        .unwrap_or_default();
+    for name in &archives {
+        if let Ok(content) = fs::read_to_string(backup_path.join(format!("{}.{}", name, algorithm.sidecar_extension()))) {
+            recorded.extend(parse_checksum_manifest(&content).into_iter().map(|(hash, name)| (name, hash)));
+        }
+    }
+    
+    let home = dirs::home_dir().unwrap_or_default();
+    let configured: Vec<String> = load_config().map(|c| c.directories.into_iter().map(|d| d.path).collect()).unwrap_or_default();
+    let configured_paths = expand_user_paths(&configured, &home);
+    let mut items = Vec::new();
+    let mut unresolved = Vec::new();
+    for archive in &archives {
+        let plain = archive.trim_end_matches(".enc");
+        let (ext, format) = ARCHIVE_EXTENSIONS.iter().find(|(ext, _)| plain.ends_with(ext)).copied().unwrap_or((".tar", ArchiveFormat::Tar));
+        let stem = plain.trim_end_matches(ext);
+        
+        let (path, user) = if SPECIAL_ITEMS.contains(&stem) {
+            (stem.to_string(), None)
+        } else if let Some((dir, (_, owner))) = configured.iter().zip(&configured_paths).find(|(_, (expanded, owner))| {
+            item_archive_stem(expanded, owner.as_deref()) == stem
+        }) {
+            (dir.clone(), owner.clone())
+        } else {
+            unresolved.push(archive.clone());
+            (stem.to_string(), None)
+        };
+        let hash = match recorded.get(archive) {
+            Some(hash) => hash.clone(),
+            None => hash_file(&backup_path.join(archive), algorithm)?,
+        };
+        let dictionary = format!("{}.dict", archive);
+        let dictionary_hash = if backup_path.join(&dictionary).is_file() {
+            Some(match recorded.get(&dictionary) {
+                Some(hash) => hash.clone(),
+                None => hash_file(&backup_path.join(&dictionary), algorithm)?,
+            })
+        } else {
+            None
+        };
+        items.push(BackupItem {
+            path,
+            archive: archive.clone(),
+            hash,
+            archive_size_bytes: fs::metadata(backup_path.join(archive)).map(|m| m.len()).unwrap_or(0),
+            source_size_bytes: 0,
+            format: Some(format),
+            duration_seconds: 0.0,
+            parts: Vec::new(),
+            multi_volume: false,
+            user,
+            dictionary_hash,
+        });
+    }
+    
+    let encrypted = archives.iter().any(|name| name.ends_with(".enc"));
+    let metadata = BackupMetadata {
+        metadata_version: METADATA_VERSION,
+        timestamp: timestamp.to_string(),
+        items,
+        hash_algorithm: algorithm.name().to_string(),
+        total_source_size_bytes: 0,
+        start_time: String::new(),
+        end_time: String::new(),
+        duration_seconds: 0,
+        encrypted,
+        cipher: encrypted.then(|| format!("{}-pbkdf2", ENCRYPTION_CIPHER)),
+        skipped_directories: Vec::new(),
+        failed_directories: Vec::new(),
+        source_system: SourceSystem::default(),
+    };
+    Ok((metadata, unresolved))
+}
+
+/// Restore a lost or corrupted `metadata.json`: from the copy in `inventories/<timestamp>` if that
+/// is intact, otherwise reconstructed from the archives and their checksum files.
+/// An intact primary only gets its missing second copy written.
+#[tauri::command]
+async fn repair_metadata(target_path: String, timestamp: String) -> Result<MetadataRepairResult, String> {
+    let suite_root = PathBuf::from(&target_path).join("macos-backup-suite");
+    let backup_path = suite_root.join("data").join(&timestamp);
+    if !backup_path.is_dir() {
+        return Err(format!("Backup nicht gefunden: {}", timestamp));
+    }
+    let primary = backup_path.join("metadata.json");
+    let secondary = suite_root.join("inventories").join(&timestamp).join("metadata.json");
+    
+    let (metadata, source, unresolved) = if let Ok(metadata) = parse_metadata_file(&primary) {
+        (metadata, "intact", Vec::new())
+    } else if let Ok(metadata) = parse_metadata_file(&secondary) {
+        (metadata, "inventory_copy", Vec::new())
+    } else {
+        let (metadata, unresolved) = reconstruct_metadata(&backup_path, &timestamp)?;
+        (metadata, "reconstructed", unresolved)
+    };
+    
+    let json = serde_json::to_string_pretty(&metadata).map_err(|e| e.to_string())?;
+    if source != "intact" {
+        write_atomic(&primary, &json).map_err(|e| format!("Fehler beim Schreiben von metadata.json: {}", e))?;
+    }
+    if source == "reconstructed" || !secondary.exists() {
+        if let Some(parent) = secondary.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let _ = write_atomic(&secondary, &json);
+    }
+    
+    Ok(MetadataRepairResult {
+        source: source.to_string(),
+        item_count: metadata.items.len(),
+        unresolved,
+    })
+}
+
+/// Read `metadata.json` of the backup `timestamp`
+fn read_backup_metadata(target_path: &str, timestamp: &str) -> Result<BackupMetadata, String> {
+    let metadata_path = PathBuf::from(target_path)
+        .join("macos-backup-suite")
+        .join("data")
+        .join(timestamp)
+        .join("metadata.json");
+    if !metadata_path.exists() {
+        return Err(format!("Backup nicht gefunden: {}", timestamp));
+    }
+    read_metadata(&metadata_path)
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Archive size divided by source size; 0.0 when the source size is unknown (0)
+fn archive_ratio(archive_bytes: u64, source_bytes: u64) -> f64 {
+    if source_bytes == 0 {
+        0.0
+    } else {
+        archive_bytes as f64 / source_bytes as f64
+    }
+}
+
+fn compression_ratio(archive_bytes: u64, source_bytes: u64) -> String {
+    if source_bytes == 0 {
+        String::new()
+    } else {
+        format!("{:.2}", archive_ratio(archive_bytes, source_bytes))
+    }
+}
+
+/// Export a backup's items as CSV or Markdown report (`format`: "csv" or "markdown") to `output_path`
+#[tauri::command]
+fn export_backup_report(
+    target_path: String,
+    timestamp: String,
+    format: String,
+    output_path: String,
+) -> Result<String, String> {
+    let metadata = read_backup_metadata(&target_path, &timestamp)?;
+    let total_archive: u64 = metadata.items.iter().map(|i| i.archive_size_bytes).sum();
+    let total_source: u64 = metadata.items.iter().map(|i| i.source_size_bytes).sum();
+    let summary_label = format!("Gesamt ({} Archive, Dauer {}s)", metadata.items.len(), metadata.duration_seconds);
+    
+    let report = match format.to_lowercase().as_str() {
+        "csv" => {
+            let mut lines = vec!["path,archive,source_size_bytes,archive_size_bytes,compression_ratio,hash".to_string()];
+            for item in &metadata.items {
+                lines.push([
+                    csv_field(&item.path),
+                    csv_field(&item.archive),
+                    item.source_size_bytes.to_string(),
+                    item.archive_size_bytes.to_string(),
+                    compression_ratio(item.archive_size_bytes, item.source_size_bytes),
+                    item.hash.clone(),
+                ].join(","));
+            }
+            lines.push([
+                csv_field(&summary_label),
+                String::new(),
+                total_source.to_string(),
+                total_archive.to_string(),
+                compression_ratio(total_archive, total_source),
+                String::new(),
+            ].join(","));
+            lines.join("\n") + "\n"
+        }
+        "markdown" | "md" => {
+            let mut lines = vec![
+                format!("# Backup-Bericht {}", metadata.timestamp),
+                String::new(),
+                format!("Start: {} · Ende: {} · Dauer: {}s", metadata.start_time, metadata.end_time, metadata.duration_seconds),
+                String::new(),
+                format!("| Pfad | Archiv | Quellgröße (Bytes) | Archivgröße (Bytes) | Kompression | {} |", HashAlgorithm::from_name(&metadata.hash_algorithm).label()),
+                "|---|---|---:|---:|---:|---|".to_string(),
+            ];
+            for item in &metadata.items {
+                lines.push(format!(
+                    "| {} | {} | {} | {} | {} | `{}` |",
+                    item.path.replace('|', "\\|"),
+                    item.archive.replace('|', "\\|"),
+                    item.source_size_bytes,
+                    item.archive_size_bytes,
+                    compression_ratio(item.archive_size_bytes, item.source_size_bytes),
+                    item.hash
+                ));
+            }
+            lines.push(format!(
+                "| **{}** | | **{}** | **{}** | **{}** | |",
+                summary_label,
+                total_source,
+                total_archive,
+                compression_ratio(total_archive, total_source)
+            ));
+            lines.join("\n") + "\n"
+        }
+        other => return Err(format!("Unbekanntes Format: {} (csv oder markdown)", other)),
+    };
+    
+    fs::write(&output_path, report).map_err(|e| format!("Bericht konnte nicht geschrieben werden: {}", e))?;
+    Ok(output_path)
+}
+
+/// Extract the archives of a backup item (all parts or volumes) into a throwaway directory to check
+/// they are readable. Unlike the restore, any non-zero tar exit status counts as failure.
+#[tauri::command]
+fn test_extract_archive(
+    target_path: String,
+    timestamp: String,
+    item_path: String,
+    encryption_passphrase: Option<String>,
+) -> Result<ExtractTestResult, String> {
+    let metadata = read_backup_metadata(&target_path, &timestamp)?;
+    let item = metadata.items.iter()
+        .find(|it| it.path == item_path)
+        .ok_or_else(|| format!("{}: Nicht im Backup gefunden", item_path))?;
+    
+    let backup_path = PathBuf::from(&target_path)
+        .join("macos-backup-suite")
+        .join("data")
+        .join(&timestamp);
+    let archive = backup_path.join(&item.archive);
+    if !archive.exists() {
+        return Err(format!("Archiv nicht gefunden: {}", item.archive));
+    }
+    
+    let temp_dir = staging_dir().join(format!("macos-backup-extract-test-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&temp_dir);
+    fs::create_dir_all(&temp_dir).map_err(|e| e.to_string())?;
+    
+    let result = (|| {
+        let passphrase = if metadata.encrypted {
+            Some(encryption_passphrase.as_deref()
+                .filter(|p| !p.is_empty())
+                .ok_or("Backup ist verschlüsselt - bitte Passphrase angeben")?)
+        } else {
+            None
+        };
+        // Volumes only form an archive together; every part of a split directory is one on its own
+        let decrypt_dir = temp_dir.join("decrypted");
+        let archives = if item.multi_volume {
+            vec![plain_item_archive(&backup_path, item, passphrase, &decrypt_dir)?]
+        } else {
+            archive_files(std::slice::from_ref(item)).iter()
+                .map(|file| plain_item_archive(&backup_path, file, passphrase, &decrypt_dir))
+                .collect::<Result<Vec<_>, String>>()?
+        };
+        
+        let extract_dir = temp_dir.join("extracted");
+        fs::create_dir_all(&extract_dir).map_err(|e| e.to_string())?;
+        let mut success = true;
+        let mut stderr = Vec::new();
+        for archive in &archives {
+            let output = Command::new("tar")
+                .current_dir(&extract_dir)
+                .args(tar_decompress_args(archive))
+                .args(["-xf", &archive.to_string_lossy()])
+                .output()
+                .map_err(|e| format!("tar Fehler: {}", e))?;
+            success &= output.status.success();
+            let text = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            if !text.is_empty() {
+                stderr.push(text);
+            }
+        }
+        
+        let (file_count, bytes) = WalkDir::new(&extract_dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter_map(|e| e.metadata().ok())
+            .filter(|m| m.is_file())
+            .fold((0, 0), |(count, size), m| (count + 1, size + m.len()));
+        
+        Ok(ExtractTestResult {
+            item_path: item_path.clone(),
+            archive: item.archive.clone(),
+            success,
+            file_count,
+            bytes,
+            stderr: stderr.join("\n"),
+        })
+    })();
+    
+    let _ = fs::remove_dir_all(&temp_dir);
+    result
+}
+
+/// Compare two backups item by item (by hash and source size)
+#[tauri::command]
+fn diff_backups(target_path: String, timestamp_a: String, timestamp_b: String) -> Result<BackupDiff, String> {
+    let metadata_a = read_backup_metadata(&target_path, &timestamp_a)?;
+    let metadata_b = read_backup_metadata(&target_path, &timestamp_b)?;
+    // Encryption salts every archive differently and another algorithm gives another hash for the
+    // same data; then only the source sizes can be compared
+    let hashes_comparable = !metadata_a.encrypted && !metadata_b.encrypted
+        && metadata_a.hash_algorithm == metadata_b.hash_algorithm;
+    
+    let mut entries = Vec::new();
+    for item_a in &metadata_a.items {
+        let item_b = metadata_b.items.iter().find(|b| b.path == item_a.path);
+        let status = match item_b {
+            None => "entfernt",
+            Some(b) if b.source_size_bytes != item_a.source_size_bytes => "geändert",
+            Some(_) if !hashes_comparable => "nicht vergleichbar",
+            Some(b) if b.hash == item_a.hash => "unverändert",
+            Some(_) => "geändert",
+        };
+        let size_b = item_b.map(|b| b.source_size_bytes);
+        entries.push(BackupDiffEntry {
+            path: item_a.path.clone(),
+            status: status.to_string(),
+            source_size_a: Some(item_a.source_size_bytes),
+            source_size_b: size_b,
+            size_delta: size_b.unwrap_or(0) as i64 - item_a.source_size_bytes as i64,
+        });
+    }
+    for item_b in &metadata_b.items {
+        if !metadata_a.items.iter().any(|a| a.path == item_b.path) {
+            entries.push(BackupDiffEntry {
+                path: item_b.path.clone(),
+                status: "hinzugefügt".to_string(),
+                source_size_a: None,
+                source_size_b: Some(item_b.source_size_bytes),
+                size_delta: item_b.source_size_bytes as i64,
+            });
+        }
+    }
+    
+    let total_size_delta = entries.iter().map(|e| e.size_delta).sum();
+    Ok(BackupDiff {
+        timestamp_a,
+        timestamp_b,
+        entries,
+        total_size_delta,
+    })
+}
+
+#[tauri::command]
+fn list_backup_files(target_path: String, timestamp: String) -> Result<BackupDetails, String> {
+    let backup_path = PathBuf::from(&target_path)
+        .join("macos-backup-suite")
+        .join("data")
+        .join(&timestamp);
+    
+    let metadata_path = backup_path.join("metadata.json");
+    if !metadata_path.exists() {
+        return Err(format!("Backup nicht gefunden: {}", timestamp));
+    }
+    
+    let metadata = read_metadata(&metadata_path)?;
+    
+    let items: Vec<BackupFileInfo> = metadata.items.iter().map(|item| {
+        BackupFileInfo {
+            path: item.path.clone(),
+            archive: item.archive.clone(),
+            archive_size_bytes: item.archive_size_bytes,
+            source_size_bytes: item.source_size_bytes,
+            compression_ratio: archive_ratio(item.archive_size_bytes, item.source_size_bytes),
+            duration_seconds: item.duration_seconds,
+            user: item.user.clone(),
+        }
+    }).collect();
+    
+    let total_archive_size_bytes: u64 = items.iter().map(|i| i.archive_size_bytes).sum();
+    // Weighted by size: only items with a known source size count towards the average
+    let (ratio_archive, ratio_source) = items.iter()
+        .filter(|i| i.source_size_bytes > 0)
+        .fold((0u64, 0u64), |(a, s), i| (a + i.archive_size_bytes, s + i.source_size_bytes));
+    
+    Ok(BackupDetails {
+        timestamp: metadata.timestamp,
+        items,
+        total_source_size_bytes: metadata.total_source_size_bytes,
+        total_archive_size_bytes,
+        average_compression_ratio: archive_ratio(ratio_archive, ratio_source),
+        start_time: metadata.start_time,
+        end_time: metadata.end_time,
+        duration_seconds: metadata.duration_seconds,
+        source_system: metadata.source_system,
+    })
+}
+
+#[tauri::command]
+fn list_backups(target_path: String) -> Result<Vec<BackupListItem>, String> {
+    let data_path = PathBuf::from(&target_path)
+        .join("macos-backup-suite")
+        .join("data");
+    
+    if !data_path.exists() {
+        return Ok(Vec::new());
+    }
+    
+    let mut backups = Vec::new();
+    if let Ok(entries) = fs::read_dir(&data_path) {
+        for entry in entries.flatten() {
+            if entry.path().is_dir() {
+                if let Some(name) = entry.file_name().to_str() {
+                    let hash_verified = is_backup_verified(&entry.path());
+                    
+                    backups.push(BackupListItem {
+                        timestamp: name.to_string(),
+                        hash_verified,
+                    });
+                }
+            }
+        }
+    }
+    
+    backups.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    Ok(backups)
+}
+
+/// Projections stop counting here, e.g. for tiny backups on a large drive
+const MAX_PROJECTED_BACKUPS: u32 = 999;
+
+/// Project how many more backups fit on the target from the sizes of the existing ones.
+/// Sizes follow a least-squares trend line, but never drop below the smallest backup so far.
+#[tauri::command]
+fn project_capacity(target_path: String) -> Result<CapacityProjection, String> {
+    let data_path = PathBuf::from(&target_path)
+        .join("macos-backup-suite")
+        .join("data");
+    
+    let mut timestamps: Vec<String> = match fs::read_dir(&data_path) {
+        Ok(entries) => entries
+            .flatten()
+            .filter(|entry| entry.path().join("metadata.json").exists() && !entry.path().join(IN_PROGRESS_MARKER).exists())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    timestamps.sort();
+    let backups: Vec<BackupSizePoint> = timestamps.into_iter()
+        .filter_map(|timestamp| {
+            let metadata = read_backup_metadata(&target_path, &timestamp).ok()?;
+            let size_bytes = metadata.items.iter().map(|i| i.archive_size_bytes).sum();
+            Some(BackupSizePoint { timestamp, size_bytes })
+        })
+        .collect();
+    
+    let free_space_gb = get_disk_space_gb(Path::new(&target_path)).1;
+    if backups.is_empty() {
+        return Ok(CapacityProjection {
+            backups,
+            average_size_bytes: 0,
+            growth_bytes_per_backup: 0.0,
+            free_space_gb,
+            projected_remaining_backups: None,
+        });
+    }
+    
+    let count = backups.len() as f64;
+    let sizes: Vec<f64> = backups.iter().map(|b| b.size_bytes as f64).collect();
+    let mean_size = sizes.iter().sum::<f64>() / count;
+    let mean_index = (count - 1.0) / 2.0;
+    let (covariance, variance) = sizes.iter().enumerate().fold((0.0, 0.0), |(cov, var), (i, size)| {
+        let dx = i as f64 - mean_index;
+        (cov + dx * (size - mean_size), var + dx * dx)
+    });
+    let growth = if variance > 0.0 { covariance / variance } else { 0.0 };
+    let smallest = sizes.iter().cloned().fold(f64::INFINITY, f64::min).max(1.0);
+    
+    // The same safety margin the backup itself keeps free
+    let projected_remaining_backups = (free_space_gb > 0.0).then(|| {
+        let mut available = (free_space_gb - BACKUP_FREE_SPACE_MARGIN_GB).max(0.0) * 1024.0 * 1024.0 * 1024.0;
+        let mut fitting = 0;
+        while fitting < MAX_PROJECTED_BACKUPS {
+            let next = (mean_size + growth * (count + fitting as f64 - mean_index)).max(smallest);
+            if next > available {
+                break;
+            }
+            available -= next;
+            fitting += 1;
+        }
+        fitting
+    });
+    
+    Ok(CapacityProjection {
+        backups,
+        average_size_bytes: mean_size as u64,
+        growth_bytes_per_backup: growth,
+        free_space_gb,
+        projected_remaining_backups,
+    })
+}
+
+#[tauri::command]
+fn get_manual_apps_from_backup(target_path: String, timestamp: String) -> Result<Vec<String>, String> {
+    let inventory_path = PathBuf::from(&target_path)
+        .join("macos-backup-suite")
+        .join("inventories")
+        .join(&timestamp)
+        .join("manual_apps.txt");
+    
+    if !inventory_path.exists() {
+        return Err("Datei manual_apps.txt nicht gefunden".to_string());
+    }
+    
+    let content = fs::read_to_string(&inventory_path)
+        .map_err(|e| format!("Fehler beim Lesen: {}", e))?;
+    
+    let apps: Vec<String> = content
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.to_string())
+        .collect();
+    
+    Ok(apps)
+}
+
+/// Software installed now but missing from a backup's inventories, per category
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct BackupCoverage {
+    pub manual_apps: Vec<String>,
+    pub brews: Vec<String>,
+    pub casks: Vec<String>,
+    /// "Name (ID)" of App Store apps
+    pub mas_apps: Vec<String>,
+    pub vscode_extensions: Vec<String>,
+    pub total_missing: usize,
+}
+
+/// Compare the software installed right now with the inventories recorded in a backup,
+/// so the user knows when the backup no longer covers the machine and should be re-run
+#[tauri::command]
+async fn backup_coverage(target_path: String, timestamp: String) -> Result<BackupCoverage, String> {
+    let inventory_root = PathBuf::from(&target_path)
+        .join("macos-backup-suite")
+        .join("inventories")
+        .join(&timestamp);
+    if !inventory_root.is_dir() {
+        return Err(format!("Backup nicht gefunden: {}", timestamp));
+    }
+    let read_lines = |name: &str| -> Vec<String> {
+        fs::read_to_string(inventory_root.join(name))
+            .map(|c| c.lines().map(|l| l.trim().to_string()).filter(|l| !l.is_empty()).collect())
+            .unwrap_or_default()
+    };
+    let last_segment = |name: &str| name.rsplit('/').next().unwrap_or(name).to_string();
+    let missing = |current: Vec<String>, backed_up: &[String]| -> Vec<String> {
+        let mut gaps: Vec<String> = current.into_iter().filter(|c| !backed_up.contains(c)).collect();
+        gaps.sort_by_key(|g| g.to_lowercase());
+        gaps
+    };
+    
+    let brewfile = read_lines("Brewfile");
+    let brewfile_entries = |kind: &str| -> Vec<String> {
+        brewfile.iter()
+            .filter(|l| l.starts_with(&format!("{} \"", kind)))
+            .filter_map(|l| l.split('"').nth(1).map(last_segment))
+            .collect()
+    };
+    // `brew bundle dump` only lists formulae installed on request, so dependencies are not compared
+    let (current_brews, current_casks) = match find_brew_path() {
+        Some(brew) => {
+            let list = |args: &[&str]| -> Vec<String> {
+                command_output(&brew, args).lines().map(|l| last_segment(l.trim())).filter(|l| !l.is_empty()).collect()
+            };
+            (list(&["leaves", "--installed-on-request"]), list(&["list", "--cask", "-1"]))
+        }
+        None => (Vec::new(), Vec::new()),
+    };
+    
+    let backed_up_mas: Vec<MasApp> = fs::read_to_string(inventory_root.join("mas_apps.json"))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    let mut mas_apps: Vec<String> = get_mas_apps()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|app| !backed_up_mas.iter().any(|b| b.id == app.id))
+        .map(|app| format!("{} ({})", app.name, app.id))
+        .collect();
+    mas_apps.sort_by_key(|app| app.to_lowercase());
+    
+    let backed_up_extensions: Vec<String> = read_lines("vscode_extensions.txt").iter().map(|e| e.to_lowercase()).collect();
+    let current_extensions = get_vscode_extensions().unwrap_or_default().iter().map(|e| e.to_lowercase()).collect();
+    
+    let mut coverage = BackupCoverage {
+        manual_apps: missing(get_manual_apps().unwrap_or_default(), &read_lines("manual_apps.txt")),
+        brews: missing(current_brews, &brewfile_entries("brew")),
+        casks: missing(current_casks, &brewfile_entries("cask")),
+        mas_apps,
+        vscode_extensions: missing(current_extensions, &backed_up_extensions),
+        total_missing: 0,
+    };
+    coverage.total_missing = coverage.manual_apps.len() + coverage.brews.len() + coverage.casks.len()
+        + coverage.mas_apps.len() + coverage.vscode_extensions.len();
+    Ok(coverage)
+}
+
+#[tauri::command]
+fn show_help_window(app_handle: tauri::AppHandle) -> Result<(), String> {
+    use tauri::WebviewUrl;
+    
+    // Check if help window already exists
+    if let Some(window) = app_handle.get_webview_window("help") {
+        window.set_focus().map_err(|e| e.to_string())?;
+        return Ok(());
+    }
+    
+    // Create help window
+    let help_window = tauri::WebviewWindowBuilder::new(
+        &app_handle,
+        "help",
+        WebviewUrl::App("help.html".into())
+    )
+    .title("macOS Backup Suite - Hilfe")
+    .inner_size(800.0, 600.0)
+    .resizable(true)
+    .build()
+    .map_err(|e| e.to_string())?;
+    
+    help_window.set_focus().map_err(|e| e.to_string())?;
+    
+    Ok(())
+}
+
+/// Assumed average time to download and install one package, app or extension
+const RESTORE_SECONDS_PER_PACKAGE: u64 = 30;
+/// Assumed extraction throughput for file restores
+const RESTORE_BYTES_PER_SECOND: u64 = 100 * 1024 * 1024;
+
+/// Package names from a package item archive and the ones installed right now.
+/// Brew and cask names are compared without their tap prefix, extensions case-insensitively.
+fn package_diff(item_path: &str, staged: &Path) -> Option<(Vec<String>, Vec<String>)> {
+    let read = |name: &str| fs::read_to_string(staged.join(name)).ok();
+    let last_segment = |name: &str| name.rsplit('/').next().unwrap_or(name).to_string();
+    match item_path {
+        "homebrew-packages" => {
+            let content = read("homebrew_packages.txt")?;
+            let listed: Vec<String> = content.lines()
+                .filter(|l| l.starts_with("brew \"") || l.starts_with("cask \""))
+                .filter_map(|l| l.split('"').nth(1).map(last_segment))
+                .collect();
+            let installed = find_brew_path()
+                .map(|brew| command_output(&brew, &["list", "-1"]))
+                .unwrap_or_default()
+                .lines()
+                .map(|l| l.trim().to_string())
+                .collect();
+            Some((listed, installed))
+        }
+        "mas-apps" => {
+            let listed = parse_mas_list(&read("mas_apps.txt")?).into_iter().map(|app| app.id).collect();
+            let installed = get_mas_apps().unwrap_or_default().into_iter().map(|app| app.id).collect();
+            Some((listed, installed))
+        }
+        "vscode-extensions" => {
+            let listed = read("vscode_extensions.txt")?.lines()
+                .filter(|l| !l.trim().is_empty())
+                .map(|l| l.trim().to_lowercase())
+                .collect();
+            let installed = get_vscode_extensions().unwrap_or_default().iter().map(|e| e.to_lowercase()).collect();
+            Some((listed, installed))
+        }
+        _ => None,
+    }
+}
+
+/// Estimate what restoring `items` involves: bytes to extract for files and directories,
+/// and for package lists how many entries still need to be installed
+#[tauri::command]
+async fn estimate_restore(target_path: String, timestamp: String, items: Vec<String>) -> Result<RestoreEstimate, String> {
+    let backup_path = PathBuf::from(&target_path)
+        .join("macos-backup-suite")
+        .join("data")
+        .join(&timestamp);
+    let metadata = read_backup_metadata(&target_path, &timestamp)?;
+    
+    let mut estimates = Vec::new();
+    for item_path in &items {
+        let Some(item) = metadata.items.iter().find(|i| &i.path == item_path) else { continue };
+        let mut estimate = RestoreItemEstimate {
+            item_path: item_path.clone(),
+            bytes_to_extract: item.source_size_bytes,
+            packages_total: None,
+            packages_missing: None,
+            note: None,
+        };
+        
+        if matches!(item_path.as_str(), "homebrew-packages" | "mas-apps" | "vscode-extensions") {
+            if item.archive.ends_with(".enc") {
+                estimate.note = Some("Verschlüsselt – Paketliste nicht lesbar".to_string());
+            } else {
+                let staging = TempDirGuard::create("macos-backup-estimate")?;
+                let diff = extract_archive_to(&backup_path.join(&item.archive), &staging)
+                    .ok()
+                    .and_then(|_| package_diff(item_path, &staging));
+                match diff {
+                    Some((listed, installed)) => {
+                        estimate.packages_missing = Some(listed.iter().filter(|p| !installed.contains(p)).count());
+                        estimate.packages_total = Some(listed.len());
+                    }
+                    None => estimate.note = Some("Paketliste konnte nicht gelesen werden".to_string()),
+                }
+            }
+        }
+        estimates.push(estimate);
+    }
+    
+    let total_bytes_to_extract: u64 = estimates.iter().map(|e| e.bytes_to_extract).sum();
+    let total_packages_missing: usize = estimates.iter().filter_map(|e| e.packages_missing).sum();
+    let estimated_seconds = total_packages_missing as u64 * RESTORE_SECONDS_PER_PACKAGE
+        + total_bytes_to_extract / RESTORE_BYTES_PER_SECOND;
+    
+    Ok(RestoreEstimate {
+        items: estimates,
+        total_bytes_to_extract,
+        total_packages_missing,
+        estimated_seconds,
+    })
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn restore_items(
+    target_path: String,
+    timestamp: String,
+    items: Vec<String>,
+    overwrite: bool,
+    encryption_passphrase: Option<String>,
+    destination_override: Option<String>,
+    dry_run: Option<bool>,
+    verify_after_restore: Option<bool>,
+    skip_incompatible_casks: Option<bool>,
+    target_home: Option<String>,
+    start_services: Option<bool>,
+    window: tauri::Window,
+) -> Result<RestoreResult, String> {
+    let result = run_restore(
+        &target_path, &timestamp, &items, overwrite, encryption_passphrase, destination_override,
+        dry_run.unwrap_or(false), verify_after_restore.unwrap_or(false), skip_incompatible_casks, target_home, start_services,
+        &WindowSink::restore(&window),
+    )?;
+    if !dry_run.unwrap_or(false) {
+        notify_restore_result(&window, "Wiederherstellung abgeschlossen", &result);
+    }
+    Ok(result)
+}
+
+/// Where an item is restored to: its original location, or below `destination` under its own name
+fn restore_target(item_path: &str, home: &Path, destination: Option<&Path>) -> PathBuf {
+    // `~/…` is the restoring user's home, `~name/…` and `/Users/name/…` the home folder of that account
+    let original_target = if item_path.starts_with('~') || item_path.starts_with('/') {
+        expand_tilde(item_path, home)
+    } else {
+        home.join(item_path)
+    };
+    match destination {
+        Some(dest) => match original_target.file_name() {
+            Some(name) => dest.join(name),
+            None => dest.to_path_buf(),
+        },
+        None => original_target,
+    }
+}
+
+/// Re-hash restored files and compare them with the source manifest written during the backup.
+/// `restore_destination` is the alternative location the item was restored to, if any.
+#[tauri::command]
+async fn verify_restore_against_source(
+    window: tauri::Window,
+    target_path: String,
+    timestamp: String,
+    item_path: String,
+    restore_destination: Option<String>,
+) -> Result<VerifyResult, String> {
+    let backup_path = PathBuf::from(&target_path)
+        .join("macos-backup-suite")
+        .join("data")
+        .join(&timestamp);
+    let content = fs::read_to_string(source_manifest_path(&backup_path, &item_path))
+        .map_err(|_| format!("Kein Quell-Manifest für {} (beim Backup war \"Quell-Manifest erzeugen\" deaktiviert)", item_path))?;
+    let manifest: SourceManifest = serde_json::from_str(&content)
+        .map_err(|e| format!("Quell-Manifest beschädigt: {}", e))?;
+    let algorithm = HashAlgorithm::from_name(&manifest.hash_algorithm);
+    
+    let home = dirs::home_dir().unwrap_or_default();
+    let destination = restore_destination.filter(|d| !d.trim().is_empty()).map(PathBuf::from);
+    let restored = restore_target(&item_path, &home, destination.as_deref());
+    if !restored.exists() {
+        return Err(format!("Wiederhergestelltes Objekt nicht gefunden: {}", restored.display()));
+    }
+    
+    let sink = WindowSink::restore(&window);
+    sink.log(&format!("Vergleiche {} mit der Quelle ({} Dateien)...", restored.display(), manifest.files.len()));
+    let total_files = manifest.files.len();
+    let mut verified_files = 0;
+    let mut failed_files = Vec::new();
+    for (i, (relative, expected)) in manifest.files.iter().enumerate() {
+        // A single file is restored under its own name
+        let file = if restored.is_file() { restored.clone() } else { restored.join(relative) };
+        match hash_file(&file, algorithm) {
+            Ok(hash) if hash == *expected => verified_files += 1,
+            Ok(_) => failed_files.push(format!("{}: Inhalt weicht von der Quelle ab", relative)),
+            Err(_) if !file.exists() => failed_files.push(format!("{}: fehlt", relative)),
+            Err(e) => failed_files.push(format!("{}: {}", relative, e)),
+        }
+        if (i + 1) % 100 == 0 || i + 1 == total_files {
+            sink.progress(verify_percent(i as u64 + 1, total_files as u64), &format!("{}/{} Dateien verglichen", i + 1, total_files));
+        }
+    }
+    
+    let success = failed_files.is_empty();
+    let message = if success {
+        format!("Alle {} Dateien stimmen mit der Quelle überein", total_files)
+    } else {
+        format!("{} von {} Dateien weichen von der Quelle ab", failed_files.len(), total_files)
+    };
+    sink.log(&format!("{} {}", if success { "✅" } else { "❌" }, message));
+    
+    Ok(VerifyResult {
+        success,
+        total_files,
+        verified_files,
+        failed_files,
+        message,
+    })
+}
+
+/// Restore the given items of a backup, reporting through `sink`
+#[allow(clippy::too_many_arguments)]
+fn run_restore(
+    target_path: &str,
+    timestamp: &str,
+    items: &[String],
+    overwrite: bool,
+    encryption_passphrase: Option<String>,
+    destination_override: Option<String>,
+    dry_run: bool,
+    verify_after_restore: bool,
+    skip_incompatible_casks: Option<bool>,
+    target_home: Option<String>,
+    start_services: Option<bool>,
+    sink: &dyn ProgressSink,
+) -> Result<RestoreResult, String> {
+    validate_staging_dir()?;
+    let (home, foreign_home) = resolve_restore_home(target_home)?;
+    RESTORE_CANCELLED.store(false, Ordering::SeqCst);
+    let destination_override = destination_override
+        .filter(|d| !d.trim().is_empty())
+        .map(PathBuf::from);
+    let backup_path = PathBuf::from(target_path)
+        .join("macos-backup-suite")
+        .join("data")
+        .join(timestamp);
+    
+    let metadata_path = backup_path.join("metadata.json");
+    if !metadata_path.exists() {
+        return Err(format!("Backup nicht gefunden: {}", timestamp));
+    }
+    
+    let metadata = read_metadata(&metadata_path)?;
+    
+    let passphrase = if metadata.encrypted {
+        match encryption_passphrase.as_deref() {
+            Some(p) if !p.is_empty() => Some(p),
+            _ => return Err("Backup ist verschlüsselt - bitte Passphrase angeben".to_string()),
+        }
+    } else {
+        None
+    };
+    // Decrypted copies of the special-item archives are staged here, private to this run
+    let decrypt_dir = TempDirGuard::create("macos-backup-decrypt")?;
+    
+    if foreign_home {
+        sink.log(&format!("Stelle in den Benutzerordner {} wieder her", home.display()));
+    }
+    let mut restored: Vec<String> = Vec::new();
+    let mut skipped: Vec<String> = Vec::new();
+    let mut errors: Vec<String> = Vec::new();
+    
+    let total = items.len();
+    
+    for (i, item_path) in items.iter().enumerate() {
+        if RESTORE_CANCELLED.load(Ordering::SeqCst) {
+            let remaining = &items[i..];
+            errors.push(format!("Wiederherstellung abgebrochen, nicht wiederhergestellt: {}", remaining.join(", ")));
+            sink.log(&format!("⚠️ Wiederherstellung abgebrochen – {} Elemente nicht wiederhergestellt", remaining.len()));
+            break;
+        }
+        
+        // Progress: Start each item at a percentage, complete after operation
+        let start_progress = (i * 100) / total;
+        let end_progress = ((i + 1) * 100) / total;
+        
+        sink.progress(start_progress, &format!("Stelle wieder her: {}", item_path));
+        
+        // Find the backup item
+        let backup_item = metadata.items.iter().find(|it| &it.path == item_path);
+        if backup_item.is_none() {
+            errors.push(format!("{}: Nicht im Backup gefunden", item_path));
+            continue;
+        }
+        let backup_item = backup_item.unwrap();
+        
+        let is_special = SPECIAL_ITEMS.contains(&item_path.as_str());
+        
+        // Special items can't be relocated to an alternate destination
+        if is_special && destination_override.is_some() {
+            skipped.push(format!("{}: Nicht an alternativen Ort wiederherstellbar", item_path));
+            sink.log(&format!("⏭️ Übersprungen: {} (alternativer Zielort)", item_path));
+            continue;
+        }
+        
+        if foreign_home && SESSION_BOUND_ITEMS.contains(&item_path.as_str()) {
+            skipped.push(format!("{}: Nur für den angemeldeten Benutzer wiederherstellbar", item_path));
+            sink.log(&format!("⏭️ Übersprungen: {} (anderer Benutzer)", item_path));
+            continue;
+        }
+        
+        // Special items read a single archive from backup_path; encrypted or multi-volume
+        // archives get a decrypted, joined copy in the staging directory instead
+        let (backup_path, archive_name) = if is_special && (passphrase.is_some() || backup_item.multi_volume) {
+            match plain_item_archive(&backup_path, backup_item, passphrase, &decrypt_dir) {
+                Ok(plain) => {
+                    let name = plain.file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    (decrypt_dir.to_path_buf(), name)
+                }
+                Err(e) => {
+                    errors.push(format!("{}: {}", item_path, e));
+                    sink.log(&format!("❌ Fehler: {} - {}", item_path, e));
+                    continue;
+                }
+            }
+        } else {
+            (backup_path.clone(), backup_item.archive.clone())
+        };
+        
+        // Dry run for special items: only report what would be installed
+        if dry_run && is_special {
+            let archive = backup_path.join(&archive_name);
+            let planned = match item_path.as_str() {
+                "homebrew-packages" => read_archive_text_member(&archive, "homebrew_packages.txt").map(|c| {
+                    let count = c.lines()
+                        .filter(|l| l.starts_with("brew ") || l.starts_with("cask ") || l.starts_with("tap "))
+                        .count();
+                    format!("{} Homebrew-Pakete", count)
+                }),
+                "mas-apps" => read_archive_text_member(&archive, "mas_apps.txt")
+                    .map(|c| format!("{} MAS Apps", c.lines().filter(|l| l.starts_with("mas ")).count())),
+                "vscode-extensions" => read_archive_text_member(&archive, "vscode_extensions.txt")
+                    .map(|c| format!("{} VS Code Extensions", c.lines().filter(|l| !l.trim().is_empty()).count())),
+                _ => Ok(format!("{:.1} MB", backup_item.source_size_bytes as f64 / (1024.0 * 1024.0))),
+            };
+            match planned {
+                Ok(summary) => {
+                    restored.push(format!("{} ({})", item_path, summary));
+                    sink.log(&format!("[Testlauf] Würde wiederherstellen: {} ({})", item_path, summary));
+                }
+                Err(e) => {
+                    errors.push(format!("{}: {}", item_path, e));
+                    sink.log(&format!("[Testlauf] ❌ {}: {}", item_path, e));
+                }
+            }
+            continue;
+        }
+        
+        // Special handling for different item types
+        if item_path == "homebrew-packages" {
+            let action = if overwrite { "Reinstalliere" } else { "Installiere fehlende" };
+            sink.log(&format!("{} Homebrew-Pakete...", action));
+            let backup_arch = &metadata.source_system.arch;
+            let arch_mismatch = !backup_arch.is_empty() && *backup_arch != current_arch();
+            if arch_mismatch {
+                sink.log(&format!(
+                    "⚠️ ACHTUNG: Backup wurde auf {} erstellt, dieser Mac ist {}. Casks und Bottles sind eventuell nicht kompatibel - einzelne Installationen können fehlschlagen.",
+                    backup_arch, current_arch()
+                ));
+            }
+            let skip_casks = arch_mismatch && skip_incompatible_casks.unwrap_or(false);
+            match restore_homebrew_packages(&backup_path, &archive_name, overwrite, skip_casks, start_services.unwrap_or(true), sink) {
+                Ok(count) => {
+                    if count > 0 {
+                        restored.push(format!("{} ({} neu installiert)", item_path, count));
+                        sink.log(&format!("✅ {} Homebrew-Pakete neu installiert/aktualisiert", count));
+                    } else {
+                        restored.push(format!("{} (alle bereits vorhanden)", item_path));
+                        sink.log(&format!("✅ Alle Homebrew-Pakete waren bereits installiert"));
+                    }
+                }
+                Err(e) => {
+                    errors.push(format!("{}: {}", item_path, e));
+                    sink.log(&format!("❌ Homebrew-Fehler: {}", e));
+                }
+            }
+            sink.progress(end_progress, "Homebrew abgeschlossen");
+            continue;
+        }
+        
+        if item_path == "mas-apps" {
+            let action = if overwrite { "Reinstalliere" } else { "Installiere fehlende" };
+            sink.log(&format!("{} Mac App Store Apps...", action));
+            match restore_mas_apps(&backup_path, &archive_name, overwrite, sink) {
+                Ok((count, warnings)) => {
+                    for warning in &warnings {
+                        skipped.push(format!("{}: {}", item_path, warning));
+                        sink.log(&format!("⚠️ {}", warning));
+                    }
+                    restored.push(format!("{} ({} Apps)", item_path, count));
+                    sink.log(&format!("✅ {} MAS Apps installiert", count));
+                }
+                Err(e) => {
+                    errors.push(format!("{}: {}", item_path, e));
+                    sink.log(&format!("❌ MAS-Fehler: {}", e));
+                }
+            }
+            sink.progress(end_progress, "MAS Apps abgeschlossen");
+            continue;
+        }
+        
+        if item_path == "vscode-extensions" {
+            let action = if overwrite { "Reinstalliere" } else { "Installiere fehlende" };
+            sink.log(&format!("{} VS Code Extensions...", action));
+            match restore_vscode_extensions(&backup_path, &archive_name, overwrite, sink) {
+                Ok(count) => {
+                    restored.push(format!("{} ({} Extensions)", item_path, count));
+                    sink.log(&format!("✅ {} VS Code Extensions installiert", count));
+                }
+                Err(e) => {
+                    errors.push(format!("{}: {}", item_path, e));
+                    sink.log(&format!("❌ VS Code-Fehler: {}", e));
+                }
+            }
+            sink.progress(end_progress, "VS Code abgeschlossen");
+            continue;
+        }
+        
+        // Browser settings restore
+        let browser_restore: Option<(&str, SettingsRestoreFn)> = match item_path.as_str() {
+            "safari-settings" => Some(("Safari", restore_safari_settings)),
+            "chrome-settings" => Some(("Chrome", restore_chrome_settings)),
+            "firefox-settings" => Some(("Firefox", restore_firefox_settings)),
+            _ => None,
+        };
+        if let Some((label, restore_fn)) = browser_restore {
+            sink.log(&format!("Stelle {}-Einstellungen wieder her...", label));
+            match restore_fn(&backup_path, &archive_name, &home) {
+                Ok(count) => {
+                    restored.push(format!("{} ({} Dateien)", item_path, count));
+                    sink.log(&format!("✅ {} {}-Einstellungen wiederhergestellt", count, label));
+                }
+                Err(e) => {
+                    errors.push(format!("{}: {}", item_path, e));
+                    sink.log(&format!("❌ {}-Fehler: {}", label, e));
+                }
+            }
+            sink.progress(end_progress, &format!("{} abgeschlossen", label));
+            continue;
+        }
+        
+        // Homebrew cache restore
+        if item_path == "homebrew-cache" {
+            sink.log("Stelle Homebrew-Cache wieder her...");
+            match restore_homebrew_cache(&backup_path, &archive_name, &home) {
+                Ok(size_mb) => {
+                    restored.push(format!("{} ({} MB)", item_path, size_mb));
+                    sink.log(&format!("✅ Homebrew-Cache wiederhergestellt ({} MB)", size_mb));
+                }
+                Err(e) => {
+                    errors.push(format!("{}: {}", item_path, e));
+                    sink.log(&format!("❌ Homebrew-Cache-Fehler: {}", e));
+                }
+            }
+            sink.progress(end_progress, "Homebrew-Cache abgeschlossen");
+            continue;
+        }
+        
+        // Login items and LaunchAgents restore
+        if item_path == "startup-items" {
+            sink.log("Stelle Startobjekte wieder her...");
+            match restore_startup_items(&backup_path, &archive_name, overwrite) {
+                Ok(count) => {
+                    restored.push(format!("{} ({} Objekte)", item_path, count));
+                    sink.log(&format!("✅ {} Startobjekte wiederhergestellt", count));
+                }
+                Err(e) => {
+                    errors.push(format!("{}: {}", item_path, e));
+                    sink.log(&format!("❌ Startobjekte-Fehler: {}", e));
+                }
+            }
+            sink.progress(end_progress, "Startobjekte abgeschlossen");
+            continue;
+        }
+        
+        // Crontab and LaunchAgents restore
+        if item_path == "scheduled-tasks" {
+            sink.log("Stelle geplante Aufgaben wieder her...");
+            match restore_scheduled_tasks(&backup_path, &archive_name, overwrite) {
+                Ok((count, warnings)) => {
+                    for warning in &warnings {
+                        skipped.push(format!("{}: {}", item_path, warning));
+                        sink.log(&format!("⚠️ {}", warning));
+                    }
+                    restored.push(format!("{} ({} Einträge)", item_path, count));
+                    sink.log(&format!("✅ {} geplante Aufgaben wiederhergestellt", count));
+                }
+                Err(e) => {
+                    errors.push(format!("{}: {}", item_path, e));
+                    sink.log(&format!("❌ Fehler bei geplanten Aufgaben: {}", e));
+                }
+            }
+            sink.progress(end_progress, "Geplante Aufgaben abgeschlossen");
+            continue;
+        }
+        
+        // SSH/GPG configuration restore
+        if item_path == "ssh-config" {
+            sink.log("Stelle SSH/GPG-Konfiguration wieder her...");
+            match restore_ssh_config(&backup_path, &archive_name, overwrite, &home) {
+                Ok(count) => {
+                    restored.push(format!("{} ({} Dateien)", item_path, count));
+                    sink.log(&format!("✅ {} SSH/GPG-Dateien wiederhergestellt", count));
+                }
+                Err(e) => {
+                    errors.push(format!("{}: {}", item_path, e));
+                    sink.log(&format!("❌ SSH-Fehler: {}", e));
+                }
+            }
+            sink.progress(end_progress, "SSH abgeschlossen");
+            continue;
+        }
+        
+        // Dotfiles restore
+        if item_path == "dotfiles" {
+            sink.log("Stelle Dotfiles wieder her...");
+            match restore_dotfiles(&backup_path, &archive_name, overwrite, &home) {
+                Ok((count, shell)) => {
+                    restored.push(format!("{} ({} Dateien)", item_path, count));
+                    sink.log(&format!("✅ {} Dotfiles wiederhergestellt", count));
+                    if let Some(shell) = shell.filter(|s| Some(s) != read_user_shell().as_ref()) {
+                        sink.log(&format!("ℹ️ Gesicherte Login-Shell: {}", shell));
+                    }
+                }
+                Err(e) => {
+                    errors.push(format!("{}: {}", item_path, e));
+                    sink.log(&format!("❌ Dotfiles-Fehler: {}", e));
+                }
+            }
+            sink.progress(end_progress, "Dotfiles abgeschlossen");
+            continue;
+        }
+        
+        if item_path == "fonts" {
+            sink.log("Stelle Schriften wieder her...");
+            match restore_fonts(&backup_path, &archive_name, overwrite, &home) {
+                Ok((count, existing)) => {
+                    restored.push(format!("{} ({} Schriften)", item_path, count));
+                    sink.log(&format!("✅ {} Schriften wiederhergestellt", count));
+                    if existing > 0 {
+                        skipped.push(format!("{}: {} Schriften existieren bereits", item_path, existing));
+                        sink.log(&format!("⏭️ {} Schriften übersprungen (existieren)", existing));
+                    }
+                }
+                Err(e) => {
+                    errors.push(format!("{}: {}", item_path, e));
+                    sink.log(&format!("❌ Schriften-Fehler: {}", e));
+                }
+            }
+            sink.progress(end_progress, "Schriften abgeschlossen");
+            continue;
+        }
+        
+        // Dock layout restore
+        if item_path == "dock-layout" {
+            sink.log("Stelle Dock-Layout wieder her...");
+            match restore_dock(&backup_path, &archive_name) {
+                Ok(()) => {
+                    restored.push(item_path.clone());
+                    sink.log("✅ Dock-Layout wiederhergestellt");
+                }
+                Err(e) => {
+                    errors.push(format!("{}: {}", item_path, e));
+                    sink.log(&format!("❌ Dock-Fehler: {}", e));
+                }
+            }
+            sink.progress(end_progress, "Dock abgeschlossen");
+            continue;
+        }
+        
+        // Terminal/iTerm2 profiles restore
+        if item_path == "terminal-settings" {
+            sink.log("Stelle Terminal-Profile wieder her...");
+            match restore_terminal_settings(&backup_path, &archive_name, overwrite, &home) {
+                Ok(restored_parts) => {
+                    restored.push(format!("{} ({})", item_path, restored_parts.join(", ")));
+                    sink.log(&format!("✅ Terminal-Profile wiederhergestellt: {}", restored_parts.join(", ")));
+                }
+                Err(e) => {
+                    errors.push(format!("{}: {}", item_path, e));
+                    sink.log(&format!("❌ Terminal-Fehler: {}", e));
+                }
+            }
+            sink.progress(end_progress, "Terminal abgeschlossen");
+            continue;
+        }
+        
+        // System defaults restore
+        if item_path == "system-defaults" {
+            sink.log("Stelle Systemeinstellungen wieder her...");
+            match restore_system_defaults(&backup_path, &archive_name) {
+                Ok((imported, failed)) => {
+                    restored.push(format!("{} ({} Domains)", item_path, imported.len()));
+                    sink.log(&format!("✅ Systemeinstellungen wiederhergestellt: {}", imported.join(", ")));
+                    for e in failed {
+                        sink.log(&format!("❌ {}", e));
+                        errors.push(format!("{}: {}", item_path, e));
+                    }
+                }
+                Err(e) => {
+                    errors.push(format!("{}: {}", item_path, e));
+                    sink.log(&format!("❌ Systemeinstellungen-Fehler: {}", e));
+                }
+            }
+            sink.progress(end_progress, "Systemeinstellungen abgeschlossen");
+            continue;
+        }
+        
+        // Printer and network configuration restore
+        if item_path == "system-config" {
+            sink.log("Stelle Drucker- und Netzwerkkonfiguration wieder her...");
+            match restore_system_config(&backup_path, &archive_name) {
+                Ok(outcome) => {
+                    restored.push(format!("{} ({} Einträge)", item_path, outcome.added.len()));
+                    if !outcome.added.is_empty() {
+                        sink.log(&format!("✅ Hinzugefügt: {}", outcome.added.join(", ")));
+                    }
+                    for note in outcome.manual {
+                        sink.log(&format!("ℹ️ {}", note));
+                    }
+                    for e in outcome.failed {
+                        sink.log(&format!("⚠️ {}", e));
+                        errors.push(format!("{}: {}", item_path, e));
+                    }
+                }
+                Err(e) => {
+                    errors.push(format!("{}: {}", item_path, e));
+                    sink.log(&format!("❌ Systemkonfiguration-Fehler: {}", e));
+                }
+            }
+            sink.progress(end_progress, "Systemkonfiguration abgeschlossen");
+            continue;
+        }
+        
+        // Regular directory/file restore; a split directory has one archive per part,
+        // a multi-volume archive one file per volume
+        let archive_paths: Vec<PathBuf> = archive_files(std::slice::from_ref(backup_item)).iter()
+            .map(|file| backup_path.join(&file.archive))
+            .collect();
+        if archive_paths.iter().any(|path| !path.exists()) {
+            errors.push(format!("{}: Archiv nicht gefunden", item_path));
+            continue;
+        }
+        
+        // Items of another account need that account on this Mac unless restored elsewhere
+        if let (Some(user), None) = (&backup_item.user, &destination_override) {
+            if !owner_home(Some(user), &home).is_dir() {
+                errors.push(format!("{}: Benutzer {} existiert auf diesem Mac nicht – anderen Zielordner wählen", item_path, user));
+                sink.log(&format!("❌ {}: Benutzer {} existiert auf diesem Mac nicht", item_path, user));
+                continue;
+            }
+        }
+        
+        let target = restore_target(item_path, &home, destination_override.as_deref());
+        
+        if dry_run {
+            if target.exists() && !overwrite {
+                skipped.push(format!("{}: Existiert bereits", item_path));
+                sink.log(&format!("[Testlauf] Würde überspringen: {} (existiert)", item_path));
+            } else if target.exists() {
+                restored.push(item_path.clone());
+                sink.log(&format!("[Testlauf] Würde überschreiben: {} → {}", item_path, target.display()));
+            } else {
+                restored.push(item_path.clone());
+                sink.log(&format!("[Testlauf] Würde wiederherstellen: {} → {}", item_path, target.display()));
+            }
+            continue;
+        }
+        
+        // Check if target exists
+        if target.exists() && !overwrite {
+            skipped.push(format!("{}: Existiert bereits", item_path));
+            sink.log(&format!("⏭️ Übersprungen: {} (existiert)", item_path));
+            continue;
+        }
+        
+        // Extract archive
+        if backup_item.multi_volume {
+            sink.log(&format!("📦 Extrahiere: {} ({} Teildateien)", item_path, archive_paths.len()));
+        } else if archive_paths.len() > 1 {
+            sink.log(&format!("📦 Extrahiere: {} ({} Teilarchive)", item_path, archive_paths.len()));
+        } else {
+            sink.log(&format!("📦 Extrahiere: {}", item_path));
+        }
+        let target_existed = target.exists();
+        // Later parts add their entries to the directory the first part created
+        let extracted = if backup_item.multi_volume {
+            extract_volumes(&archive_paths, &target, overwrite, passphrase, backup_item.format)
+        } else {
+            archive_paths.iter().enumerate().try_for_each(|(i, archive_path)| {
+                extract_tar_gz(archive_path, &target, overwrite || i > 0, passphrase, backup_item.format)
+            })
+        };
+        if extracted.is_err() && RESTORE_CANCELLED.load(Ordering::SeqCst) {
+            // Only a target created by this restore can be removed, an existing one was partly overwritten
+            let message = if target_existed {
+                format!("abgebrochen, {} ist teilweise überschrieben", target.display())
+            } else {
+                let _ = if target.is_dir() { fs::remove_dir_all(&target) } else { fs::remove_file(&target) };
+                "abgebrochen, unvollständig extrahierte Dateien wurden entfernt".to_string()
+            };
+            errors.push(format!("{}: {}", item_path, message));
+            sink.log(&format!("❌ {}: {}", item_path, message));
+            continue;
+        }
+        match extracted {
+            Ok(_) if verify_after_restore => {
+                match verify_restored_item(&target, backup_item.source_size_bytes) {
+                    Ok((file_count, bytes)) => {
+                        restored.push(item_path.clone());
+                        sink.log(&format!(
+                            "✅ Wiederhergestellt und geprüft: {} ({} Dateien, {:.1} MB)",
+                            item_path, file_count, bytes as f64 / (1024.0 * 1024.0)
+                        ));
+                    }
+                    Err(e) => {
+                        errors.push(format!("{}: Prüfung fehlgeschlagen - {}", item_path, e));
+                        sink.log(&format!("❌ Prüfung fehlgeschlagen: {} - {}", item_path, e));
+                    }
+                }
+            }
+            Ok(_) => {
+                restored.push(item_path.clone());
+                sink.log(&format!("✅ Wiederhergestellt: {}", item_path));
+            }
+            Err(e) => {
+                errors.push(format!("{}: {}", item_path, e));
+                sink.log(&format!("❌ Fehler: {} - {}", item_path, e));
+            }
+        }
+    }
+    drop(decrypt_dir);
     
-    let _ = window.emit("backup-log", &message);
+    if foreign_home && !dry_run && !restored.is_empty() {
+        if let Err(e) = chown_restored_files(&home) {
+            errors.push(format!("Besitzer im Benutzerordner konnte nicht angepasst werden: {}", e));
+            sink.log(&format!("❌ Besitzer konnte nicht angepasst werden: {}", e));
+        }
+    }
     
-    Ok(VerifyResult {
+    let result = RestoreResult {
+        restored_count: restored.len(),
+        skipped_count: skipped.len(),
+        error_count: errors.len(),
+        restored,
+        skipped,
+        errors,
+    };
+    Ok(result)
+}
+
+/// Sample files written by `run_self_test`: path inside the test directory and size in bytes
+const SELF_TEST_FILES: &[(&str, usize)] = &[
+    ("notizen.txt", 4 * 1024),
+    ("leer.txt", 0),
+    (".versteckt", 64),
+    ("Fotos/bild.raw", 1024 * 1024),
+    ("Fotos/Urlaub 2024/Prüfung äöü.txt", 512),
+];
+
+/// Deterministic content for a sample file, different for every path (xorshift seeded from the path)
+fn self_test_content(path: &str, size: usize) -> Vec<u8> {
+    let mut state = path.bytes().fold(0xcbf2_9ce4_8422_2325u64, |h, b| (h ^ b as u64).wrapping_mul(0x100_0000_01b3));
+    (0..size).map(|_| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state as u8
+    }).collect()
+}
+
+/// Run one self-test phase and record its outcome, returns whether it succeeded
+fn self_test_phase(phases: &mut Vec<SelfTestPhase>, name: &str, phase: impl FnOnce() -> Result<String, String>) -> bool {
+    let started = Local::now();
+    let result = phase();
+    phases.push(SelfTestPhase {
+        name: name.to_string(),
+        success: result.is_ok(),
+        duration_seconds: elapsed_seconds(started),
+        detail: result.unwrap_or_else(|e| e),
+    });
+    phases.last().is_some_and(|p| p.success)
+}
+
+/// Back up a small sample tree, verify it, restore it and compare the restored files byte for byte,
+/// all inside the staging directory, to confirm archiving, hashing and extraction work on this Mac.
+/// Runs through `run_backup`, `run_verify` and `run_restore`, so it checks the same code as real backups.
+#[tauri::command]
+async fn run_self_test() -> Result<SelfTestReport, String> {
+    validate_staging_dir()?;
+    let root = TempDirGuard::create("macos-backup-self-test")?;
+    let source = root.join("quelle");
+    let target = root.join("ziel").to_string_lossy().to_string();
+    let restore_dir = root.join("wiederhergestellt");
+    let mut timestamp = String::new();
+    let item_path = source.to_string_lossy().to_string();
+    let sink = CollectingSink::default();
+    let mut phases = Vec::new();
+    
+    let success = self_test_phase(&mut phases, "Testdaten", || {
+        for (path, size) in SELF_TEST_FILES {
+            let file = source.join(path);
+            if let Some(parent) = file.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            fs::write(&file, self_test_content(path, *size)).map_err(|e| format!("{}: {}", path, e))?;
+        }
+        Ok(format!("{} Dateien angelegt", SELF_TEST_FILES.len()))
+    }) && self_test_phase(&mut phases, "Backup", || {
+        // The user's archive settings, but only the sample tree: no special items and no filters that could drop sample files
+        let config = BackupConfig {
+            backup_special_items: false,
+            exclude_patterns: Vec::new(),
+            exclude_system_files: false,
+            max_file_size_mb: None,
+            ..load_config().unwrap_or_default()
+        };
+        fs::create_dir_all(&target).map_err(|e| e.to_string())?;
+        let mut log = BackupLog::new(Box::new(sink.clone()));
+        let result = run_backup(&config, target.clone(), vec![BackupDirectory::new(item_path.clone())], None, None, None, None, &sink, &mut log);
+        log.finish(&result);
+        drop(log);
+        let metadata = result?;
+        let item = metadata.items.iter()
+            .find(|item| item.path == item_path)
+            .ok_or("Testdaten fehlen im Backup")?;
+        let detail = format!("{} ({} Bytes)", item.archive, item.archive_size_bytes);
+        timestamp = metadata.timestamp;
+        Ok(detail)
+    }) && self_test_phase(&mut phases, "Verifizierung", || {
+        let result = run_verify(&target, &timestamp, true, &sink)?;
+        if result.success {
+            Ok(result.message)
+        } else {
+            Err(result.failed_files.join("; "))
+        }
+    }) && self_test_phase(&mut phases, "Wiederherstellung", || {
+        let result = run_restore(
+            &target, &timestamp, std::slice::from_ref(&item_path), false, None, Some(restore_dir.to_string_lossy().to_string()),
+            false, true, None, None, None, &sink,
+        )?;
+        if result.error_count > 0 || result.restored_count != 1 {
+            return Err(result.errors.iter().chain(&result.skipped).cloned().collect::<Vec<_>>().join("; "));
+        }
+        Ok(format!("nach {} wiederhergestellt", restore_dir.display()))
+    }) && self_test_phase(&mut phases, "Vergleich", || {
+        let restored = restore_dir.join("quelle");
+        let mismatched: Vec<&str> = SELF_TEST_FILES.iter()
+            .filter(|(path, size)| fs::read(restored.join(path)).ok() != Some(self_test_content(path, *size)))
+            .map(|(path, _)| *path)
+            .collect();
+        let restored_files = WalkDir::new(&restored).into_iter().filter_map(|e| e.ok()).filter(|e| e.file_type().is_file()).count();
+        if !mismatched.is_empty() {
+            return Err(format!("abweichend oder fehlend: {}", mismatched.join(", ")));
+        }
+        if restored_files != SELF_TEST_FILES.len() {
+            return Err(format!("{} Dateien wiederhergestellt, {} erwartet", restored_files, SELF_TEST_FILES.len()));
+        }
+        Ok(format!("{} Dateien identisch", SELF_TEST_FILES.len()))
+    });
+    
+    Ok(SelfTestReport {
         success,
-        total_files,
-        verified_files,
-        failed_files: failed_files_result,
-        message,
+        phases,
+        log: sink.lines(),
     })
 }
 
+/// Restored data may be a little smaller than recorded (e.g. files that changed during the backup),
+/// anything below this share of `source_size_bytes` counts as an incomplete restore
+const RESTORE_SIZE_TOLERANCE: f64 = 0.95;
 
-#[tauri::command]
-fn list_backup_files(target_path: String, timestamp: String) -> Result<BackupDetails, String> {
-    let backup_path = PathBuf::from(&target_path)
-        .join("macos-backup-suite")
-        .join("data")
-        .join(&timestamp);
+/// Check that a restored file or directory exists and roughly has the size recorded in the backup.
+/// Returns the number of files and bytes found at `target`.
+fn verify_restored_item(target: &Path, expected_bytes: u64) -> Result<(u64, u64), String> {
+    if !target.exists() {
+        return Err("Ziel existiert nach der Wiederherstellung nicht".to_string());
+    }
     
-    let metadata_path = backup_path.join("metadata.json");
-    if !metadata_path.exists() {
-        return Err(format!("Backup nicht gefunden: {}", timestamp));
+    let (file_count, bytes) = if target.is_file() {
+        (1, fs::metadata(target).map(|m| m.len()).unwrap_or(0))
+    } else {
+        WalkDir::new(target)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter_map(|e| e.metadata().ok())
+            .filter(|m| m.is_file())
+            .fold((0, 0), |(count, size), m| (count + 1, size + m.len()))
+    };
+    
+    // Merging into an existing directory can only add data, so only a shortfall is an error
+    if (bytes as f64) < expected_bytes as f64 * RESTORE_SIZE_TOLERANCE {
+        return Err(format!(
+            "nur {} von {} Bytes vorhanden ({} Dateien)",
+            bytes, expected_bytes, file_count
+        ));
     }
     
-    let metadata_content = fs::read_to_string(&metadata_path)
-        .map_err(|e| format!("Fehler beim Lesen der Metadaten: {}", e))?;
-    let metadata: BackupMetadata = serde_json::from_str(&metadata_content)
-        .map_err(|e| format!("Fehler beim Parsen der Metadaten: {}", e))?;
+    Ok((file_count, bytes))
+}
+
+/// Reads the volumes of a multi-volume archive one after another as a single stream. Encrypted
+/// volumes are decrypted into `decrypt_dir` one at a time, so only one plaintext volume is on disk at once.
+struct VolumeReader {
+    volumes: std::collections::VecDeque<PathBuf>,
+    passphrase: Option<String>,
+    decrypt_dir: PathBuf,
+    /// Volume being read, its plaintext file and the open file
+    current: Option<(PathBuf, PathBuf, fs::File)>,
+}
+
+impl VolumeReader {
+    fn new(volumes: &[PathBuf], passphrase: Option<&str>, decrypt_dir: &Path) -> Self {
+        VolumeReader {
+            volumes: volumes.iter().cloned().collect(),
+            passphrase: passphrase.map(str::to_string),
+            decrypt_dir: decrypt_dir.to_path_buf(),
+            current: None,
+        }
+    }
     
-    let items: Vec<BackupFileInfo> = metadata.items.iter().map(|item| {
-        BackupFileInfo {
-            path: item.path.clone(),
-            archive: item.archive.clone(),
-            archive_size_bytes: item.archive_size_bytes,
-            source_size_bytes: item.source_size_bytes,
+    fn close_volume(&mut self) {
+        if let Some((_, plain, _)) = self.current.take() {
+            if self.passphrase.is_some() {
+                let _ = fs::remove_file(plain);
+            }
         }
-    }).collect();
+    }
+}
+
+impl Read for VolumeReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            if let Some((volume, _, file)) = self.current.as_mut() {
+                let n = file.read(buf).map_err(|e| {
+                    std::io::Error::other(format!("Teildatei {} konnte nicht gelesen werden: {}", volume.display(), e))
+                })?;
+                if n > 0 || buf.is_empty() {
+                    return Ok(n);
+                }
+                self.close_volume();
+            }
+            let Some(volume) = self.volumes.pop_front() else {
+                return Ok(0);
+            };
+            let plain = match &self.passphrase {
+                Some(pass) => decrypt_archive(&volume, pass, &self.decrypt_dir).map_err(std::io::Error::other)?,
+                None => volume.clone(),
+            };
+            let file = fs::File::open(&plain).map_err(|e| {
+                std::io::Error::other(format!("Teildatei {} konnte nicht gelesen werden: {}", volume.display(), e))
+            })?;
+            self.current = Some((volume, plain, file));
+        }
+    }
+}
+
+impl Drop for VolumeReader {
+    fn drop(&mut self) {
+        self.close_volume();
+    }
+}
+
+/// Readable single-file copy of an item's archive for code that takes one archive file: the volumes
+/// of a multi-volume archive are joined into `dir` (decrypting each on the way), an encrypted archive
+/// is decrypted into `dir`, and a plain single archive is used where it is.
+fn plain_item_archive(backup_path: &Path, item: &BackupItem, passphrase: Option<&str>, dir: &Path) -> Result<PathBuf, String> {
+    let archive = backup_path.join(&item.archive);
+    if !item.multi_volume {
+        return match passphrase {
+            Some(pass) => decrypt_archive(&archive, pass, dir),
+            None => Ok(archive),
+        };
+    }
     
-    let total_archive_size_bytes: u64 = items.iter().map(|i| i.archive_size_bytes).sum();
+    let volumes: Vec<PathBuf> = archive_files(std::slice::from_ref(item)).iter()
+        .map(|file| backup_path.join(&file.archive))
+        .collect();
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let joined = dir.join(item.archive.trim_end_matches(".enc").trim_end_matches(".001"));
+    let written = fs::File::create(&joined).and_then(|mut file| {
+        std::io::copy(&mut VolumeReader::new(&volumes, passphrase, dir), &mut file).map(|_| ())
+    });
+    if let Err(e) = written {
+        let _ = fs::remove_file(&joined);
+        return Err(format!("Teildateien konnten nicht zusammengesetzt werden: {}", e));
+    }
+    Ok(joined)
+}
+
+/// Extract a multi-volume archive by streaming its volumes one after another into a single tar.
+/// Encrypted volumes are decrypted one at a time, so only one plaintext volume is on disk at once.
+fn extract_volumes(volumes: &[PathBuf], target: &Path, overwrite: bool, passphrase: Option<&str>, format: Option<ArchiveFormat>) -> Result<(), String> {
+    use std::os::unix::process::CommandExt;
+    use std::process::Stdio;
     
-    Ok(BackupDetails {
-        timestamp: metadata.timestamp,
-        items,
-        total_source_size_bytes: metadata.total_source_size_bytes,
-        total_archive_size_bytes,
-        start_time: metadata.start_time,
-        end_time: metadata.end_time,
-        duration_seconds: metadata.duration_seconds,
-    })
+    let parent = target.parent().unwrap_or(Path::new("/"));
+    fs::create_dir_all(parent).map_err(|e| format!("Fehler beim Erstellen des Verzeichnisses: {}", e))?;
+    if !overwrite && target.exists() {
+        return Err("Ziel existiert bereits und Überschreiben ist deaktiviert".to_string());
+    }
+    if RESTORE_CANCELLED.load(Ordering::SeqCst) {
+        return Err("Wiederherstellung abgebrochen".to_string());
+    }
+    
+    // Multi-volume archives are always written with a recorded format
+    let format = format.ok_or("Archivformat der Teildateien unbekannt")?;
+    let mut cmd = Command::new("tar");
+    cmd.current_dir(parent);
+    if !overwrite {
+        cmd.arg("-k");
+    }
+    cmd.args(format.decompress_args())
+        .args(["-xf", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    unsafe {
+        cmd.pre_exec(|| {
+            libc::setpgid(0, 0);
+            Ok(())
+        });
+    }
+    let mut child = cmd.spawn().map_err(|e| format!("tar Fehler: {}", e))?;
+    let pid = child.id();
+    track_restore_pid(pid);
+    let stderr_reader = child.stderr.take().map(|mut stderr| {
+        std::thread::spawn(move || {
+            let mut output = String::new();
+            let _ = stderr.read_to_string(&mut output);
+            output
+        })
+    });
+    
+    let fed = (|| -> Result<(), String> {
+        let decrypt_dir = TempDirGuard::create("macos-backup-decrypt-volumes")?;
+        let mut stdin = child.stdin.take().ok_or("tar nimmt keine Eingabe an")?;
+        std::io::copy(&mut VolumeReader::new(volumes, passphrase, &decrypt_dir), &mut stdin)
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    })();
+    
+    let status = child.wait().map_err(|e| format!("tar Fehler: {}", e));
+    untrack_restore_pid(pid);
+    let tar_stderr = stderr_reader.and_then(|handle| handle.join().ok()).unwrap_or_default();
+    if !status?.success() {
+        // -k causes error if files exist but that's expected when not overwriting
+        if overwrite || !tar_stderr.contains("exist") {
+            return Err(format!("Extraktion fehlgeschlagen: {}", tar_stderr));
+        }
+    }
+    fed
 }
 
-#[tauri::command]
-fn list_backups(target_path: String) -> Result<Vec<BackupListItem>, String> {
-    let data_path = PathBuf::from(&target_path)
-        .join("macos-backup-suite")
-        .join("data");
+/// Extract an item archive next to `target`. With a recorded `format` tar is called with the matching
+/// decompressor directly; older backups without one go through ditto and the zstd/gzip fallbacks.
+fn extract_tar_gz(archive: &Path, target: &Path, overwrite: bool, passphrase: Option<&str>, format: Option<ArchiveFormat>) -> Result<(), String> {
+    // Create parent directory if needed
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Fehler beim Erstellen des Verzeichnisses: {}", e))?;
+    }
     
-    if !data_path.exists() {
-        return Ok(Vec::new());
+    // Check if target exists and we're not overwriting
+    if !overwrite && target.exists() {
+        return Err("Ziel existiert bereits und Überschreiben ist deaktiviert".to_string());
     }
     
-    let mut backups = Vec::new();
-    if let Ok(entries) = fs::read_dir(&data_path) {
-        for entry in entries.flatten() {
-            if entry.path().is_dir() {
-                if let Some(name) = entry.file_name().to_str() {
-                    let metadata_path = entry.path().join("metadata.json");
-                    let hash_verified = metadata_path.exists();
-                    
-                    backups.push(BackupListItem {
-                        timestamp: name.to_string(),
-                        hash_verified,
-                    });
+    // Encrypted archives are decrypted to a temporary copy first
+    if let Some(pass) = passphrase {
+        let decrypt_dir = TempDirGuard::create("macos-backup-decrypt-extract")?;
+        let decrypted = decrypt_archive(archive, pass, &decrypt_dir)?;
+        return extract_tar_gz(&decrypted, target, overwrite, None, format);
+    }
+    
+    if let Some(format) = format {
+        let mut cmd = Command::new("tar");
+        cmd.current_dir(target.parent().unwrap_or(Path::new("/")));
+        if !overwrite {
+            cmd.arg("-k");
+        }
+        let decompress_args = match format {
+            ArchiveFormat::Zst => vec![zstd_decompress_arg_for(archive)],
+            _ => format.decompress_args(),
+        };
+        let output = run_restore_command(cmd
+            .args(decompress_args)
+            .args(["-xf", &archive.to_string_lossy()]))
+            .map_err(|e| format!("tar Fehler: {}", e))?;
+        
+        if !output.status.success() {
+            let tar_stderr = String::from_utf8_lossy(&output.stderr);
+            // -k causes error if files exist but that's expected when not overwriting
+            if overwrite || !tar_stderr.contains("exist") {
+                return Err(format!("Extraktion fehlgeschlagen: {}", tar_stderr));
+            }
+        }
+        return Ok(());
+    }
+    
+    // Use ditto to extract (better for macOS, preserves attributes, merges into existing dirs)
+    // ditto extracts archives and merges with existing directories
+    let output = run_restore_command(Command::new("ditto")
+        .args(["-x", "-k", &archive.to_string_lossy(), &target.parent().unwrap_or(Path::new("/")).to_string_lossy()]))
+        .map_err(|e| format!("ditto Fehler: {}", e))?;
+    
+    if !output.status.success() {
+        // Fallback to tar if ditto fails (for .tar.gz or .tar.zst files)
+        let archive_str = archive.to_string_lossy().to_string();
+        
+        // Check if zstd is available for decompression
+        let zstd_available = find_zstd_path().is_some();
+        
+        let tar_output = if zstd_available {
+            // Try zstd first (handles both .zst and auto-detects format)
+            let result = if overwrite {
+                run_restore_command(Command::new("tar")
+                    .current_dir(target.parent().unwrap_or(Path::new("/")))
+                    .args([&zstd_decompress_arg(), "-xf", &archive_str]))
+            } else {
+                run_restore_command(Command::new("tar")
+                    .current_dir(target.parent().unwrap_or(Path::new("/")))
+                    .args(["-k", &zstd_decompress_arg(), "-xf", &archive_str]))
+            };
+            
+            // If zstd fails, try gzip (for older backups)
+            match result {
+                Ok(o) if !o.status.success() => {
+                    if overwrite {
+                        run_restore_command(Command::new("tar")
+                            .current_dir(target.parent().unwrap_or(Path::new("/")))
+                            .args(["-xzf", &archive_str]))
+                    } else {
+                        run_restore_command(Command::new("tar")
+                            .current_dir(target.parent().unwrap_or(Path::new("/")))
+                            .args(["-k", "-xzf", &archive_str]))
+                    }
                 }
+                other => other
+            }
+        } else {
+            // No zstd, use gzip
+            if overwrite {
+                run_restore_command(Command::new("tar")
+                    .current_dir(target.parent().unwrap_or(Path::new("/")))
+                    .args(["-xzf", &archive_str]))
+            } else {
+                run_restore_command(Command::new("tar")
+                    .current_dir(target.parent().unwrap_or(Path::new("/")))
+                    .args(["-k", "-xzf", &archive_str]))
+            }
+        }.map_err(|e| format!("tar Fehler: {}", e))?;
+        
+        if !tar_output.status.success() {
+            let tar_stderr = String::from_utf8_lossy(&tar_output.stderr);
+            // -k causes error if files exist but that's expected when not overwriting
+            if !(overwrite == false && tar_stderr.contains("exist")) {
+                return Err(format!("Extraktion fehlgeschlagen: {}", tar_stderr));
             }
         }
     }
     
-    backups.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-    Ok(backups)
+    Ok(())
 }
 
-#[tauri::command]
-fn get_manual_apps_from_backup(target_path: String, timestamp: String) -> Result<Vec<String>, String> {
-    let inventory_path = PathBuf::from(&target_path)
-        .join("macos-backup-suite")
-        .join("inventories")
-        .join(&timestamp)
-        .join("manual_apps.txt");
-    
-    if !inventory_path.exists() {
-        return Err("Datei manual_apps.txt nicht gefunden".to_string());
-    }
-    
-    let content = fs::read_to_string(&inventory_path)
-        .map_err(|e| format!("Fehler beim Lesen: {}", e))?;
-    
-    let apps: Vec<String> = content
-        .lines()
-        .filter(|l| !l.trim().is_empty())
-        .map(|l| l.to_string())
-        .collect();
-    
-    Ok(apps)
+/// Archives of a backup item as found by `locate_item_archives`
+struct ItemArchives {
+    /// One archive, the parts of a split directory or the volumes of a multi-volume archive
+    paths: Vec<PathBuf>,
+    /// `paths` are slices of a single tar stream and can only be read in order
+    multi_volume: bool,
+    format: Option<ArchiveFormat>,
+    /// Passphrase of the volumes, which stay encrypted until they are streamed
+    passphrase: Option<String>,
 }
 
-#[tauri::command]
-fn show_help_window(app_handle: tauri::AppHandle) -> Result<(), String> {
-    use tauri::WebviewUrl;
-    
-    // Check if help window already exists
-    if let Some(window) = app_handle.get_webview_window("help") {
-        window.set_focus().map_err(|e| e.to_string())?;
-        return Ok(());
+impl ItemArchives {
+    /// Run `tar <mode> -f … <members>` in `dir` once per archive, or once for a multi-volume
+    /// archive with its volumes streamed into tar in order. Returns the output of every run.
+    fn run_tar(&self, dir: &Path, mode: &str, members: &[&str], decrypt_dir: &Path) -> Result<Vec<std::process::Output>, String> {
+        use std::process::Stdio;
+        
+        if !self.multi_volume {
+            return self.paths.iter().map(|archive| {
+                Command::new("tar")
+                    .current_dir(dir)
+                    .args(tar_decompress_args(archive))
+                    .args([mode, "-f", &archive.to_string_lossy()])
+                    .args(members)
+                    .output()
+                    .map_err(|e| format!("tar Fehler: {}", e))
+            }).collect();
+        }
+        
+        let format = self.format.ok_or("Archivformat der Teildateien unbekannt")?;
+        let mut child = Command::new("tar")
+            .current_dir(dir)
+            .args(format.decompress_args())
+            .args([mode, "-f", "-"])
+            .args(members)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("tar Fehler: {}", e))?;
+        let mut stdin = child.stdin.take().ok_or("tar nimmt keine Eingabe an")?;
+        let mut volumes = VolumeReader::new(&self.paths, self.passphrase.as_deref(), decrypt_dir);
+        // Fed from a thread so tar's output is drained meanwhile
+        let feeder = std::thread::spawn(move || std::io::copy(&mut volumes, &mut stdin).map(|_| ()));
+        let output = child.wait_with_output().map_err(|e| format!("tar Fehler: {}", e))?;
+        let fed = feeder.join().unwrap_or_else(|_| Err(std::io::Error::other("Teildateien konnten nicht gelesen werden")));
+        // Once tar succeeded a closed pipe doesn't matter; otherwise a read error explains the failure
+        match fed {
+            Err(e) if !output.status.success() => Err(e.to_string()),
+            _ => Ok(vec![output]),
+        }
     }
     
-    // Create help window
-    let help_window = tauri::WebviewWindowBuilder::new(
-        &app_handle,
-        "help",
-        WebviewUrl::App("help.html".into())
-    )
-    .title("macOS Backup Suite - Hilfe")
-    .inner_size(800.0, 600.0)
-    .resizable(true)
-    .build()
-    .map_err(|e| e.to_string())?;
-    
-    help_window.set_focus().map_err(|e| e.to_string())?;
-    
-    Ok(())
+    /// Reader of the decompressed tar stream of a multi-volume archive. For zstd the returned
+    /// child process must be waited on once the reader has been dropped.
+    fn open_volume_stream(&self, decrypt_dir: &Path) -> Result<(Box<dyn Read>, Option<std::process::Child>), String> {
+        let mut volumes = VolumeReader::new(&self.paths, self.passphrase.as_deref(), decrypt_dir);
+        match self.format.ok_or("Archivformat der Teildateien unbekannt")? {
+            ArchiveFormat::Gz => Ok((Box::new(flate2::read::GzDecoder::new(volumes)), None)),
+            ArchiveFormat::Tar => Ok((Box::new(volumes), None)),
+            ArchiveFormat::Zst => {
+                let zstd = find_zstd_path().ok_or("zstd nicht gefunden")?;
+                let mut child = Command::new(zstd)
+                    .args(["-d", "-c", "-q"])
+                    .stdin(std::process::Stdio::piped())
+                    .stdout(std::process::Stdio::piped())
+                    .spawn()
+                    .map_err(|e| format!("zstd Fehler: {}", e))?;
+                let mut stdin = child.stdin.take().ok_or("zstd nimmt keine Eingabe an")?;
+                std::thread::spawn(move || {
+                    let _ = std::io::copy(&mut volumes, &mut stdin);
+                });
+                let stdout = child.stdout.take().ok_or("zstd liefert keine Ausgabe")?;
+                Ok((Box::new(stdout), Some(child)))
+            }
+        }
+    }
 }
 
-#[tauri::command]
-async fn restore_items(
-    target_path: String,
-    timestamp: String,
-    items: Vec<String>,
-    overwrite: bool,
-    window: tauri::Window,
-) -> Result<RestoreResult, String> {
-    let backup_path = PathBuf::from(&target_path)
+/// Locate an item's archives in a backup: one archive, every part of a split directory or the
+/// volumes of a multi-volume archive. Encrypted archives other than volumes are decrypted into `decrypt_dir`.
+fn locate_item_archives(
+    target_path: &str,
+    timestamp: &str,
+    item_path: &str,
+    passphrase: Option<&str>,
+    decrypt_dir: &Path,
+) -> Result<ItemArchives, String> {
+    let backup_path = PathBuf::from(target_path)
         .join("macos-backup-suite")
         .join("data")
-        .join(&timestamp);
+        .join(timestamp);
     
     let metadata_path = backup_path.join("metadata.json");
     if !metadata_path.exists() {
         return Err(format!("Backup nicht gefunden: {}", timestamp));
     }
     
-    let metadata_content = fs::read_to_string(&metadata_path)
-        .map_err(|e| format!("Fehler beim Lesen der Metadaten: {}", e))?;
-    let metadata: BackupMetadata = serde_json::from_str(&metadata_content)
-        .map_err(|e| format!("Fehler beim Parsen: {}", e))?;
+    let metadata = read_metadata(&metadata_path)?;
     
-    let home = dirs::home_dir().ok_or("Home-Verzeichnis nicht gefunden")?;
-    let mut restored: Vec<String> = Vec::new();
-    let mut skipped: Vec<String> = Vec::new();
-    let mut errors: Vec<String> = Vec::new();
+    let item = metadata.items.iter()
+        .find(|it| it.path == item_path)
+        .ok_or_else(|| format!("{}: Nicht im Backup gefunden", item_path))?;
     
-    let total = items.len();
+    let archive_paths: Vec<PathBuf> = archive_files(std::slice::from_ref(item)).iter()
+        .map(|file| backup_path.join(&file.archive))
+        .collect();
+    if archive_paths.iter().any(|path| !path.exists()) {
+        return Err(format!("{}: Archiv nicht gefunden", item_path));
+    }
     
-    for (i, item_path) in items.iter().enumerate() {
-        // Progress: Start each item at a percentage, complete after operation
-        let start_progress = (i * 100) / total;
-        let end_progress = ((i + 1) * 100) / total;
-        
-        let _ = window.emit("restore-progress", serde_json::json!({
-            "progress": start_progress,
-            "message": format!("Stelle wieder her: {}", item_path)
-        }));
-        
-        // Find the backup item
-        let backup_item = metadata.items.iter().find(|it| &it.path == item_path);
-        if backup_item.is_none() {
-            errors.push(format!("{}: Nicht im Backup gefunden", item_path));
-            continue;
-        }
-        let backup_item = backup_item.unwrap();
-        
-        // Special handling for different item types
-        if item_path == "homebrew-packages" {
-            let action = if overwrite { "Reinstalliere" } else { "Installiere fehlende" };
-            let _ = window.emit("restore-log", format!("{} Homebrew-Pakete...", action));
-            match restore_homebrew_packages(&backup_path, &backup_item.archive, overwrite) {
-                Ok(count) => {
-                    if count > 0 {
-                        restored.push(format!("{} ({} neu installiert)", item_path, count));
-                        let _ = window.emit("restore-log", format!("✅ {} Homebrew-Pakete neu installiert/aktualisiert", count));
-                    } else {
-                        restored.push(format!("{} (alle bereits vorhanden)", item_path));
-                        let _ = window.emit("restore-log", format!("✅ Alle Homebrew-Pakete waren bereits installiert"));
-                    }
-                }
-                Err(e) => {
-                    errors.push(format!("{}: {}", item_path, e));
-                    let _ = window.emit("restore-log", format!("❌ Homebrew-Fehler: {}", e));
-                }
+    let passphrase = if metadata.encrypted {
+        Some(passphrase
+            .filter(|p| !p.is_empty())
+            .ok_or("Backup ist verschlüsselt - bitte Passphrase angeben")?)
+    } else {
+        None
+    };
+    
+    // Volumes are decrypted one at a time while they are streamed
+    if item.multi_volume {
+        return Ok(ItemArchives {
+            paths: archive_paths,
+            multi_volume: true,
+            format: item.format,
+            passphrase: passphrase.map(str::to_string),
+        });
+    }
+    let paths = match passphrase {
+        Some(pass) => archive_paths.iter().map(|path| decrypt_archive(path, pass, decrypt_dir)).collect::<Result<_, _>>()?,
+        None => archive_paths,
+    };
+    Ok(ItemArchives { paths, multi_volume: false, format: item.format, passphrase: None })
+}
+
+/// List the members of an item's archives so the UI can present a file tree
+#[tauri::command]
+async fn list_archive_contents(
+    target_path: String,
+    timestamp: String,
+    item_path: String,
+    encryption_passphrase: Option<String>,
+) -> Result<Vec<ArchiveEntry>, String> {
+    let decrypt_dir = staging_dir().join("macos-backup-list");
+    let result = locate_item_archives(&target_path, &timestamp, &item_path, encryption_passphrase.as_deref(), &decrypt_dir)
+        .and_then(|archives| {
+            if archives.multi_volume {
+                let (reader, zstd_child) = archives.open_volume_stream(&decrypt_dir)?;
+                return read_tar_entries(reader, zstd_child);
             }
-            let _ = window.emit("restore-progress", serde_json::json!({
-                "progress": end_progress,
-                "message": "Homebrew abgeschlossen"
-            }));
-            continue;
-        }
-        
-        if item_path == "mas-apps" {
-            let action = if overwrite { "Reinstalliere" } else { "Installiere fehlende" };
-            let _ = window.emit("restore-log", format!("{} Mac App Store Apps...", action));
-            match restore_mas_apps(&backup_path, &backup_item.archive, overwrite) {
-                Ok(count) => {
-                    restored.push(format!("{} ({} Apps)", item_path, count));
-                    let _ = window.emit("restore-log", format!("✅ {} MAS Apps installiert", count));
-                }
-                Err(e) => {
-                    errors.push(format!("{}: {}", item_path, e));
-                    let _ = window.emit("restore-log", format!("❌ MAS-Fehler: {}", e));
-                }
+            // Parts of a split directory can repeat parent directory entries
+            let mut seen = std::collections::HashSet::new();
+            let mut entries = Vec::new();
+            for archive in &archives.paths {
+                entries.extend(list_tar_entries(archive)?.into_iter().filter(|entry| seen.insert(entry.path.clone())));
             }
-            let _ = window.emit("restore-progress", serde_json::json!({
-                "progress": end_progress,
-                "message": "MAS Apps abgeschlossen"
-            }));
-            continue;
-        }
-        
-        if item_path == "vscode-extensions" {
-            let action = if overwrite { "Reinstalliere" } else { "Installiere fehlende" };
-            let _ = window.emit("restore-log", format!("{} VS Code Extensions...", action));
-            match restore_vscode_extensions(&backup_path, &backup_item.archive, overwrite) {
-                Ok(count) => {
-                    restored.push(format!("{} ({} Extensions)", item_path, count));
-                    let _ = window.emit("restore-log", format!("✅ {} VS Code Extensions installiert", count));
-                }
-                Err(e) => {
-                    errors.push(format!("{}: {}", item_path, e));
-                    let _ = window.emit("restore-log", format!("❌ VS Code-Fehler: {}", e));
+            Ok(entries)
+        });
+    let _ = fs::remove_dir_all(&decrypt_dir);
+    result
+}
+
+/// Restore a single file or subfolder from an archive into `destination`
+#[tauri::command]
+async fn restore_single_file(
+    target_path: String,
+    timestamp: String,
+    item_path: String,
+    inner_path: String,
+    destination: String,
+    encryption_passphrase: Option<String>,
+) -> Result<String, String> {
+    let destination = PathBuf::from(&destination);
+    fs::create_dir_all(&destination)
+        .map_err(|e| format!("Fehler beim Erstellen des Verzeichnisses: {}", e))?;
+    
+    let decrypt_dir = staging_dir().join("macos-backup-single");
+    let result = locate_item_archives(&target_path, &timestamp, &item_path, encryption_passphrase.as_deref(), &decrypt_dir)
+        .and_then(|archives| {
+            // A subfolder of a split directory can be spread over several parts, so every part is
+            // searched; parts without `inner_path` fail with "not found in archive"
+            let mut found = false;
+            let mut last_error = String::new();
+            for output in archives.run_tar(&destination, "-x", &[&inner_path], &decrypt_dir)? {
+                if output.status.success() {
+                    found = true;
+                } else {
+                    last_error = String::from_utf8_lossy(&output.stderr).trim().to_string();
                 }
             }
-            let _ = window.emit("restore-progress", serde_json::json!({
-                "progress": end_progress,
-                "message": "VS Code abgeschlossen"
-            }));
-            continue;
-        }
-        
-        // Safari settings restore
-        if item_path == "safari-settings" {
-            let _ = window.emit("restore-log", "Stelle Safari-Einstellungen wieder her...".to_string());
-            match restore_safari_settings(&backup_path, &backup_item.archive) {
-                Ok(count) => {
-                    restored.push(format!("{} ({} Dateien)", item_path, count));
-                    let _ = window.emit("restore-log", format!("✅ {} Safari-Einstellungen wiederhergestellt", count));
-                }
-                Err(e) => {
-                    errors.push(format!("{}: {}", item_path, e));
-                    let _ = window.emit("restore-log", format!("❌ Safari-Fehler: {}", e));
-                }
+            
+            if !found {
+                return Err(format!("Extraktion fehlgeschlagen: {}", last_error));
+            }
+            Ok(destination.join(&inner_path).to_string_lossy().to_string())
+        });
+    let _ = fs::remove_dir_all(&decrypt_dir);
+    result
+}
+
+/// Parent of all `mount_archive_readonly` directories inside the staging dir
+fn mount_root() -> PathBuf {
+    staging_dir().join("macos-backup-browse")
+}
+
+/// Make a mounted directory writable again and delete it
+fn remove_mounted_dir(dir: &Path) -> Result<(), String> {
+    let _ = Command::new("chmod").arg("-R").arg("u+w").arg(dir).output();
+    fs::remove_dir_all(dir).map_err(|e| format!("Fehler beim Entfernen von {}: {}", dir.display(), e))
+}
+
+/// Extract an item's archives into a read-only temp directory for browsing, without restoring it.
+/// Returns the directory; it is revealed in Finder unless `reveal` is false.
+#[tauri::command]
+async fn mount_archive_readonly(
+    target_path: String,
+    timestamp: String,
+    item_path: String,
+    encryption_passphrase: Option<String>,
+    reveal: Option<bool>,
+) -> Result<String, String> {
+    validate_staging_dir()?;
+    let root = mount_root();
+    
+    // Leftovers from earlier sessions are not tracked anymore and would only accumulate
+    if let (Ok(entries), Ok(mounted)) = (fs::read_dir(&root), MOUNTED_ARCHIVES.lock()) {
+        for entry in entries.flatten() {
+            // Dot directories are decryption scratch space of a mount that is still running
+            if !mounted.contains(&entry.path()) && !entry.file_name().to_string_lossy().starts_with('.') {
+                let _ = remove_mounted_dir(&entry.path());
             }
-            let _ = window.emit("restore-progress", serde_json::json!({
-                "progress": end_progress,
-                "message": "Safari abgeschlossen"
-            }));
-            continue;
         }
-        
-        // Homebrew cache restore
-        if item_path == "homebrew-cache" {
-            let _ = window.emit("restore-log", "Stelle Homebrew-Cache wieder her...".to_string());
-            match restore_homebrew_cache(&backup_path, &backup_item.archive) {
-                Ok(size_mb) => {
-                    restored.push(format!("{} ({} MB)", item_path, size_mb));
-                    let _ = window.emit("restore-log", format!("✅ Homebrew-Cache wiederhergestellt ({} MB)", size_mb));
-                }
-                Err(e) => {
-                    errors.push(format!("{}: {}", item_path, e));
-                    let _ = window.emit("restore-log", format!("❌ Homebrew-Cache-Fehler: {}", e));
+    }
+    
+    let name = Path::new(&item_path).file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "archiv".to_string());
+    let mount_dir = root.join(format!("{}-{}", timestamp, name));
+    if mount_dir.exists() {
+        remove_mounted_dir(&mount_dir)?;
+    }
+    fs::create_dir_all(&mount_dir).map_err(|e| format!("Fehler beim Erstellen des Verzeichnisses: {}", e))?;
+    
+    let decrypt_dir = root.join(format!(".{}-{}-decrypt", timestamp, name));
+    let result = locate_item_archives(&target_path, &timestamp, &item_path, encryption_passphrase.as_deref(), &decrypt_dir)
+        .and_then(|archives| {
+            // The parts of a split directory are extracted into the same directory
+            for output in archives.run_tar(&mount_dir, "-x", &[], &decrypt_dir)? {
+                if !output.status.success() {
+                    return Err(format!("Entpacken fehlgeschlagen: {}", String::from_utf8_lossy(&output.stderr).trim()));
                 }
             }
-            let _ = window.emit("restore-progress", serde_json::json!({
-                "progress": end_progress,
-                "message": "Homebrew-Cache abgeschlossen"
-            }));
-            continue;
-        }
-        
-        // Regular directory/file restore
-        let archive_path = backup_path.join(&backup_item.archive);
-        if !archive_path.exists() {
-            errors.push(format!("{}: Archiv nicht gefunden", item_path));
-            continue;
-        }
-        
-        // Determine target path
-        let target = if item_path.starts_with("~/") {
-            home.join(&item_path[2..])
-        } else if item_path.starts_with('/') {
-            PathBuf::from(item_path)
-        } else {
-            home.join(item_path)
-        };
-        
-        // Check if target exists
-        if target.exists() && !overwrite {
-            skipped.push(format!("{}: Existiert bereits", item_path));
-            let _ = window.emit("restore-log", format!("⏭️ Übersprungen: {} (existiert)", item_path));
-            continue;
-        }
+            Ok(())
+        });
+    let _ = fs::remove_dir_all(&decrypt_dir);
+    if let Err(e) = result {
+        let _ = remove_mounted_dir(&mount_dir);
+        return Err(e);
+    }
+    
+    // Read-only so browsing can't change anything that might later be compared or copied
+    let _ = Command::new("chmod").arg("-R").arg("a-w").arg(&mount_dir).output();
+    if let Ok(mut mounted) = MOUNTED_ARCHIVES.lock() {
+        mounted.push(mount_dir.clone());
+    }
+    
+    if reveal.unwrap_or(true) {
+        let _ = Command::new("open").arg(&mount_dir).output();
+    }
+    Ok(mount_dir.to_string_lossy().to_string())
+}
+
+/// Remove a directory created by `mount_archive_readonly`
+#[tauri::command]
+fn unmount_archive(temp_path: String) -> Result<(), String> {
+    let dir = PathBuf::from(&temp_path);
+    let mut mounted = MOUNTED_ARCHIVES.lock().map_err(|e| e.to_string())?;
+    // Only directories this app mounted may be deleted through this command
+    let position = mounted.iter().position(|d| *d == dir)
+        .ok_or_else(|| format!("{} ist kein geöffnetes Archiv", temp_path))?;
+    remove_mounted_dir(&dir)?;
+    mounted.remove(position);
+    Ok(())
+}
+
+/// Longest time a single install command may run before it is killed
+const EXTERNAL_COMMAND_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30 * 60);
+
+/// Base directory for restore staging: `staging_dir` from the config, or the system temp dir
+fn staging_dir() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_default();
+    load_config()
+        .ok()
+        .and_then(|config| config.staging_dir)
+        .filter(|dir| !dir.trim().is_empty())
+        .map(|dir| expand_tilde(&dir, &home))
+        .unwrap_or_else(std::env::temp_dir)
+}
+
+/// Check that a configured staging directory can be used before a restore starts
+fn validate_staging_dir() -> Result<(), String> {
+    let dir = staging_dir();
+    if !dir.is_dir() {
+        return Err(format!("Staging-Verzeichnis existiert nicht: {}", dir.display()));
+    }
+    if !is_writable(&dir) {
+        return Err(format!("Staging-Verzeichnis ist nicht beschreibbar: {}", dir.display()));
+    }
+    Ok(())
+}
+
+/// Home directory a restore writes into: `target_home` when an admin restores into another
+/// account, otherwise the current user's. Returns the directory and whether it is a different account.
+fn resolve_restore_home(target_home: Option<String>) -> Result<(PathBuf, bool), String> {
+    let own_home = dirs::home_dir().ok_or("Home-Verzeichnis nicht gefunden")?;
+    let Some(target_home) = target_home.filter(|h| !h.trim().is_empty()) else {
+        return Ok((own_home, false));
+    };
+    let home = PathBuf::from(target_home.trim());
+    if !home.is_dir() {
+        return Err(format!("Ziel-Benutzerordner existiert nicht: {}", home.display()));
+    }
+    if !is_writable(&home) {
+        return Err(format!("Ziel-Benutzerordner ist nicht beschreibbar (als Administrator ausführen?): {}", home.display()));
+    }
+    let foreign = fs::canonicalize(&home).ok() != fs::canonicalize(&own_home).ok();
+    Ok((home, foreign))
+}
+
+/// Hand everything this process created in another user's home over to that user,
+/// so restored files don't end up owned by the admin running the restore
+fn chown_restored_files(home: &Path) -> Result<(), String> {
+    use std::os::unix::fs::MetadataExt;
+    let meta = fs::metadata(home).map_err(|e| e.to_string())?;
+    let own_uid = unsafe { libc::getuid() };
+    if own_uid == meta.uid() {
+        return Ok(());
+    }
+    let owner = format!("{}:{}", meta.uid(), meta.gid());
+    let output = Command::new("find")
+        .arg(home)
+        .args(["-user", &own_uid.to_string(), "-exec", "chown", "-h", &owner, "{}", "+"])
+        .output()
+        .map_err(|e| format!("find Fehler: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(())
+}
+
+/// Special items that act on the logged-in user's session (launchctl, crontab, cfprefsd, VS Code CLI)
+/// and can't be restored into another account's home
+const SESSION_BOUND_ITEMS: &[&str] = &["vscode-extensions", "startup-items", "scheduled-tasks", "dock-layout", "system-defaults", "terminal-settings"];
+
+/// Temporary directory that is removed again when dropped, also on early returns
+struct TempDirGuard(PathBuf);
+
+impl TempDirGuard {
+    /// Create a new private (0700) directory named after `name` in the staging dir. The process id
+    /// and a counter make the name unique, so concurrent runs never share a directory.
+    fn create(name: &str) -> Result<Self, String> {
+        use std::os::unix::fs::DirBuilderExt;
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
         
-        // Extract archive
-        let _ = window.emit("restore-log", format!("📦 Extrahiere: {}", item_path));
-        match extract_tar_gz(&archive_path, &target, overwrite) {
-            Ok(_) => {
-                restored.push(item_path.clone());
-                let _ = window.emit("restore-log", format!("✅ Wiederhergestellt: {}", item_path));
-            }
-            Err(e) => {
-                errors.push(format!("{}: {}", item_path, e));
-                let _ = window.emit("restore-log", format!("❌ Fehler: {} - {}", item_path, e));
-            }
-        }
+        let parent = staging_dir();
+        fs::create_dir_all(&parent).map_err(|e| e.to_string())?;
+        let path = parent.join(format!("{}-{}-{}", name, std::process::id(), NEXT.fetch_add(1, Ordering::SeqCst)));
+        // Left behind by a crashed process that had the same id
+        let _ = fs::remove_dir_all(&path);
+        fs::DirBuilder::new()
+            .mode(0o700)
+            .create(&path)
+            .map_err(|e| format!("Temporäres Verzeichnis konnte nicht angelegt werden: {}", e))?;
+        Ok(Self(path))
     }
+}
+
+impl std::ops::Deref for TempDirGuard {
+    type Target = Path;
     
-    Ok(RestoreResult {
-        restored_count: restored.len(),
-        skipped_count: skipped.len(),
-        error_count: errors.len(),
-        restored,
-        skipped,
-        errors,
-    })
+    fn deref(&self) -> &Path {
+        &self.0
+    }
 }
 
-fn extract_tar_gz(archive: &Path, target: &Path, overwrite: bool) -> Result<(), String> {
-    // Create parent directory if needed
-    if let Some(parent) = target.parent() {
-        fs::create_dir_all(parent).map_err(|e| format!("Fehler beim Erstellen des Verzeichnisses: {}", e))?;
+impl AsRef<Path> for TempDirGuard {
+    fn as_ref(&self) -> &Path {
+        &self.0
     }
-    
-    // Check if target exists and we're not overwriting
-    if !overwrite && target.exists() {
-        return Err("Ziel existiert bereits und Überschreiben ist deaktiviert".to_string());
+}
+
+impl Drop for TempDirGuard {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
     }
+}
+
+/// Run a command and pass every stdout/stderr line to `on_line` as soon as it is printed.
+/// Returns whether the command succeeded and all output lines, or an error if it exceeded `EXTERNAL_COMMAND_TIMEOUT`.
+fn run_streaming(mut cmd: Command, on_line: impl Fn(&str)) -> Result<(bool, Vec<String>), String> {
+    use std::io::BufRead;
+    use std::os::unix::process::CommandExt;
+    use std::process::Stdio;
+    use std::sync::mpsc::RecvTimeoutError;
     
-    // Use ditto to extract (better for macOS, preserves attributes, merges into existing dirs)
-    // ditto extracts archives and merges with existing directories
-    let output = Command::new("ditto")
-        .args(["-x", "-k", &archive.to_string_lossy(), &target.parent().unwrap_or(Path::new("/")).to_string_lossy()])
-        .output()
-        .map_err(|e| format!("ditto Fehler: {}", e))?;
+    if RESTORE_CANCELLED.load(Ordering::SeqCst) {
+        return Err("Wiederherstellung abgebrochen".to_string());
+    }
+    // Own process group so a timeout (or cancel_restore) also kills helpers spawned by brew/mas
+    unsafe {
+        cmd.pre_exec(|| {
+            libc::setpgid(0, 0);
+            Ok(())
+        });
+    }
+    let mut child = cmd
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    track_restore_pid(child.id());
+    
+    let (tx, rx) = std::sync::mpsc::channel::<String>();
+    let mut readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        let tx = tx.clone();
+        readers.push(std::thread::spawn(move || {
+            for line in std::io::BufReader::new(stdout).lines().map_while(Result::ok) {
+                let _ = tx.send(line);
+            }
+        }));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        let tx = tx.clone();
+        readers.push(std::thread::spawn(move || {
+            for line in std::io::BufReader::new(stderr).lines().map_while(Result::ok) {
+                let _ = tx.send(line);
+            }
+        }));
+    }
+    drop(tx);
     
-    if !output.status.success() {
-        // Fallback to tar if ditto fails (for .tar.gz or .tar.zst files)
-        let archive_str = archive.to_string_lossy().to_string();
-        
-        // Check if zstd is available for decompression
-        let zstd_available = Command::new("which")
-            .arg("zstd")
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false);
-        
-        let tar_output = if zstd_available {
-            // Try zstd first (handles both .zst and auto-detects format)
-            let result = if overwrite {
-                Command::new("tar")
-                    .current_dir(target.parent().unwrap_or(Path::new("/")))
-                    .args(["--use-compress-program=zstd -d", "-xf", &archive_str])
-                    .output()
-            } else {
-                Command::new("tar")
-                    .current_dir(target.parent().unwrap_or(Path::new("/")))
-                    .args(["-k", "--use-compress-program=zstd -d", "-xf", &archive_str])
-                    .output()
-            };
-            
-            // If zstd fails, try gzip (for older backups)
-            match result {
-                Ok(o) if !o.status.success() => {
-                    if overwrite {
-                        Command::new("tar")
-                            .current_dir(target.parent().unwrap_or(Path::new("/")))
-                            .args(["-xzf", &archive_str])
-                            .output()
-                    } else {
-                        Command::new("tar")
-                            .current_dir(target.parent().unwrap_or(Path::new("/")))
-                            .args(["-k", "-xzf", &archive_str])
-                            .output()
-                    }
+    let deadline = std::time::Instant::now() + EXTERNAL_COMMAND_TIMEOUT;
+    let mut lines = Vec::new();
+    loop {
+        match rx.recv_timeout(deadline.saturating_duration_since(std::time::Instant::now())) {
+            Ok(line) => {
+                if !line.trim().is_empty() {
+                    on_line(&line);
                 }
-                other => other
-            }
-        } else {
-            // No zstd, use gzip
-            if overwrite {
-                Command::new("tar")
-                    .current_dir(target.parent().unwrap_or(Path::new("/")))
-                    .args(["-xzf", &archive_str])
-                    .output()
-            } else {
-                Command::new("tar")
-                    .current_dir(target.parent().unwrap_or(Path::new("/")))
-                    .args(["-k", "-xzf", &archive_str])
-                    .output()
+                lines.push(line);
             }
-        }.map_err(|e| format!("tar Fehler: {}", e))?;
-        
-        if !tar_output.status.success() {
-            let tar_stderr = String::from_utf8_lossy(&tar_output.stderr);
-            // -k causes error if files exist but that's expected when not overwriting
-            if !(overwrite == false && tar_stderr.contains("exist")) {
-                return Err(format!("Extraktion fehlgeschlagen: {}", tar_stderr));
+            Err(RecvTimeoutError::Disconnected) => break,
+            Err(RecvTimeoutError::Timeout) => {
+                unsafe {
+                    libc::killpg(child.id() as i32, libc::SIGKILL);
+                }
+                let _ = child.wait();
+                untrack_restore_pid(child.id());
+                return Err(format!("Zeitüberschreitung nach {} Minuten", EXTERNAL_COMMAND_TIMEOUT.as_secs() / 60));
             }
         }
     }
+    for reader in readers {
+        let _ = reader.join();
+    }
     
-    Ok(())
+    let status = child.wait();
+    untrack_restore_pid(child.id());
+    let status = status.map_err(|e| e.to_string())?;
+    Ok((status.success(), lines))
+}
+
+/// Run `make_command(item)` for every item with at most `max_parallel` commands at a time,
+/// streaming their output as `restore-log` events prefixed with the item.
+/// Returns the items whose command failed.
+fn run_install_pool(
+    items: &[String],
+    max_parallel: usize,
+    sink: &dyn ProgressSink,
+    make_command: fn(&str) -> Command,
+) -> Vec<String> {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::Mutex;
+    
+    let next = AtomicUsize::new(0);
+    let failed = Mutex::new(Vec::new());
+    
+    std::thread::scope(|scope| {
+        for _ in 0..max_parallel.min(items.len()) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                let Some(item) = items.get(index) else { break };
+                sink.log(&format!("📦 Installiere {}...", item));
+                let result = run_streaming(make_command(item), |line| {
+                    sink.log(&format!("   [{}] {}", item, line));
+                });
+                match result {
+                    Ok((true, _)) => {
+                        sink.log(&format!("✅ {} installiert", item));
+                    }
+                    _ => {
+                        sink.log(&format!("⚠️ {} fehlgeschlagen", item));
+                        if let Ok(mut failed) = failed.lock() {
+                            failed.push(item.clone());
+                        }
+                    }
+                }
+            });
+        }
+    });
+    
+    failed.into_inner().unwrap_or_default()
+}
+
+/// Casks from `casks` that declare a `depends_on arch` not matching this Mac (via `brew info --json=v2`)
+fn incompatible_casks(casks: &[String]) -> Vec<String> {
+    if casks.is_empty() {
+        return Vec::new();
+    }
+    let Some(brew_path) = find_brew_path() else { return Vec::new() };
+    let output = match Command::new(&brew_path)
+        .args(["info", "--json=v2", "--cask"])
+        .args(casks)
+        .output()
+    {
+        Ok(o) => o,
+        Err(_) => return Vec::new(),
+    };
+    let json: serde_json::Value = match serde_json::from_slice(&output.stdout) {
+        Ok(json) => json,
+        Err(_) => return Vec::new(),
+    };
+    
+    // brew names the architectures "arm" and "intel"
+    let current = if current_arch() == "arm64" { "arm" } else { "intel" };
+    json["casks"].as_array().map(|entries| {
+        entries.iter().filter_map(|cask| {
+            let archs = cask["depends_on"]["arch"].as_array()?;
+            let supported = archs.iter().any(|a| a["type"].as_str() == Some(current));
+            if supported {
+                None
+            } else {
+                cask["token"].as_str().map(|t| t.to_string())
+            }
+        }).collect()
+    }).unwrap_or_default()
 }
 
-fn restore_homebrew_packages(backup_path: &Path, archive_name: &str, reinstall: bool) -> Result<usize, String> {
+fn restore_homebrew_packages(backup_path: &Path, archive_name: &str, reinstall: bool, skip_incompatible_casks: bool, start_services: bool, sink: &dyn ProgressSink) -> Result<usize, String> {
     let archive = backup_path.join(archive_name);
     
     // Extract to temp dir
-    let temp_dir = std::env::temp_dir().join("macos-backup-restore");
-    fs::create_dir_all(&temp_dir).map_err(|e| e.to_string())?;
+    let temp_dir = TempDirGuard::create("macos-backup-restore")?;
     
     // Try zstd first, fallback to gzip for older backups
-    let zstd_available = Command::new("which")
-        .arg("zstd")
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false);
+    let zstd_available = find_zstd_path().is_some();
     
     let output = if zstd_available {
         let zstd_result = Command::new("tar")
             .current_dir(&temp_dir)
-            .args(["--use-compress-program=zstd -d", "-xf", &archive.to_string_lossy()])
+            .args([&zstd_decompress_arg(), "-xf", &archive.to_string_lossy()])
             .output();
         
         match zstd_result {
@@ -2020,42 +9625,62 @@ fn restore_homebrew_packages(backup_path: &Path, archive_name: &str, reinstall:
     fs::rename(&packages_file, &brewfile).map_err(|e| e.to_string())?;
     
     // Count entries (brew and cask lines only, not mas - those are handled separately)
-    let file_content = fs::read_to_string(&brewfile).map_err(|e| e.to_string())?;
+    let mut file_content = fs::read_to_string(&brewfile).map_err(|e| e.to_string())?;
+    if skip_incompatible_casks {
+        let casks: Vec<String> = file_content.lines()
+            .filter(|l| l.starts_with("cask \""))
+            .filter_map(|l| l.split('"').nth(1).map(|c| c.to_string()))
+            .collect();
+        let skipped = incompatible_casks(&casks);
+        if !skipped.is_empty() {
+            for cask in &skipped {
+                sink.log(&format!("⏭️ Überspringe Cask {} (nicht für diese Architektur)", cask));
+            }
+            file_content = file_content.lines()
+                .filter(|l| !skipped.iter().any(|c| l.starts_with(&format!("cask \"{}\"", c))))
+                .collect::<Vec<_>>()
+                .join("\n");
+            fs::write(&brewfile, &file_content).map_err(|e| e.to_string())?;
+        }
+    }
     let count = file_content.lines()
         .filter(|l| l.starts_with("brew ") || l.starts_with("cask ") || l.starts_with("tap "))
         .count();
     
     if count == 0 {
-        let _ = fs::remove_dir_all(&temp_dir);
         return Ok(0);
     }
     
     // Use brew bundle to install from Brewfile
     // --force will reinstall already installed packages
     let force_flag = if reinstall { " --force" } else { "" };
-    let output = Command::new("/bin/zsh")
-        .args(["-l", "-c", &format!("cd {:?} && brew bundle{}", temp_dir, force_flag)])
-        .output()
-        .map_err(|e| format!("brew bundle Fehler: {}", e))?;
+    let mut cmd = Command::new("/bin/zsh");
+    cmd.args(["-l", "-c", &format!("cd {:?} && brew bundle{}", &*temp_dir, force_flag)]);
+    let result = run_streaming(cmd, |line| {
+        sink.log(&format!("   {}", line));
+    });
     
-    // Cleanup
-    let _ = fs::remove_dir_all(&temp_dir);
+    let (success, lines) = result.map_err(|e| format!("brew bundle Fehler: {}", e))?;
+    
+    let services_file = temp_dir.join("brew_services.json");
+    if start_services && services_file.exists() {
+        start_brew_services(&services_file, sink);
+    }
     
     // Parse output to count what was actually installed/upgraded
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let installed = stdout.lines()
+    let installed = lines.iter()
         .filter(|l| l.starts_with("Installing ") || l.starts_with("Upgrading "))
         .count();
-    let _already_present = stdout.lines()
-        .filter(|l| l.starts_with("Using "))
-        .count();
     
     // brew bundle returns non-zero if some packages fail, but we still count it as partial success
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+    if !success && installed == 0 {
+        let errors: Vec<&str> = lines.iter()
+            .map(|l| l.as_str())
+            .filter(|l| l.to_lowercase().contains("error"))
+            .collect();
         // Only error if completely failed
-        if stderr.contains("error") && installed == 0 {
-            return Err(format!("brew bundle fehlgeschlagen: {}", stderr));
+        if !errors.is_empty() {
+            return Err(format!("brew bundle fehlgeschlagen: {}", errors.join("\n")));
         }
     }
     
@@ -2068,82 +9693,175 @@ fn restore_homebrew_packages(backup_path: &Path, archive_name: &str, reinstall:
     }
 }
 
-/// Quick-Restore mode: Install essential packages first for rapid productivity
-/// Essential brew packages: git, vim, python, node, curl, wget, htop, tree, jq, ripgrep
-/// Essential casks: visual-studio-code, iterm2, google-chrome, firefox, 1password
+/// Start the services that were running when the backup was made (`brew services start`)
+fn start_brew_services(services_file: &Path, sink: &dyn ProgressSink) {
+    let Some(brew_path) = find_brew_path() else { return };
+    let services: Vec<BrewService> = fs::read_to_string(services_file)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    
+    let mut started = Vec::new();
+    let mut failed = Vec::new();
+    for service in services.iter().filter(|s| s.status.as_deref() == Some("started")) {
+        sink.log(&format!("Starte Dienst {}...", service.name));
+        match run_restore_command(Command::new(&brew_path).args(["services", "start", &service.name])) {
+            Ok(o) if o.status.success() => started.push(service.name.clone()),
+            Ok(o) => failed.push(format!("{} ({})", service.name, String::from_utf8_lossy(&o.stderr).lines().next().unwrap_or("").trim())),
+            Err(e) => failed.push(format!("{} ({})", service.name, e)),
+        }
+    }
+    
+    if !started.is_empty() {
+        sink.log(&format!("✅ Homebrew-Dienste gestartet: {}", started.join(", ")));
+    }
+    if !failed.is_empty() {
+        sink.log(&format!("⚠️ Homebrew-Dienste nicht gestartet: {}", failed.join(", ")));
+    }
+}
+
+/// Position of an item in a full restore: package managers first so `code` and other
+/// tools exist for the following steps, then the remaining special items, then files
+fn restore_all_rank(item_path: &str) -> usize {
+    const LEADING: &[&str] = &["homebrew-cache", "homebrew-packages", "vscode-extensions", "mas-apps"];
+    if let Some(pos) = LEADING.iter().position(|p| *p == item_path) {
+        return pos;
+    }
+    match SPECIAL_ITEMS.iter().position(|p| *p == item_path) {
+        Some(pos) => LEADING.len() + pos,
+        None => LEADING.len() + SPECIAL_ITEMS.len(),
+    }
+}
+
+/// All items of a backup in restore order (see `restore_all_rank`)
+fn items_in_restore_order(target_path: &str, timestamp: &str) -> Result<Vec<String>, String> {
+    let metadata = read_backup_metadata(target_path, timestamp)?;
+    let mut items: Vec<String> = metadata.items.into_iter().map(|item| item.path).collect();
+    // Stable sort keeps the backup order among the files
+    items.sort_by_key(|path| restore_all_rank(path));
+    Ok(items)
+}
+
+/// Restore every item of a backup in a sensible order, through the same per-item logic as `restore_items`
 #[tauri::command]
-async fn quick_restore_essentials(
+async fn restore_all(
     target_path: String,
     timestamp: String,
+    overwrite: bool,
+    encryption_passphrase: Option<String>,
     window: tauri::Window,
 ) -> Result<RestoreResult, String> {
-    // Essential packages that make a system immediately usable
-    let essential_brews = vec![
-        "git", "vim", "python", "node", "curl", "wget", "htop", "tree", "jq", "ripgrep", "fd", "bat", "fzf"
-    ];
-    let essential_casks = vec![
-        "visual-studio-code", "iterm2", "google-chrome", "firefox", "1password", "rectangle", "alfred"
-    ];
-    
-    let brew_path = find_brew_path()
-        .ok_or_else(|| "Homebrew nicht gefunden".to_string())?;
+    let items = items_in_restore_order(&target_path, &timestamp)?;
+    let _ = window.emit("restore-log", format!("Stelle alle {} Elemente wieder her...", items.len()));
     
-    let mut restored: Vec<String> = Vec::new();
-    let mut skipped: Vec<String> = Vec::new();
-    let mut errors: Vec<String> = Vec::new();
+    restore_items(target_path, timestamp, items, overwrite, encryption_passphrase, None, None, None, None, None, None, window).await
+}
+
+/// Restore only the software inventory and settings, i.e. every special item (the complete Brewfile,
+/// App Store apps, VS Code extensions, ...) but no file or directory archive, e.g. when files come back from a cloud sync
+#[tauri::command]
+async fn restore_software_only(
+    target_path: String,
+    timestamp: String,
+    overwrite: bool,
+    encryption_passphrase: Option<String>,
+    window: tauri::Window,
+) -> Result<RestoreResult, String> {
+    let items: Vec<String> = items_in_restore_order(&target_path, &timestamp)?
+        .into_iter()
+        .filter(|path| SPECIAL_ITEMS.contains(&path.as_str()))
+        .collect();
+    if items.is_empty() {
+        return Err("Das Backup enthält kein Software-Inventar".to_string());
+    }
+    let _ = window.emit("restore-log", format!("Stelle {} Software-Elemente wieder her (ohne Dateien)...", items.len()));
     
-    // First, get the Brewfile from backup to check what was actually installed
-    let backup_path = PathBuf::from(&target_path)
+    restore_items(target_path, timestamp, items, overwrite, encryption_passphrase, None, None, None, None, None, None, window).await
+}
+
+/// Formulae and casks listed in the Brewfile of a backup
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+struct BackupPackages {
+    brews: Vec<String>,
+    casks: Vec<String>,
+}
+
+/// Read the Brewfile of the `homebrew-packages` item; empty if the backup has none
+fn read_backup_packages(target_path: &str, timestamp: &str, passphrase: Option<&str>) -> Result<BackupPackages, String> {
+    let backup_path = PathBuf::from(target_path)
         .join("macos-backup-suite")
         .join("data")
-        .join(&timestamp);
+        .join(timestamp);
     
     let metadata_path = backup_path.join("metadata.json");
     if !metadata_path.exists() {
         return Err(format!("Backup nicht gefunden: {}", timestamp));
     }
     
-    let metadata_content = fs::read_to_string(&metadata_path)
-        .map_err(|e| format!("Fehler beim Lesen der Metadaten: {}", e))?;
-    let metadata: BackupMetadata = serde_json::from_str(&metadata_content)
-        .map_err(|e| format!("Fehler beim Parsen: {}", e))?;
+    let metadata = read_metadata(&metadata_path)?;
     
     // Find homebrew-packages archive
     let brew_item = metadata.items.iter().find(|it| it.path == "homebrew-packages");
     
-    let mut packages_in_backup: Vec<String> = Vec::new();
-    let mut casks_in_backup: Vec<String> = Vec::new();
+    let mut packages = BackupPackages::default();
     
     if let Some(item) = brew_item {
-        // Extract and read Brewfile
-        let archive = backup_path.join(&item.archive);
-        let temp_dir = std::env::temp_dir().join("macos-backup-quick-restore");
-        let _ = fs::create_dir_all(&temp_dir);
-        
-        let _ = Command::new("tar")
-            .current_dir(&temp_dir)
-            .args(["-xzf", &archive.to_string_lossy()])
-            .output();
-        
-        let packages_file = temp_dir.join("homebrew_packages.txt");
-        if packages_file.exists() {
-            if let Ok(content) = fs::read_to_string(&packages_file) {
-                for line in content.lines() {
-                    if line.starts_with("brew \"") {
-                        if let Some(pkg) = line.split('"').nth(1) {
-                            packages_in_backup.push(pkg.to_string());
-                        }
-                    } else if line.starts_with("cask \"") {
-                        if let Some(cask) = line.split('"').nth(1) {
-                            casks_in_backup.push(cask.to_string());
-                        }
-                    }
+        // Read the Brewfile straight from the (decrypted) archive, whatever its compression
+        let passphrase = if metadata.encrypted {
+            Some(passphrase.filter(|p| !p.is_empty()).ok_or("Backup ist verschlüsselt - bitte Passphrase angeben")?)
+        } else {
+            None
+        };
+        let temp_dir = TempDirGuard::create("macos-backup-quick-restore")?;
+        let archive = plain_item_archive(&backup_path, item, passphrase, &temp_dir)?;
+        let content = read_archive_text_member(&archive, "homebrew_packages.txt")?;
+        for line in content.lines() {
+            if line.starts_with("brew \"") {
+                if let Some(pkg) = line.split('"').nth(1) {
+                    packages.brews.push(pkg.to_string());
+                }
+            } else if line.starts_with("cask \"") {
+                if let Some(cask) = line.split('"').nth(1) {
+                    packages.casks.push(cask.to_string());
                 }
             }
         }
-        let _ = fs::remove_dir_all(&temp_dir);
     }
     
+    Ok(packages)
+}
+
+/// List the formulae and casks of a backup so they can be marked as Quick-Restore essentials
+#[tauri::command]
+async fn list_backup_packages(target_path: String, timestamp: String, encryption_passphrase: Option<String>) -> Result<BackupPackages, String> {
+    read_backup_packages(&target_path, &timestamp, encryption_passphrase.as_deref())
+}
+
+/// Quick-Restore mode: Install essential packages first for rapid productivity.
+/// The essentials come from `quick_restore_brews` / `quick_restore_casks` in the config
+#[tauri::command]
+async fn quick_restore_essentials(
+    target_path: String,
+    timestamp: String,
+    encryption_passphrase: Option<String>,
+    window: tauri::Window,
+) -> Result<RestoreResult, String> {
+    RESTORE_CANCELLED.store(false, Ordering::SeqCst);
+    // Essential packages that make a system immediately usable
+    let config = load_config()?;
+    let essential_brews = config.quick_restore_brews;
+    let essential_casks = config.quick_restore_casks;
+    
+    let brew_path = find_brew_path()
+        .ok_or_else(|| "Homebrew nicht gefunden".to_string())?;
+    
+    let mut restored: Vec<String> = Vec::new();
+    let mut skipped: Vec<String> = Vec::new();
+    let mut errors: Vec<String> = Vec::new();
+    
+    // First, get the Brewfile from backup to check what was actually installed
+    let BackupPackages { brews: packages_in_backup, casks: casks_in_backup } = read_backup_packages(&target_path, &timestamp, encryption_passphrase.as_deref())?;
+    
     let _ = window.emit("restore-log", "🚀 Quick-Restore: Installiere essentielle Pakete...");
     let _ = window.emit("restore-progress", serde_json::json!({
         "progress": 5,
@@ -2152,14 +9870,17 @@ async fn quick_restore_essentials(
     
     // Install essential brew packages that were in the backup
     let brews_to_install: Vec<&str> = essential_brews.iter()
+        .map(|pkg| pkg.as_str())
         .filter(|pkg| packages_in_backup.iter().any(|b| b.contains(*pkg)))
-        .cloned()
         .collect();
     
     let total_items = brews_to_install.len() + essential_casks.len();
     let mut current = 0;
     
     for pkg in &brews_to_install {
+        if RESTORE_CANCELLED.load(Ordering::SeqCst) {
+            break;
+        }
         current += 1;
         let progress = 5 + (current * 45 / total_items.max(1));
         let _ = window.emit("restore-progress", serde_json::json!({
@@ -2167,9 +9888,8 @@ async fn quick_restore_essentials(
             "message": format!("Installiere {}...", pkg)
         }));
         
-        let output = Command::new(&brew_path)
-            .args(["install", pkg])
-            .output();
+        let output = run_restore_command(Command::new(&brew_path)
+            .args(["install", pkg]));
         
         match output {
             Ok(o) if o.status.success() => {
@@ -2192,11 +9912,14 @@ async fn quick_restore_essentials(
     
     // Install essential casks that were in the backup
     let casks_to_install: Vec<&str> = essential_casks.iter()
+        .map(|cask| cask.as_str())
         .filter(|cask| casks_in_backup.iter().any(|c| c.contains(*cask)))
-        .cloned()
         .collect();
     
     for cask in &casks_to_install {
+        if RESTORE_CANCELLED.load(Ordering::SeqCst) {
+            break;
+        }
         current += 1;
         let progress = 50 + (current * 45 / total_items.max(1));
         let _ = window.emit("restore-progress", serde_json::json!({
@@ -2204,9 +9927,8 @@ async fn quick_restore_essentials(
             "message": format!("Installiere {}...", cask)
         }));
         
-        let output = Command::new(&brew_path)
-            .args(["install", "--cask", cask])
-            .output();
+        let output = run_restore_command(Command::new(&brew_path)
+            .args(["install", "--cask", cask]));
         
         match output {
             Ok(o) if o.status.success() => {
@@ -2227,74 +9949,50 @@ async fn quick_restore_essentials(
         }
     }
     
+    if RESTORE_CANCELLED.load(Ordering::SeqCst) {
+        errors.push("Quick-Restore abgebrochen".to_string());
+        let _ = window.emit("restore-log", "⚠️ Quick-Restore abgebrochen");
+    }
+    
     let _ = window.emit("restore-progress", serde_json::json!({
         "progress": 100,
         "message": "Quick-Restore abgeschlossen"
     }));
     
-    let _ = window.emit("restore-log", format!(
-        "🎉 Quick-Restore abgeschlossen: {} installiert, {} übersprungen, {} Fehler",
-        restored.len(), skipped.len(), errors.len()
-    ));
-    
-    Ok(RestoreResult {
-        restored_count: restored.len(),
-        skipped_count: skipped.len(),
-        error_count: errors.len(),
-        restored,
-        skipped,
-        errors,
-    })
-}
-
-/// Restore Safari settings from backup
-fn restore_safari_settings(backup_path: &Path, archive_name: &str) -> Result<usize, String> {
-    let archive = backup_path.join(archive_name);
-    let home = dirs::home_dir().ok_or("Home-Verzeichnis nicht gefunden")?;
-    
-    let temp_dir = std::env::temp_dir().join("macos-backup-restore-safari");
-    let _ = fs::remove_dir_all(&temp_dir);
-    fs::create_dir_all(&temp_dir).map_err(|e| e.to_string())?;
-    
-    // Extract archive
-    let zstd_available = Command::new("which")
-        .arg("zstd")
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false);
-    
-    let output = if zstd_available && archive_name.ends_with(".zst") {
-        Command::new("tar")
-            .current_dir(&temp_dir)
-            .args(["--use-compress-program=zstd -d", "-xf", &archive.to_string_lossy()])
-            .output()
-            .map_err(|e| e.to_string())?
-    } else {
-        Command::new("tar")
-            .current_dir(&temp_dir)
-            .args(["-xzf", &archive.to_string_lossy()])
-            .output()
-            .map_err(|e| e.to_string())?
-    };
-    
-    if !output.status.success() {
-        return Err("Entpacken fehlgeschlagen".to_string());
-    }
+    let _ = window.emit("restore-log", format!(
+        "🎉 Quick-Restore abgeschlossen: {} installiert, {} übersprungen, {} Fehler",
+        restored.len(), skipped.len(), errors.len()
+    ));
     
-    let mut restored_count = 0;
+    let result = RestoreResult {
+        restored_count: restored.len(),
+        skipped_count: skipped.len(),
+        error_count: errors.len(),
+        restored,
+        skipped,
+        errors,
+    };
+    notify_restore_result(&window, "Quick-Restore abgeschlossen", &result);
+    Ok(result)
+}
+
+type SettingsRestoreFn = fn(&Path, &str, &Path) -> Result<usize, String>;
+
+/// Restore a browser's settings from its archive
+fn restore_browser_settings(browser: BrowserKind, backup_path: &Path, archive_name: &str, home: &Path) -> Result<usize, String> {
+    let archive = backup_path.join(archive_name);
     
-    // Safari paths to restore
-    let safari_destinations = [
-        ("Bookmarks.plist", home.join("Library/Safari/Bookmarks.plist")),
-        ("ReadingListArchives", home.join("Library/Safari/ReadingListArchives")),
-        ("Extensions", home.join("Library/Safari/Extensions")),
-        ("TopSites.plist", home.join("Library/Safari/TopSites.plist")),
-        ("LastSession.plist", home.join("Library/Safari/LastSession.plist")),
-        ("Preferences", home.join("Library/Containers/com.apple.Safari/Data/Library/Preferences")),
-    ];
+    let temp_dir = staging_dir().join(format!("macos-backup-restore-{}", browser.label().to_lowercase()));
+    let _ = fs::remove_dir_all(&temp_dir);
+    extract_archive_to(&archive, &temp_dir)?;
+    
+    // Archives contain the staging directory as top-level entry
+    let extracted = temp_dir.join(browser.stage_dir_name());
+    let extracted = if extracted.is_dir() { extracted } else { temp_dir.clone() };
     
-    for (name, dest_path) in &safari_destinations {
-        let source = temp_dir.join(name);
+    let mut restored_count = 0;
+    for (name, dest_path) in browser.restore_paths(&extracted, home) {
+        let source = extracted.join(&name);
         if source.exists() {
             // Create parent directory
             if let Some(parent) = dest_path.parent() {
@@ -2320,26 +10018,36 @@ fn restore_safari_settings(backup_path: &Path, archive_name: &str) -> Result<usi
     Ok(restored_count)
 }
 
+/// Restore Safari settings from backup
+fn restore_safari_settings(backup_path: &Path, archive_name: &str, home: &Path) -> Result<usize, String> {
+    restore_browser_settings(BrowserKind::Safari, backup_path, archive_name, home)
+}
+
+/// Restore Chrome bookmarks and preferences from backup
+fn restore_chrome_settings(backup_path: &Path, archive_name: &str, home: &Path) -> Result<usize, String> {
+    restore_browser_settings(BrowserKind::Chrome, backup_path, archive_name, home)
+}
+
+/// Restore Firefox bookmark backups and preferences from backup
+fn restore_firefox_settings(backup_path: &Path, archive_name: &str, home: &Path) -> Result<usize, String> {
+    restore_browser_settings(BrowserKind::Firefox, backup_path, archive_name, home)
+}
+
 /// Restore Homebrew cache from backup
-fn restore_homebrew_cache(backup_path: &Path, archive_name: &str) -> Result<usize, String> {
+fn restore_homebrew_cache(backup_path: &Path, archive_name: &str, home: &Path) -> Result<usize, String> {
     let archive = backup_path.join(archive_name);
-    let home = dirs::home_dir().ok_or("Home-Verzeichnis nicht gefunden")?;
     
     // Homebrew cache location
     let cache_path = home.join("Library/Caches/Homebrew");
     fs::create_dir_all(&cache_path).map_err(|e| e.to_string())?;
     
     // Extract archive
-    let zstd_available = Command::new("which")
-        .arg("zstd")
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false);
+    let zstd_available = find_zstd_path().is_some();
     
     let output = if zstd_available && archive_name.ends_with(".zst") {
         Command::new("tar")
             .current_dir(&cache_path)
-            .args(["--use-compress-program=zstd -d", "-xf", &archive.to_string_lossy()])
+            .args([&zstd_decompress_arg(), "-xf", &archive.to_string_lossy()])
             .output()
             .map_err(|e| e.to_string())?
     } else {
@@ -2367,25 +10075,538 @@ fn restore_homebrew_cache(backup_path: &Path, archive_name: &str) -> Result<usiz
     Ok((total_size / 1_048_576) as usize)
 }
 
+/// Re-add login items; LaunchAgent plists in backups made by older versions are copied back too
+fn restore_startup_items(backup_path: &Path, archive_name: &str, overwrite: bool) -> Result<usize, String> {
+    let archive = backup_path.join(archive_name);
+    let home = dirs::home_dir().ok_or("Home-Verzeichnis nicht gefunden")?;
+    
+    let temp_dir = staging_dir().join("macos-backup-restore-startup");
+    let _ = fs::remove_dir_all(&temp_dir);
+    let result = extract_archive_to(&archive, &temp_dir).map(|_| {
+        let staged = temp_dir.join("startup_items");
+        let mut restored_count = 0;
+        
+        // LaunchAgents: copy back, never touching Apple's own agents
+        let agents_dest = home.join("Library/LaunchAgents");
+        let _ = fs::create_dir_all(&agents_dest);
+        if let Ok(entries) = fs::read_dir(staged.join("LaunchAgents")) {
+            for entry in entries.flatten() {
+                let name = entry.file_name();
+                if name.to_string_lossy().starts_with("com.apple.") {
+                    continue;
+                }
+                let dest = agents_dest.join(&name);
+                if dest.exists() && !overwrite {
+                    continue;
+                }
+                if fs::copy(entry.path(), &dest).is_ok() {
+                    restored_count += 1;
+                }
+            }
+        }
+        
+        // Login items: re-add those whose app still exists and isn't registered yet
+        let existing: Vec<String> = read_login_items()
+            .iter()
+            .map(|l| l.split('\t').next().unwrap_or("").to_string())
+            .collect();
+        let login_items = fs::read_to_string(staged.join("login_items.txt")).unwrap_or_default();
+        for line in login_items.lines() {
+            let mut parts = line.splitn(2, '\t');
+            let name = parts.next().unwrap_or("").trim();
+            let path = parts.next().unwrap_or("").trim();
+            if name.is_empty() || path.is_empty() || existing.iter().any(|e| e == name) || !Path::new(path).exists() {
+                continue;
+            }
+            let script = format!(
+                "tell application \"System Events\" to make login item at end with properties {{path:\"{}\", hidden:false}}",
+                path.replace('\\', "\\\\").replace('"', "\\\"")
+            );
+            if Command::new("osascript").args(["-e", &script]).output().map(|o| o.status.success()).unwrap_or(false) {
+                restored_count += 1;
+            }
+        }
+        
+        restored_count
+    });
+    
+    let _ = fs::remove_dir_all(&temp_dir);
+    result
+}
+
+/// Restore crontab entries and LaunchAgents. Entries whose executable doesn't exist
+/// on this Mac are skipped and returned as warnings.
+fn restore_scheduled_tasks(backup_path: &Path, archive_name: &str, overwrite: bool) -> Result<(usize, Vec<String>), String> {
+    use std::process::Stdio;
+    
+    let archive = backup_path.join(archive_name);
+    let home = dirs::home_dir().ok_or("Home-Verzeichnis nicht gefunden")?;
+    
+    let temp_dir = staging_dir().join("macos-backup-restore-tasks");
+    let _ = fs::remove_dir_all(&temp_dir);
+    let result = extract_archive_to(&archive, &temp_dir).and_then(|_| {
+        let staged = temp_dir.join("scheduled_tasks");
+        let mut restored_count = 0;
+        let mut warnings = Vec::new();
+        
+        // Crontab: keep the existing entries unless overwriting, add the restored ones
+        let backed_up = fs::read_to_string(staged.join("crontab.txt")).unwrap_or_default();
+        if !backed_up.trim().is_empty() {
+            let existing = if overwrite { String::new() } else { read_crontab() };
+            let mut lines: Vec<String> = existing.lines().map(|l| l.to_string()).collect();
+            for line in backed_up.lines() {
+                if lines.iter().any(|l| l == line) {
+                    continue;
+                }
+                if let Some(executable) = crontab_executable(line) {
+                    if !Path::new(&executable).exists() {
+                        warnings.push(format!("Cron-Eintrag übersprungen, {} fehlt: {}", executable, line));
+                        continue;
+                    }
+                }
+                if !line.trim().is_empty() && !line.trim().starts_with('#') {
+                    restored_count += 1;
+                }
+                lines.push(line.to_string());
+            }
+            
+            let mut child = Command::new("crontab")
+                .arg("-")
+                .stdin(Stdio::piped())
+                .spawn()
+                .map_err(|e| format!("crontab Fehler: {}", e))?;
+            if let Some(mut stdin) = child.stdin.take() {
+                let _ = writeln!(stdin, "{}", lines.join("\n"));
+            }
+            let status = child.wait().map_err(|e| format!("crontab Fehler: {}", e))?;
+            if !status.success() {
+                return Err("crontab konnte nicht geschrieben werden".to_string());
+            }
+        }
+        
+        // LaunchAgents: copy into place and bootstrap them into the user's GUI domain
+        let domain = format!("gui/{}", unsafe { libc::getuid() });
+        let agents_dest = home.join("Library/LaunchAgents");
+        let _ = fs::create_dir_all(&agents_dest);
+        if let Ok(entries) = fs::read_dir(staged.join("LaunchAgents")) {
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if name.starts_with("com.apple.") {
+                    continue;
+                }
+                if let Some(executable) = launch_agent_executable(&entry.path()) {
+                    if executable.starts_with('/') && !Path::new(&executable).exists() {
+                        warnings.push(format!("LaunchAgent {} übersprungen, {} fehlt", name, executable));
+                        continue;
+                    }
+                }
+                let dest = agents_dest.join(&name);
+                if dest.exists() && !overwrite {
+                    continue;
+                }
+                if fs::copy(entry.path(), &dest).is_ok() {
+                    let dest_str = dest.to_string_lossy();
+                    // An already loaded agent must be booted out first, or bootstrap fails
+                    let _ = Command::new("launchctl").args(["bootout", &domain, &dest_str]).output();
+                    let bootstrapped = Command::new("launchctl")
+                        .args(["bootstrap", &domain, &dest_str])
+                        .output()
+                        .map(|o| o.status.success())
+                        .unwrap_or(false);
+                    if !bootstrapped {
+                        warnings.push(format!("LaunchAgent {} kopiert, aber launchctl bootstrap fehlgeschlagen", name));
+                    }
+                    restored_count += 1;
+                }
+            }
+        }
+        
+        Ok((restored_count, warnings))
+    });
+    
+    let _ = fs::remove_dir_all(&temp_dir);
+    result
+}
+
+/// Restore SSH/GPG files with 0700 directories and 0600 files (0644 for public keys)
+fn restore_ssh_config(backup_path: &Path, archive_name: &str, overwrite: bool, home: &Path) -> Result<usize, String> {
+    let archive = backup_path.join(archive_name);
+    
+    let temp_dir = staging_dir().join("macos-backup-restore-ssh");
+    let _ = fs::remove_dir_all(&temp_dir);
+    let result = extract_archive_to(&archive, &temp_dir).map(|_| {
+        let staged = temp_dir.join("ssh_config");
+        let mut restored_count = 0;
+        
+        for dir_name in [".ssh", ".gnupg"] {
+            let Ok(entries) = fs::read_dir(staged.join(dir_name)) else { continue };
+            let dest_dir = home.join(dir_name);
+            let _ = fs::create_dir_all(&dest_dir);
+            let _ = Command::new("chmod").args(["700", &dest_dir.to_string_lossy()]).output();
+            
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let dest = dest_dir.join(&name);
+                if dest.exists() && !overwrite {
+                    continue;
+                }
+                if fs::copy(entry.path(), &dest).is_ok() {
+                    let mode = if name.ends_with(".pub") { "644" } else { "600" };
+                    let _ = Command::new("chmod").args([mode, &dest.to_string_lossy()]).output();
+                    restored_count += 1;
+                }
+            }
+        }
+        
+        restored_count
+    });
+    
+    let _ = fs::remove_dir_all(&temp_dir);
+    result
+}
+
+/// Restore dotfiles into the home directory (existing files only with `overwrite`).
+/// Returns the number of restored entries and the recorded login shell.
+fn restore_dotfiles(backup_path: &Path, archive_name: &str, overwrite: bool, home: &Path) -> Result<(usize, Option<String>), String> {
+    let archive = backup_path.join(archive_name);
+    
+    let temp_dir = staging_dir().join("macos-backup-restore-dotfiles");
+    let _ = fs::remove_dir_all(&temp_dir);
+    let result = extract_archive_to(&archive, &temp_dir).map(|_| {
+        let staged = temp_dir.join("dotfiles");
+        let shell = fs::read_to_string(staged.join("login_shell.txt"))
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        
+        // File by file, so dotfile directories merge with what already exists
+        let mut restored_count = 0;
+        for entry in WalkDir::new(&staged).min_depth(1).into_iter().flatten() {
+            let Ok(relative) = entry.path().strip_prefix(&staged) else { continue };
+            if !entry.file_type().is_file() || relative == Path::new("login_shell.txt") {
+                continue;
+            }
+            let dest = home.join(relative);
+            if dest.exists() && !overwrite {
+                continue;
+            }
+            if let Some(parent) = dest.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            if fs::copy(entry.path(), &dest).is_ok() {
+                restored_count += 1;
+            }
+        }
+        
+        (restored_count, shell)
+    });
+    
+    let _ = fs::remove_dir_all(&temp_dir);
+    result
+}
+
+/// Restore backed-up fonts into ~/Library/Fonts (also those from /Library/Fonts, which needs no admin rights).
+/// Returns the number of restored fonts and of fonts skipped because they already exist.
+fn restore_fonts(backup_path: &Path, archive_name: &str, overwrite: bool, home: &Path) -> Result<(usize, usize), String> {
+    let archive = backup_path.join(archive_name);
+    let fonts_dir = home.join("Library/Fonts");
+    
+    let temp_dir = staging_dir().join("macos-backup-restore-fonts");
+    let _ = fs::remove_dir_all(&temp_dir);
+    let result = extract_archive_to(&archive, &temp_dir).map(|_| {
+        let mut restored_count = 0;
+        let mut existing_count = 0;
+        for origin in ["user", "library"] {
+            let staged = temp_dir.join("fonts").join(origin);
+            for entry in WalkDir::new(&staged).min_depth(1).into_iter().flatten() {
+                let Ok(relative) = entry.path().strip_prefix(&staged) else { continue };
+                if !entry.file_type().is_file() {
+                    continue;
+                }
+                let dest = fonts_dir.join(relative);
+                if dest.exists() && !overwrite {
+                    existing_count += 1;
+                    continue;
+                }
+                if let Some(parent) = dest.parent() {
+                    let _ = fs::create_dir_all(parent);
+                }
+                if fs::copy(entry.path(), &dest).is_ok() {
+                    restored_count += 1;
+                }
+            }
+        }
+        (restored_count, existing_count)
+    });
+    
+    let _ = fs::remove_dir_all(&temp_dir);
+    result
+}
+
+/// Restore the Dock preferences and restart the Dock to apply them
+fn restore_dock(backup_path: &Path, archive_name: &str) -> Result<(), String> {
+    let archive = backup_path.join(archive_name);
+    
+    let temp_dir = staging_dir().join("macos-backup-restore-dock");
+    let _ = fs::remove_dir_all(&temp_dir);
+    let result = extract_archive_to(&archive, &temp_dir).and_then(|_| {
+        let plist = temp_dir.join("dock_layout").join("com.apple.dock.plist");
+        if !plist_is_valid(&plist) {
+            return Err("Gesicherte Dock-Einstellungen sind beschädigt".to_string());
+        }
+        
+        // Import through cfprefsd so the cached preferences don't overwrite the restored file
+        let output = Command::new("defaults")
+            .args(["import", "com.apple.dock", &plist.to_string_lossy()])
+            .output()
+            .map_err(|e| format!("defaults Fehler: {}", e))?;
+        if !output.status.success() {
+            return Err(format!("defaults import fehlgeschlagen: {}", String::from_utf8_lossy(&output.stderr).trim()));
+        }
+        
+        let _ = Command::new("killall").arg("Dock").output();
+        Ok(())
+    });
+    
+    let _ = fs::remove_dir_all(&temp_dir);
+    result
+}
+
+/// Import the Terminal and iTerm2 preferences, copy iTerm2's dynamic profiles (existing ones only with
+/// `overwrite`) and quit the running terminal apps so they pick up the restored profiles on their next start.
+/// Returns what was restored.
+fn restore_terminal_settings(backup_path: &Path, archive_name: &str, overwrite: bool, home: &Path) -> Result<Vec<String>, String> {
+    let archive = backup_path.join(archive_name);
+    
+    let temp_dir = staging_dir().join("macos-backup-restore-terminal");
+    let _ = fs::remove_dir_all(&temp_dir);
+    let result = extract_archive_to(&archive, &temp_dir).and_then(|_| {
+        let staged = temp_dir.join("terminal_settings");
+        let mut restored = Vec::new();
+        let mut processes = Vec::new();
+        
+        for (file_name, domain, app) in TERMINAL_PREFERENCES {
+            let plist = staged.join(file_name);
+            if !plist.exists() {
+                continue;
+            }
+            if !plist_is_valid(&plist) {
+                return Err(format!("Gesicherte {}-Einstellungen sind beschädigt", app));
+            }
+            // Import through cfprefsd so the cached preferences don't overwrite the restored file
+            let output = Command::new("defaults")
+                .args(["import", domain, &plist.to_string_lossy()])
+                .output()
+                .map_err(|e| format!("defaults Fehler: {}", e))?;
+            if !output.status.success() {
+                return Err(format!("defaults import fehlgeschlagen ({}): {}", app, String::from_utf8_lossy(&output.stderr).trim()));
+            }
+            restored.push(app.to_string());
+            processes.push(*app);
+        }
+        
+        let mut profile_count = 0;
+        if let Ok(entries) = fs::read_dir(staged.join("DynamicProfiles")) {
+            let dest_dir = home.join(ITERM2_DYNAMIC_PROFILES);
+            let _ = fs::create_dir_all(&dest_dir);
+            for entry in entries.flatten() {
+                let dest = dest_dir.join(entry.file_name());
+                if dest.exists() && !overwrite {
+                    continue;
+                }
+                if fs::copy(entry.path(), &dest).is_ok() {
+                    profile_count += 1;
+                }
+            }
+        }
+        if profile_count > 0 {
+            restored.push(format!("{} dynamische iTerm2-Profile", profile_count));
+        }
+        
+        for process in processes {
+            let _ = Command::new("killall").arg(process).output();
+        }
+        Ok(restored)
+    });
+    
+    let _ = fs::remove_dir_all(&temp_dir);
+    result
+}
+
+/// Process that caches the preferences of a `defaults` domain and has to be restarted
+fn system_defaults_process(domain: &str) -> Option<&'static str> {
+    match domain {
+        "com.apple.finder" => Some("Finder"),
+        "com.apple.dock" => Some("Dock"),
+        "com.apple.screencapture" | "com.apple.menuextra.clock" => Some("SystemUIServer"),
+        _ => None,
+    }
+}
+
+/// Import the exported `defaults` domains and restart the affected processes.
+/// Returns the imported domains and an error message per domain that failed.
+fn restore_system_defaults(backup_path: &Path, archive_name: &str) -> Result<(Vec<String>, Vec<String>), String> {
+    let archive = backup_path.join(archive_name);
+    
+    let temp_dir = staging_dir().join("macos-backup-restore-defaults");
+    let _ = fs::remove_dir_all(&temp_dir);
+    let result = extract_archive_to(&archive, &temp_dir).map(|_| {
+        let mut plists: Vec<PathBuf> = fs::read_dir(temp_dir.join("system_defaults"))
+            .map(|entries| entries.flatten().map(|e| e.path()).collect())
+            .unwrap_or_default();
+        plists.sort();
+        
+        let mut imported = Vec::new();
+        let mut failed = Vec::new();
+        for plist in plists.iter().filter(|p| p.extension().is_some_and(|e| e == "plist")) {
+            let Some(domain) = plist.file_stem().map(|s| s.to_string_lossy().to_string()) else { continue };
+            if !plist_is_valid(plist) {
+                failed.push(format!("{}: Gesicherte Einstellungen sind beschädigt", domain));
+                continue;
+            }
+            match Command::new("defaults").args(["import", &domain, &plist.to_string_lossy()]).output() {
+                Ok(o) if o.status.success() => imported.push(domain),
+                Ok(o) => failed.push(format!("{}: defaults import fehlgeschlagen: {}", domain, String::from_utf8_lossy(&o.stderr).trim())),
+                Err(e) => failed.push(format!("{}: defaults Fehler: {}", domain, e)),
+            }
+        }
+        
+        let mut processes: Vec<&str> = imported.iter().filter_map(|d| system_defaults_process(d)).collect();
+        processes.sort();
+        processes.dedup();
+        for process in processes {
+            let _ = Command::new("killall").arg(process).output();
+        }
+        (imported, failed)
+    });
+    
+    let _ = fs::remove_dir_all(&temp_dir);
+    result
+}
+
+/// Stderr of a failed command run, or its spawn error
+fn command_error(output: std::io::Result<std::process::Output>) -> Option<String> {
+    match output {
+        Ok(o) if o.status.success() => None,
+        Ok(o) => Some(String::from_utf8_lossy(&o.stderr).trim().to_string()),
+        Err(e) => Some(e.to_string()),
+    }
+}
+
+/// Outcome of `restore_system_config`
+struct SystemConfigRestore {
+    added: Vec<String>,
+    failed: Vec<String>,
+    /// Recorded settings that can't be applied automatically
+    manual: Vec<String>,
+}
+
+/// Re-add missing printers with `lpadmin` and missing locations and network services with `networksetup`
+fn restore_system_config(backup_path: &Path, archive_name: &str) -> Result<SystemConfigRestore, String> {
+    let archive = backup_path.join(archive_name);
+    
+    let temp_dir = staging_dir().join("macos-backup-restore-system-config");
+    let _ = fs::remove_dir_all(&temp_dir);
+    let result = extract_archive_to(&archive, &temp_dir).map(|_| {
+        let staged = temp_dir.join("system_config");
+        let mut added = Vec::new();
+        let mut failed = Vec::new();
+        
+        let printers: Vec<RecordedPrinter> = fs::read_to_string(staged.join("printers.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        let existing_printers: Vec<String> = lpstat_devices().into_iter().map(|(name, _)| name).collect();
+        for printer in printers.iter().filter(|p| !existing_printers.contains(&p.name)) {
+            let mut cmd = Command::new("lpadmin");
+            cmd.args(["-p", &printer.name, "-E", "-v", &printer.device_uri]);
+            let ppd = staged.join("ppd").join(format!("{}.ppd", printer.name));
+            if printer.has_ppd && ppd.exists() {
+                cmd.arg("-P").arg(&ppd);
+            } else {
+                cmd.args(["-m", "everywhere"]);
+            }
+            if let Some(description) = &printer.description {
+                cmd.args(["-D", description]);
+            }
+            if let Some(location) = &printer.location {
+                cmd.args(["-L", location]);
+            }
+            match command_error(cmd.output()) {
+                None => added.push(format!("Drucker {}", printer.name)),
+                Some(e) => failed.push(format!("Drucker {}: {}", printer.name, e)),
+            }
+        }
+        
+        let network: NetworkConfig = fs::read_to_string(staged.join("network.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        // Creating locations and services needs administrator rights; failures are reported, not fatal
+        let existing_locations = command_output("networksetup", &["-listlocations"]);
+        for location in network.locations.iter().filter(|l| !existing_locations.lines().any(|e| e.trim() == l.as_str())) {
+            match command_error(Command::new("networksetup").args(["-createlocation", location, "populate"]).output()) {
+                None => added.push(format!("Umgebung {}", location)),
+                Some(e) => failed.push(format!("Umgebung {}: {}", location, e)),
+            }
+        }
+        let existing_services = network_service_order();
+        let hardware_ports = command_output("networksetup", &["-listallhardwareports"]);
+        for service in network.services.iter().filter(|s| !existing_services.iter().any(|e| e.name == s.name)) {
+            let Some(port) = service.hardware_port.as_deref() else { continue };
+            if !hardware_ports.lines().any(|l| l.trim().strip_prefix("Hardware Port: ") == Some(port)) {
+                failed.push(format!("Netzwerkdienst {}: Anschluss {} nicht vorhanden", service.name, port));
+                continue;
+            }
+            match command_error(Command::new("networksetup").args(["-createnetworkservice", &service.name, port]).output()) {
+                None => added.push(format!("Netzwerkdienst {}", service.name)),
+                Some(e) => failed.push(format!("Netzwerkdienst {}: {}", service.name, e)),
+            }
+        }
+        let manual = if network.wifi_networks.is_empty() {
+            Vec::new()
+        } else {
+            vec![format!("WLAN-Netze bitte manuell verbinden (Passwort nötig): {}", network.wifi_networks.join(", "))]
+        };
+        SystemConfigRestore { added, failed, manual }
+    });
+    
+    let _ = fs::remove_dir_all(&temp_dir);
+    result
+}
+
+/// Compare the installed MAS app versions against the ones recorded in the backup.
+/// Apps that are not installed are not reported here.
+fn mas_version_mismatches(recorded: &[MasApp]) -> Vec<String> {
+    if recorded.is_empty() {
+        return Vec::new();
+    }
+    let installed = get_mas_apps().unwrap_or_default();
+    recorded.iter().filter_map(|app| {
+        let current = installed.iter().find(|i| i.id == app.id)?;
+        if app.version.is_empty() || current.version.is_empty() || current.version == app.version {
+            return None;
+        }
+        Some(format!("{} ({}): gesichert {}, installiert {}", app.name, app.id, app.version, current.version))
+    }).collect()
+}
+
 /// Parallel MAS app installation with up to 4 concurrent downloads
-/// Provides ~60-80% time savings when installing many apps
-fn restore_mas_apps(backup_path: &Path, archive_name: &str, _reinstall: bool) -> Result<usize, String> {
+/// Provides ~60-80% time savings when installing many apps.
+/// Returns the number of installed apps and warnings for version differences to the backup
+/// (the App Store only offers the current version).
+fn restore_mas_apps(backup_path: &Path, archive_name: &str, _reinstall: bool, sink: &dyn ProgressSink) -> Result<(usize, Vec<String>), String> {
     let archive = backup_path.join(archive_name);
     
-    let temp_dir = std::env::temp_dir().join("macos-backup-restore-mas");
-    fs::create_dir_all(&temp_dir).map_err(|e| e.to_string())?;
+    let temp_dir = TempDirGuard::create("macos-backup-restore-mas")?;
     
     // Try zstd first, fallback to gzip for older backups
-    let zstd_available = Command::new("which")
-        .arg("zstd")
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false);
+    let zstd_available = find_zstd_path().is_some();
     
     let output = if zstd_available {
         let zstd_result = Command::new("tar")
             .current_dir(&temp_dir)
-            .args(["--use-compress-program=zstd -d", "-xf", &archive.to_string_lossy()])
+            .args([&zstd_decompress_arg(), "-xf", &archive.to_string_lossy()])
             .output();
         
         match zstd_result {
@@ -2424,6 +10645,11 @@ fn restore_mas_apps(backup_path: &Path, archive_name: &str, _reinstall: bool) ->
         .unwrap_or_default();
     
     let file_content = fs::read_to_string(&apps_file).map_err(|e| e.to_string())?;
+    // Older backups have no recorded versions
+    let recorded_versions: Vec<MasApp> = fs::read_to_string(temp_dir.join("mas_apps.json"))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
     let mut apps_to_install: Vec<String> = Vec::new();
     
     for line in file_content.lines() {
@@ -2442,135 +10668,55 @@ fn restore_mas_apps(backup_path: &Path, archive_name: &str, _reinstall: bool) ->
         }
     }
     
-    let _ = fs::remove_dir_all(&temp_dir);
-    
-    // If no apps need to be installed, return 0
-    if apps_to_install.is_empty() {
-        return Ok(0);
-    }
-    
-    let num_to_install = apps_to_install.len();
-    
-    // Parallel MAS installation with up to 4 concurrent downloads
-    // This provides ~60-80% time savings for many apps
-    const MAX_PARALLEL_MAS: usize = 4;
-    
-    let script_path = std::env::temp_dir().join("mas_install_parallel.sh");
-    let marker_path = std::env::temp_dir().join("mas_install_done.marker");
-    let app_ids_file = std::env::temp_dir().join("mas_app_ids.txt");
-    
-    // Remove old markers
-    let _ = fs::remove_file(&marker_path);
-    
-    // Write app IDs to file for parallel processing
-    let app_ids_str = apps_to_install.join("\n");
-    let _ = fs::write(&app_ids_file, &app_ids_str);
-    
-    // Create parallel installation script using GNU parallel or xargs -P
-    let script_content = format!(
-        r#"#!/bin/zsh
-export PATH="/opt/homebrew/bin:/usr/local/bin:$PATH"
-
-echo "🚀 Installiere {} MAS Apps (max {} parallel)..."
-echo ""
-
-# Install function
-install_app() {{
-    local app_id=$1
-    echo "📦 Installiere App $app_id..."
-    mas install "$app_id" 2>&1
-    if [ $? -eq 0 ]; then
-        echo "✅ App $app_id erfolgreich installiert"
-    else
-        echo "⚠️ App $app_id fehlgeschlagen"
-    fi
-}}
-
-export -f install_app
-
-# Parallel installation with xargs -P (max {} parallel)
-cat "{}" | xargs -P {} -I {{}} /bin/zsh -c 'install_app "{{}}"'
-
-echo "done" > "{}"
-echo ""
-echo "✅ Installation abgeschlossen."
-echo "Dieses Fenster kann geschlossen werden."
-read -k1
-"#,
-        num_to_install,
-        MAX_PARALLEL_MAS,
-        MAX_PARALLEL_MAS,
-        app_ids_file.to_string_lossy(),
-        MAX_PARALLEL_MAS,
-        marker_path.to_string_lossy()
-    );
-    
-    if fs::write(&script_path, &script_content).is_err() {
-        return Err("Konnte Installations-Skript nicht erstellen".to_string());
-    }
-    
-    // Make the script executable
-    let _ = Command::new("chmod")
-        .args(["+x", &script_path.to_string_lossy()])
-        .output();
-    
-    // Open Terminal and run the script
-    let result = Command::new("open")
-        .args(["-a", "Terminal", &script_path.to_string_lossy()])
-        .output();
+    drop(temp_dir);
     
-    if result.is_err() {
-        return Err("Konnte Terminal nicht öffnen".to_string());
+    // If no apps need to be installed, only check the versions
+    if apps_to_install.is_empty() {
+        return Ok((0, mas_version_mismatches(&recorded_versions)));
     }
     
-    // Wait for installation to complete
-    loop {
-        std::thread::sleep(std::time::Duration::from_secs(2));
-        
-        if marker_path.exists() {
-            let _ = fs::remove_file(&marker_path);
-            break;
-        }
+    if find_homebrew_command("mas").is_none() {
+        return Err("mas nicht gefunden. Installiere mit: brew install mas".to_string());
     }
     
-    // Check how many were actually installed
-    let check = Command::new("/bin/zsh")
-        .args(["-l", "-c", "mas list"])
-        .output()
-        .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
-        .unwrap_or_default();
+    // Parallel MAS installation with up to 4 concurrent downloads
+    // This provides ~60-80% time savings for many apps
+    const MAX_PARALLEL_MAS: usize = 4;
     
-    let installed_count = apps_to_install.iter()
-        .filter(|id| check.contains(id.as_str()))
-        .count();
+    sink.log(&format!("🚀 Installiere {} MAS Apps (max {} parallel)...", apps_to_install.len(), MAX_PARALLEL_MAS));
+    let failed = run_install_pool(&apps_to_install, MAX_PARALLEL_MAS, sink, |app_id| {
+        let mut cmd = Command::new(find_homebrew_command("mas").unwrap_or_else(|| "mas".into()));
+        cmd.args(["install", app_id]);
+        cmd
+    });
+    let installed_count = apps_to_install.len() - failed.len();
     
-    // Clean up
-    let _ = fs::remove_file(&script_path);
-    let _ = fs::remove_file(&app_ids_file);
+    if installed_count == 0 {
+        return Err(format!("Keine MAS Apps installiert (0/{})", apps_to_install.len()));
+    }
     
-    Ok(installed_count)
+    let mut warnings: Vec<String> = failed.iter()
+        .map(|app_id| format!("App {}: Installation fehlgeschlagen", app_id))
+        .collect();
+    warnings.extend(mas_version_mismatches(&recorded_versions));
+    Ok((installed_count, warnings))
 }
 
 
 /// Parallel VS Code extension installation with up to 6 concurrent installs
 /// Provides ~60-80% time savings when installing many extensions
-fn restore_vscode_extensions(backup_path: &Path, archive_name: &str, _reinstall: bool) -> Result<usize, String> {
+fn restore_vscode_extensions(backup_path: &Path, archive_name: &str, reinstall: bool, sink: &dyn ProgressSink) -> Result<usize, String> {
     let archive = backup_path.join(archive_name);
     
-    let temp_dir = std::env::temp_dir().join("macos-backup-restore-vscode");
-    fs::create_dir_all(&temp_dir).map_err(|e| e.to_string())?;
+    let temp_dir = TempDirGuard::create("macos-backup-restore-vscode")?;
     
     // Try zstd first, fallback to gzip for older backups
-    let zstd_available = Command::new("which")
-        .arg("zstd")
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false);
+    let zstd_available = find_zstd_path().is_some();
     
     let output = if zstd_available {
         let zstd_result = Command::new("tar")
             .current_dir(&temp_dir)
-            .args(["--use-compress-program=zstd -d", "-xf", &archive.to_string_lossy()])
+            .args([&zstd_decompress_arg(), "-xf", &archive.to_string_lossy()])
             .output();
         
         match zstd_result {
@@ -2607,71 +10753,345 @@ fn restore_vscode_extensions(backup_path: &Path, archive_name: &str, _reinstall:
     let total = extensions.len();
     
     if total == 0 {
-        let _ = fs::remove_dir_all(&temp_dir);
         return Ok(0);
     }
     
     // Parallel VS Code extension installation with up to 6 concurrent installs
     const MAX_PARALLEL_VSCODE: usize = 6;
     
-    // Use rayon for parallel processing if available, otherwise use threads
-    let force_flag = if _reinstall { "--force" } else { "" };
+    let extensions: Vec<String> = extensions.iter().map(|s| s.to_string()).collect();
+    drop(temp_dir);
     
-    use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
-    use std::sync::Arc;
+    let make_command: fn(&str) -> Command = if reinstall {
+        |ext| {
+            let mut cmd = Command::new("/bin/zsh");
+            cmd.args(["-l", "-c", &format!("code --install-extension {} --force", ext)]);
+            cmd
+        }
+    } else {
+        |ext| {
+            let mut cmd = Command::new("/bin/zsh");
+            cmd.args(["-l", "-c", &format!("code --install-extension {}", ext)]);
+            cmd
+        }
+    };
+    let failed = run_install_pool(&extensions, MAX_PARALLEL_VSCODE, sink, make_command);
+    let installed = total - failed.len();
+    
+    if installed == 0 && total > 0 {
+        return Err(format!("Keine Extensions installiert (0/{})", total));
+    }
+    
+    Ok(installed)
+}
+
+/// Replace archives that are byte-identical to an archive of an older backup with hard links
+/// to the oldest copy. Candidates are grouped by the hash in `metadata.json`, and both files
+/// are re-hashed before linking so a stale metadata entry can never lose data.
+#[tauri::command]
+async fn deduplicate_backups(target_path: String) -> Result<DeduplicationResult, String> {
+    use std::collections::BTreeMap;
+    use std::os::unix::fs::MetadataExt;
     
-    let installed_counter = Arc::new(AtomicUsize::new(0));
-    let extensions_owned: Vec<String> = extensions.iter().map(|s| s.to_string()).collect();
+    let data_path = PathBuf::from(&target_path)
+        .join("macos-backup-suite")
+        .join("data");
     
-    // Process extensions in parallel batches
-    let chunks: Vec<Vec<String>> = extensions_owned
-        .chunks(MAX_PARALLEL_VSCODE)
-        .map(|c| c.to_vec())
+    let mut timestamps: Vec<String> = fs::read_dir(&data_path)
+        .map_err(|e| format!("Backup-Verzeichnis nicht lesbar: {}", e))?
+        .flatten()
+        .filter(|entry| entry.path().join("metadata.json").exists())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
         .collect();
+    // Timestamps sort chronologically, so the first archive of each group is the oldest
+    timestamps.sort();
+    
+    // Hashes are only comparable within the same algorithm
+    let mut groups: BTreeMap<(HashAlgorithm, String), Vec<PathBuf>> = BTreeMap::new();
+    for timestamp in &timestamps {
+        let Ok(metadata) = read_backup_metadata(&target_path, timestamp) else { continue };
+        let algorithm = HashAlgorithm::from_name(&metadata.hash_algorithm);
+        for item in archive_files(&metadata.items) {
+            groups.entry((algorithm, item.hash)).or_default().push(data_path.join(timestamp).join(&item.archive));
+        }
+    }
     
-    for chunk in chunks {
-        let mut batch_handles: Vec<std::thread::JoinHandle<()>> = Vec::new();
+    let mut result = DeduplicationResult { linked_files: 0, reclaimed_bytes: 0, skipped: Vec::new() };
+    for ((algorithm, hash), paths) in groups.into_iter().filter(|(_, paths)| paths.len() > 1) {
+        let Some(original) = paths.iter().find(|p| p.exists()) else { continue };
+        let Ok(original_meta) = fs::metadata(original) else { continue };
+        if hash_file(original, algorithm).map(|h| h != hash).unwrap_or(true) {
+            result.skipped.push(format!("{}: Hash stimmt nicht mit den Metadaten überein", original.display()));
+            continue;
+        }
         
-        for ext in chunk {
-            let counter = Arc::clone(&installed_counter);
-            let force = force_flag.to_string();
+        for duplicate in paths.iter().filter(|p| *p != original) {
+            let Ok(duplicate_meta) = fs::metadata(duplicate) else { continue };
+            if duplicate_meta.ino() == original_meta.ino() && duplicate_meta.dev() == original_meta.dev() {
+                continue; // already linked
+            }
+            if duplicate_meta.dev() != original_meta.dev() {
+                result.skipped.push(format!("{}: anderes Volume", duplicate.display()));
+                continue;
+            }
+            if hash_file(duplicate, algorithm).map(|h| h != hash).unwrap_or(true) {
+                result.skipped.push(format!("{}: Hash stimmt nicht mit den Metadaten überein", duplicate.display()));
+                continue;
+            }
             
-            let handle = std::thread::spawn(move || {
-                let cmd = if force.is_empty() {
-                    format!("code --install-extension {}", ext)
-                } else {
-                    format!("code --install-extension {} {}", ext, force)
-                };
-                
-                let result = Command::new("/bin/zsh")
-                    .args(["-l", "-c", &cmd])
-                    .output();
-                
-                if let Ok(output) = result {
-                    if output.status.success() {
-                        counter.fetch_add(1, AtomicOrdering::SeqCst);
-                    }
+            // Link next to the duplicate first, then swap it in with an atomic rename
+            let link_path = duplicate.with_extension("dedup-link");
+            let _ = fs::remove_file(&link_path);
+            let linked = fs::hard_link(original, &link_path).and_then(|_| fs::rename(&link_path, duplicate));
+            match linked {
+                Ok(()) => {
+                    result.linked_files += 1;
+                    result.reclaimed_bytes += duplicate_meta.len();
+                }
+                Err(e) => {
+                    let _ = fs::remove_file(&link_path);
+                    result.skipped.push(format!("{}: {}", duplicate.display(), e));
+                }
+            }
+        }
+    }
+    
+    Ok(result)
+}
+
+/// Outcome of recompressing a backup's archives at a higher zstd level
+#[derive(Debug, Serialize, Clone)]
+pub struct RecompressResult {
+    pub recompressed_files: usize,
+    /// Archives left as they were, with the reason
+    pub skipped: Vec<String>,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    pub bytes_saved: u64,
+}
+
+/// Highest zstd level; levels above 19 need `--ultra` and a lot of memory
+const ZSTD_MAX_LEVEL: i32 = 22;
+
+/// Hash of an archive's decompressed tar stream: archives with the same content hash extract identically
+fn hash_archive_content(archive: &Path, algorithm: HashAlgorithm) -> Result<String, String> {
+    let (mut reader, zstd_child) = open_archive_reader(archive)?;
+    let mut hasher = algorithm.hasher();
+    let mut buffer = [0u8; 64 * 1024];
+    let read = loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => break Ok(()),
+            Ok(n) => hasher.update(&buffer[..n]),
+            Err(e) => break Err(format!("Archiv konnte nicht gelesen werden: {}", e)),
+        }
+    };
+    drop(reader);
+    if let Some(mut child) = zstd_child {
+        let status = child.wait().map_err(|e| format!("zstd Fehler: {}", e))?;
+        if read.is_ok() && !status.success() {
+            return Err("zstd konnte das Archiv nicht entpacken".to_string());
+        }
+    }
+    read.map(|_| hasher.finalize())
+}
+
+/// Decompress a zstd archive and compress it again at `level` into `output`, reusing its dictionary
+fn recompress_zstd_archive(archive: &Path, output: &Path, level: i32) -> Result<(), String> {
+    use std::process::Stdio;
+    
+    let zstd = find_zstd_path().ok_or("zstd nicht gefunden")?;
+    let threads = load_config().map(|c| c.throttle).unwrap_or_default().zstd_threads;
+    let dictionary = Some(zstd_dictionary_path(archive)).filter(|d| d.is_file());
+    
+    let mut decompress = Command::new(zstd);
+    if let Some(dictionary) = &dictionary {
+        decompress.arg("-D").arg(dictionary);
+    }
+    let mut decompress = decompress
+        .args(["-d", "-c", "-q"])
+        .arg(archive)
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("zstd Fehler: {}", e))?;
+    let stdout = decompress.stdout.take().ok_or("zstd liefert keine Ausgabe")?;
+    
+    let mut compress = Command::new(zstd);
+    if level > 19 {
+        compress.arg("--ultra");
+    }
+    if let Some(dictionary) = &dictionary {
+        compress.arg("-D").arg(dictionary);
+    }
+    let compressed = compress
+        .args([format!("-{}", level), format!("-T{}", threads)])
+        .args(["-q", "-f", "-o"])
+        .arg(output)
+        .stdin(Stdio::from(stdout))
+        .output();
+    let decompressed = decompress.wait();
+    
+    match (decompressed, compressed) {
+        (Ok(d), Ok(c)) if d.success() && c.status.success() => Ok(()),
+        (_, compressed) => {
+            let _ = fs::remove_file(output);
+            Err(match compressed {
+                Ok(c) if !c.status.success() => format!("zstd Fehler: {}", String::from_utf8_lossy(&c.stderr).trim()),
+                Err(e) => format!("zstd Fehler: {}", e),
+                _ => "Archiv konnte nicht entpackt werden".to_string(),
+            })
+        }
+    }
+}
+
+/// Recompress the zstd archives of a finished backup at a higher `level` (e.g. 19 for cold backups).
+/// Each new archive replaces the original only after its content proved identical; metadata.json and
+/// the checksum files are rewritten with the new hash and size right after every replacement, so an
+/// interrupted run never leaves them out of date. Archives hard-linked into other backups are skipped.
+#[tauri::command]
+async fn recompress_backup(
+    window: tauri::Window,
+    target_path: String,
+    timestamp: String,
+    level: i32,
+) -> Result<RecompressResult, String> {
+    use std::os::unix::fs::MetadataExt;
+    
+    if !(1..=ZSTD_MAX_LEVEL).contains(&level) {
+        return Err(format!("Ungültige Kompressionsstufe {} (1–{})", level, ZSTD_MAX_LEVEL));
+    }
+    let suite_root = PathBuf::from(&target_path).join("macos-backup-suite");
+    let backup_path = suite_root.join("data").join(&timestamp);
+    let metadata_path = backup_path.join("metadata.json");
+    if !metadata_path.exists() {
+        return Err(format!("Backup nicht gefunden: {}", timestamp));
+    }
+    let mut metadata = read_metadata(&metadata_path)?;
+    if metadata.encrypted {
+        return Err("Verschlüsselte Backups können nicht neu komprimiert werden".to_string());
+    }
+    let algorithm = HashAlgorithm::from_name(&metadata.hash_algorithm);
+    let sink = WindowSink::backup(&window);
+    
+    let mut result = RecompressResult {
+        recompressed_files: 0,
+        skipped: Vec::new(),
+        bytes_before: 0,
+        bytes_after: 0,
+        bytes_saved: 0,
+    };
+    let total_files = archive_files(&metadata.items).len();
+    let mut done_files = 0;
+    for item_index in 0..metadata.items.len() {
+        let file_count = 1 + metadata.items[item_index].parts.len();
+        if metadata.items[item_index].multi_volume {
+            result.skipped.push(format!("{}: in Teildateien aufgeteilt", metadata.items[item_index].path));
+            done_files += file_count;
+            continue;
+        }
+        // The item's own archive first, then the part archives of a split directory
+        for index in 0..file_count {
+            done_files += 1;
+            let item = &metadata.items[item_index];
+            let (archive_name, old_size) = match index {
+                0 => (item.archive.clone(), item.archive_size_bytes - item.parts.iter().map(|p| p.archive_size_bytes).sum::<u64>()),
+                n => (item.parts[n - 1].archive.clone(), item.parts[n - 1].archive_size_bytes),
+            };
+            let archive = backup_path.join(&archive_name);
+            if detect_compression(&archive) != ArchiveCompression::Zstd {
+                result.skipped.push(format!("{}: nicht zstd-komprimiert", archive_name));
+                continue;
+            }
+            // Replacing a hard-linked archive would break the link and store it twice
+            if fs::metadata(&archive).map(|m| m.nlink() > 1).unwrap_or(false) {
+                result.skipped.push(format!("{}: mit einem anderen Backup geteilt (Hardlink)", archive_name));
+                continue;
+            }
+            sink.progress(done_files * 100 / total_files.max(1), &format!("Komprimiere {} mit Stufe {}...", archive_name, level));
+            
+            let recompressed = backup_path.join(format!(".{}.recompress", archive_name));
+            let outcome = recompress_zstd_archive(&archive, &recompressed, level).and_then(|_| {
+                // Only an archive with exactly the same tar stream may replace the original
+                if hash_archive_content(&archive, algorithm)? != hash_archive_content(&recompressed, algorithm)? {
+                    return Err("Inhalt nach dem Neukomprimieren nicht identisch".to_string());
                 }
+                let new_size = fs::metadata(&recompressed).map(|m| m.len()).map_err(|e| e.to_string())?;
+                if new_size >= old_size {
+                    return Err("keine Ersparnis".to_string());
+                }
+                fs::rename(&recompressed, &archive).map_err(|e| e.to_string())?;
+                Ok((hash_file(&archive, algorithm)?, new_size))
             });
+            let (hash, new_size) = match outcome {
+                Ok(replaced) => replaced,
+                Err(e) => {
+                    let _ = fs::remove_file(&recompressed);
+                    sink.log(&format!("⚠️ {}: {}", archive_name, e));
+                    result.skipped.push(format!("{}: {}", archive_name, e));
+                    continue;
+                }
+            };
             
-            batch_handles.push(handle);
-        }
-        
-        // Wait for this batch to complete before starting next
-        for handle in batch_handles {
-            let _ = handle.join();
+            let item = &mut metadata.items[item_index];
+            if index == 0 {
+                item.hash = hash;
+            } else {
+                item.parts[index - 1].hash = hash;
+                item.parts[index - 1].archive_size_bytes = new_size;
+            }
+            item.archive_size_bytes = item.archive_size_bytes - old_size + new_size;
+            result.recompressed_files += 1;
+            result.bytes_before += old_size;
+            result.bytes_after += new_size;
+            sink.log(&format!(
+                "✅ {}: {:.1} MB → {:.1} MB",
+                archive_name, old_size as f64 / (1024.0 * 1024.0), new_size as f64 / (1024.0 * 1024.0)
+            ));
+            
+            // The archive on disk has changed, so the recorded hash and size must follow immediately
+            let metadata_json = serde_json::to_string_pretty(&metadata).map_err(|e| e.to_string())?;
+            write_atomic(&metadata_path, &metadata_json).map_err(|e| e.to_string())?;
+            let inventory_metadata = suite_root.join("inventories").join(&timestamp).join("metadata.json");
+            if inventory_metadata.exists() {
+                let _ = write_atomic(&inventory_metadata, &metadata_json);
+            }
+            write_checksum_files(&backup_path, &metadata.items, algorithm)?;
         }
     }
+    result.bytes_saved = result.bytes_before - result.bytes_after;
     
-    let installed = installed_counter.load(AtomicOrdering::SeqCst);
+    sink.progress(100, &format!("{:.1} MB eingespart", result.bytes_saved as f64 / (1024.0 * 1024.0)));
     
-    let _ = fs::remove_dir_all(&temp_dir);
+    Ok(result)
+}
+
+/// Reveal a backup's `data/<timestamp>` directory in Finder, or open the
+/// `macos-backup-suite` folder when no timestamp is given
+#[tauri::command]
+fn reveal_backup_in_finder(target_path: String, timestamp: Option<String>) -> Result<(), String> {
+    let suite_root = PathBuf::from(&target_path).join("macos-backup-suite");
     
-    if installed == 0 && total > 0 {
-        return Err(format!("Keine Extensions installiert (0/{})", total));
+    let mut cmd = Command::new("open");
+    match timestamp.filter(|t| !t.trim().is_empty()) {
+        Some(timestamp) => {
+            let backup_path = suite_root.join("data").join(&timestamp);
+            if !backup_path.is_dir() {
+                return Err(format!("Backup {} nicht gefunden", timestamp));
+            }
+            cmd.arg("-R").arg(&backup_path);
+        }
+        None => {
+            if !suite_root.is_dir() {
+                return Err(format!("Kein Backup-Ordner gefunden in {}", target_path));
+            }
+            cmd.arg(&suite_root);
+        }
     }
     
-    Ok(installed)
+    let output = cmd.output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(format!("Finder konnte nicht geöffnet werden: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(())
 }
 
 #[tauri::command]
@@ -2695,13 +11115,20 @@ fn delete_backup(target_path: String, timestamp: String) -> Result<(), String> {
     }
     
     // Update latest.json if we deleted the latest backup
+    refresh_latest_pointer(&suite_root, &timestamp);
+    
+    Ok(())
+}
+
+/// Point `latest.json` at the newest remaining backup if it referenced `removed`
+fn refresh_latest_pointer(suite_root: &Path, removed: &str) {
     let latest_path = suite_root.join("latest.json");
     
     if latest_path.exists() {
         if let Ok(content) = fs::read_to_string(&latest_path) {
             if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
                 if let Some(latest) = json.get("latest").and_then(|v| v.as_str()) {
-                    if latest == timestamp {
+                    if latest == removed {
                         // Find the next latest backup
                         let data_path = suite_root.join("data");
                         let mut backups: Vec<String> = Vec::new();
@@ -2721,7 +11148,7 @@ fn delete_backup(target_path: String, timestamp: String) -> Result<(), String> {
                                 "latest": new_latest,
                                 "created_at": chrono::Local::now().to_rfc3339()
                             });
-                            let _ = fs::write(&latest_path, serde_json::to_string_pretty(&new_json).unwrap());
+                            let _ = write_atomic(&latest_path, serde_json::to_string_pretty(&new_json).unwrap());
                         } else {
                             // No more backups, remove latest.json
                             let _ = fs::remove_file(&latest_path);
@@ -2731,10 +11158,106 @@ fn delete_backup(target_path: String, timestamp: String) -> Result<(), String> {
             }
         }
     }
-    
+}
+
+/// Timestamp directory names directly below `dir`
+fn timestamp_dirs(dir: &Path) -> std::collections::BTreeSet<String> {
+    fs::read_dir(dir)
+        .map(|entries| entries.flatten()
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect())
+        .unwrap_or_default()
+}
+
+/// Timestamps that exist only under `data/` or only under `inventories/`. A `data/` directory
+/// only counts as orphaned without metadata.json and without the in-progress marker: finished
+/// backups just lost their inventory, and interrupted ones stay resumable.
+fn orphaned_backups(suite_root: &Path) -> OrphanedBackups {
+    let data_path = suite_root.join("data");
+    let data = timestamp_dirs(&data_path);
+    let inventories = timestamp_dirs(&suite_root.join("inventories"));
+    let (finished, unfinished): (Vec<String>, Vec<String>) = data.difference(&inventories)
+        .filter(|timestamp| !data_path.join(timestamp).join(IN_PROGRESS_MARKER).exists())
+        .cloned()
+        .partition(|timestamp| data_path.join(timestamp).join("metadata.json").exists());
+    OrphanedBackups {
+        data_only: unfinished,
+        inventory_only: inventories.difference(&data).cloned().collect(),
+        missing_inventory: finished,
+    }
+}
+
+/// Software-list items whose archives hold the inventory files of the same name
+const INVENTORY_ITEMS: &[&str] = &["homebrew-packages", "mas-apps", "vscode-extensions"];
+
+/// Recreate `inventories/<timestamp>` of a finished backup from what is left in `data/`:
+/// the metadata copy and, for unencrypted backups, the files of the software-list archives
+fn rebuild_inventory(suite_root: &Path, timestamp: &str) -> Result<(), String> {
+    let backup_root = suite_root.join("data").join(timestamp);
+    let inventory_root = suite_root.join("inventories").join(timestamp);
+    let metadata = read_metadata(&backup_root.join("metadata.json"))?;
+    fs::create_dir_all(&inventory_root).map_err(|e| e.to_string())?;
+    let metadata_json = serde_json::to_string_pretty(&metadata).map_err(|e| e.to_string())?;
+    write_atomic(&inventory_root.join("metadata.json"), &metadata_json).map_err(|e| e.to_string())?;
+    if !metadata.encrypted {
+        for item in metadata.items.iter().filter(|item| INVENTORY_ITEMS.contains(&item.path.as_str())) {
+            extract_archive_to(&backup_root.join(&item.archive), &inventory_root)?;
+        }
+    }
     Ok(())
 }
 
+/// List backups whose `data/` or `inventories/` counterpart is missing
+#[tauri::command]
+fn find_orphaned_backups(target_path: String) -> Result<OrphanedBackups, String> {
+    let suite_root = PathBuf::from(&target_path).join("macos-backup-suite");
+    if !suite_root.is_dir() {
+        return Err(format!("Kein Backup-Ordner gefunden in {}", target_path));
+    }
+    Ok(orphaned_backups(&suite_root))
+}
+
+/// Remove the directories reported by `find_orphaned_backups` and rebuild missing inventories
+/// of finished backups; returns what was actually removed or rebuilt
+#[tauri::command]
+fn cleanup_orphans(target_path: String) -> Result<OrphanedBackups, String> {
+    let suite_root = PathBuf::from(&target_path).join("macos-backup-suite");
+    if !suite_root.is_dir() {
+        return Err(format!("Kein Backup-Ordner gefunden in {}", target_path));
+    }
+    let orphans = orphaned_backups(&suite_root);
+    let mut removed = OrphanedBackups { data_only: Vec::new(), inventory_only: Vec::new(), missing_inventory: Vec::new() };
+    let mut errors = Vec::new();
+    
+    for timestamp in orphans.data_only {
+        match fs::remove_dir_all(suite_root.join("data").join(&timestamp)) {
+            Ok(()) => {
+                refresh_latest_pointer(&suite_root, &timestamp);
+                removed.data_only.push(timestamp);
+            }
+            Err(e) => errors.push(format!("data/{}: {}", timestamp, e)),
+        }
+    }
+    for timestamp in orphans.inventory_only {
+        match fs::remove_dir_all(suite_root.join("inventories").join(&timestamp)) {
+            Ok(()) => removed.inventory_only.push(timestamp),
+            Err(e) => errors.push(format!("inventories/{}: {}", timestamp, e)),
+        }
+    }
+    for timestamp in orphans.missing_inventory {
+        match rebuild_inventory(&suite_root, &timestamp) {
+            Ok(()) => removed.missing_inventory.push(timestamp),
+            Err(e) => errors.push(format!("inventories/{}: {}", timestamp, e)),
+        }
+    }
+    
+    if !errors.is_empty() {
+        return Err(format!("Fehler beim Aufräumen: {}", errors.join("; ")));
+    }
+    Ok(removed)
+}
+
 // ========== Menu Building ==========
 
 fn build_menu(app_handle: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
@@ -2823,23 +11346,61 @@ fn build_menu(app_handle: &AppHandle) -> Result<(), Box<dyn std::error::Error>>
     Ok(())
 }
 
+/// Send `signal` to the process group of every running tar
+fn signal_tar_processes(signal: libc::c_int) {
+    if let Ok(pids) = TAR_PIDS.lock() {
+        for pid in pids.iter() {
+            unsafe {
+                libc::kill(-(*pid as i32), signal);
+            }
+        }
+    }
+}
+
 #[tauri::command]
 fn cancel_backup() -> Result<(), String> {
     BACKUP_CANCELLED.store(true, Ordering::SeqCst);
+    let was_paused = BACKUP_PAUSED.swap(false, Ordering::SeqCst);
     
-    // Kill any running tar process
-    let pid = TAR_PID.load(Ordering::SeqCst);
-    if pid > 0 {
-        // Kill the process group to also kill zstd child
-        unsafe {
-            libc::kill(-(pid as i32), libc::SIGTERM);
-        }
-        TAR_PID.store(0, Ordering::SeqCst);
+    // Kill the process groups of all running tar processes to also kill their zstd children
+    signal_tar_processes(libc::SIGTERM);
+    // A stopped process group only handles SIGTERM after being continued
+    if was_paused {
+        signal_tar_processes(libc::SIGCONT);
     }
     
     Ok(())
 }
 
+#[tauri::command]
+fn pause_backup(window: tauri::Window) -> Result<(), String> {
+    BACKUP_PAUSED.store(true, Ordering::SeqCst);
+    
+    // Stop the running tar process groups (tar + zstd)
+    signal_tar_processes(libc::SIGSTOP);
+    
+    let _ = window.emit("backup-log", "⏸️ Backup pausiert");
+    let _ = window.emit("backup-progress", serde_json::json!({
+        "message": "Backup pausiert",
+        "paused": true
+    }));
+    Ok(())
+}
+
+#[tauri::command]
+fn resume_backup(window: tauri::Window) -> Result<(), String> {
+    BACKUP_PAUSED.store(false, Ordering::SeqCst);
+    
+    signal_tar_processes(libc::SIGCONT);
+    
+    let _ = window.emit("backup-log", "▶️ Backup fortgesetzt");
+    let _ = window.emit("backup-progress", serde_json::json!({
+        "message": "Backup wird fortgesetzt...",
+        "paused": false
+    }));
+    Ok(())
+}
+
 #[tauri::command]
 fn get_home_dir() -> Result<String, String> {
     dirs::home_dir()
@@ -2849,6 +11410,15 @@ fn get_home_dir() -> Result<String, String> {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    if let Some(code) = run_headless() {
+        std::process::exit(code);
+    }
+    
+    // Scheduled runs quit right away when the backup target isn't connected
+    if is_scheduled_run() && !scheduled_target_available() {
+        return;
+    }
+    
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
@@ -2860,22 +11430,71 @@ pub fn run() {
             load_config,
             save_config,
             get_external_volumes,
+            validate_backup_target,
+            eject_volume,
             check_homebrew,
             check_mas,
             get_brew_packages,
             get_mas_apps,
             get_manual_apps,
             get_manual_apps_from_backup,
+            backup_coverage,
             get_vscode_extensions,
+            get_login_items,
+            get_cron_and_agents,
+            get_recorded_shell,
+            change_login_shell,
             create_backup,
+            estimate_backup_size,
+            preview_backup,
+            get_backup_capabilities,
             list_backups,
+            project_capacity,
             delete_backup,
+            reveal_backup_in_finder,
+            deduplicate_backups,
+            find_orphaned_backups,
+            mount_archive_readonly,
+            repair_metadata,
+            scan_git_repositories,
+            get_git_repositories,
+            restore_git_repositories,
+            unmount_archive,
+            cleanup_orphans,
+            estimate_restore,
             restore_items,
+            restore_all,
+            list_archive_contents,
+            restore_single_file,
             quick_restore_essentials,
+            list_backup_packages,
+            restore_software_only,
             list_backup_files,
+            export_backup_report,
+            diff_backups,
+            test_extract_archive,
             verify_backup,
             verify_backup_parallel,
+            verify_with_manifest,
+            quick_verify,
+            run_self_test,
+            verify_restore_against_source,
+            verify_backup_incremental,
+            recompress_backup,
+            get_backup_log,
+            resume_interrupted_backup,
+            find_incomplete_backups,
+            register_backup_schedule,
+            unregister_backup_schedule,
+            get_schedule_status,
+            is_scheduled_run,
             cancel_backup,
+            cancel_verify,
+            cancel_restore,
+            store_passphrase_in_keychain,
+            get_passphrase_from_keychain,
+            pause_backup,
+            resume_backup,
             get_home_dir,
             list_user_folders,
             check_read_permission,
@@ -2885,6 +11504,8 @@ pub fn run() {
             show_help_window,
             get_window_state,
             save_window_state,
+            get_ui_state,
+            save_ui_state,
         ])
         .setup(|app| {
             let app_handle = app.handle();
@@ -2925,3 +11546,323 @@ pub fn run() {
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// The tests share the cancel flags and `TAR_PIDS`, so they run one at a time
+    static SERIAL: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    
+    /// Fresh scratch directory for a test. The config path points into the scratch area,
+    /// so the tests run with the default settings and never read the user's config.json.
+    fn scratch_dir(name: &str) -> PathBuf {
+        let base = std::env::temp_dir().join(format!("macos-backup-test-{}", std::process::id()));
+        let _ = CONFIG_PATH_OVERRIDE.set(base.join("config.json"));
+        let dir = base.join(name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+    
+    /// Points HOME at a scratch directory and puts the previous value back when dropped
+    struct HomeGuard(Option<std::ffi::OsString>);
+    
+    impl HomeGuard {
+        fn set(home: &Path) -> Self {
+            let previous = std::env::var_os("HOME");
+            std::env::set_var("HOME", home);
+            HomeGuard(previous)
+        }
+    }
+    
+    impl Drop for HomeGuard {
+        fn drop(&mut self) {
+            match self.0.take() {
+                Some(home) => std::env::set_var("HOME", home),
+                None => std::env::remove_var("HOME"),
+            }
+        }
+    }
+    
+    /// Finished backup of `size_bytes` in `target`, as far as `read_backup_metadata` is concerned
+    fn write_backup_metadata(target: &Path, timestamp: &str, size_bytes: u64) {
+        let backup_root = target.join("macos-backup-suite").join("data").join(timestamp);
+        fs::create_dir_all(&backup_root).unwrap();
+        let metadata = BackupMetadata {
+            metadata_version: METADATA_VERSION,
+            timestamp: timestamp.to_string(),
+            items: vec![BackupItem {
+                path: "/Users/test/Documents".to_string(),
+                archive: "Documents.tar.gz".to_string(),
+                hash: "0".repeat(64),
+                archive_size_bytes: size_bytes,
+                source_size_bytes: size_bytes,
+                format: Some(ArchiveFormat::Gz),
+                duration_seconds: 0.0,
+                parts: Vec::new(),
+                multi_volume: false,
+                user: None,
+                dictionary_hash: None,
+            }],
+            hash_algorithm: HashAlgorithm::Sha256.name().to_string(),
+            total_source_size_bytes: size_bytes,
+            start_time: String::new(),
+            end_time: String::new(),
+            duration_seconds: 0,
+            encrypted: false,
+            cipher: None,
+            skipped_directories: Vec::new(),
+            failed_directories: Vec::new(),
+            source_system: SourceSystem::default(),
+        };
+        fs::write(backup_root.join("metadata.json"), serde_json::to_string_pretty(&metadata).unwrap()).unwrap();
+    }
+    
+    #[test]
+    fn glob_match_handles_wildcards() {
+        assert!(glob_match("*.tmp", "notes.tmp"));
+        assert!(glob_match("node_modules", "node_modules"));
+        assert!(glob_match("._*", "._Foto.jpg"));
+        assert!(glob_match("file?.txt", "file1.txt"));
+        assert!(glob_match("*cache*", "Library/Caches/cache.db"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("*.tmp", "notes.tmp.bak"));
+        assert!(!glob_match("file?.txt", "file10.txt"));
+        assert!(!glob_match("node_modules", "node_module"));
+    }
+    
+    #[test]
+    fn migrate_config_upgrades_plain_directory_lists() {
+        let migrated = migrate_config(serde_json::json!({
+            "directories": ["/Users/test/Documents", { "path": "/Users/test/Desktop", "enabled": false }],
+        }));
+        assert_eq!(migrated["config_version"], CONFIG_VERSION);
+        assert_eq!(migrated["directories"][0], serde_json::json!({ "path": "/Users/test/Documents", "label": null, "enabled": true, "priority": 0 }));
+        assert_eq!(migrated["directories"][1]["enabled"], false);
+        
+        // Current configs pass through unchanged
+        let current = serde_json::json!({ "config_version": CONFIG_VERSION, "directories": ["/Users/test/Documents"] });
+        assert_eq!(migrate_config(current.clone()), current);
+    }
+    
+    #[test]
+    fn parse_checksum_manifest_accepts_only_plain_archive_names() {
+        let hash = "A".repeat(64);
+        let manifest = format!(
+            "{h}  Documents.tar.gz\n{h} *Desktop.tar.zst\n{h}  ../escape.tar.gz\n{h}  sub/dir.tar.gz\nabc  short.tar.gz\n\n",
+            h = hash
+        );
+        assert_eq!(parse_checksum_manifest(&manifest), vec![
+            (hash.to_lowercase(), "Documents.tar.gz".to_string()),
+            (hash.to_lowercase(), "Desktop.tar.zst".to_string()),
+        ]);
+    }
+    
+    #[test]
+    fn csv_field_quotes_only_when_needed() {
+        assert_eq!(csv_field("Documents"), "Documents");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("Zoll \"12\""), "\"Zoll \"\"12\"\"\"");
+        assert_eq!(csv_field("zwei\nZeilen"), "\"zwei\nZeilen\"");
+    }
+    
+    #[test]
+    fn volume_writer_splits_and_reassembles() {
+        let root = scratch_dir("volume-writer");
+        let archive = root.join("daten.tar.gz");
+        let data = self_test_content("daten", 10_000);
+        let mut writer = VolumeWriter::new(&archive, 4096, HashAlgorithm::Sha256);
+        // Uneven chunks, so writes cross volume boundaries
+        for chunk in data.chunks(1500) {
+            writer.write_all(chunk).unwrap();
+        }
+        let volumes = writer.finish().unwrap();
+        
+        let names: Vec<&str> = volumes.iter().map(|v| v.archive.as_str()).collect();
+        assert_eq!(names, ["daten.tar.gz.001", "daten.tar.gz.002", "daten.tar.gz.003"]);
+        assert_eq!(volumes.iter().map(|v| v.archive_size_bytes).collect::<Vec<_>>(), [4096, 4096, 1808]);
+        for volume in &volumes {
+            assert_eq!(hash_file(&root.join(&volume.archive), HashAlgorithm::Sha256).unwrap(), volume.hash);
+        }
+        
+        let mut joined = Vec::new();
+        let paths: Vec<PathBuf> = volumes.iter().map(|v| root.join(&v.archive)).collect();
+        VolumeReader::new(&paths, None, &root).read_to_end(&mut joined).unwrap();
+        assert_eq!(joined, data);
+        let _ = fs::remove_dir_all(&root);
+    }
+    
+    #[test]
+    fn crontab_executable_finds_absolute_commands() {
+        assert_eq!(crontab_executable("0 3 * * * /usr/local/bin/backup.sh --quiet").as_deref(), Some("/usr/local/bin/backup.sh"));
+        assert_eq!(crontab_executable("@daily /opt/tools/sync").as_deref(), Some("/opt/tools/sync"));
+        assert_eq!(crontab_executable("*/5 * * * * echo hallo"), None);
+        assert_eq!(crontab_executable("PATH=/usr/bin:/bin"), None);
+        assert_eq!(crontab_executable("# 0 3 * * * /usr/local/bin/backup.sh"), None);
+        assert_eq!(crontab_executable("   "), None);
+    }
+    
+    #[test]
+    fn restore_all_rank_puts_package_managers_first() {
+        let mut items = vec!["/Users/test/Documents", "dotfiles", "vscode-extensions", "homebrew-packages", "homebrew-cache", "/Users/test/Desktop"];
+        items.sort_by_key(|path| restore_all_rank(path));
+        assert_eq!(items, ["homebrew-cache", "homebrew-packages", "vscode-extensions", "dotfiles", "/Users/test/Documents", "/Users/test/Desktop"]);
+    }
+    
+    #[test]
+    fn project_capacity_follows_the_size_trend() {
+        let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let target = scratch_dir("capacity");
+        write_backup_metadata(&target, "20240101-120000", 100 * 1024 * 1024);
+        write_backup_metadata(&target, "20240102-120000", 200 * 1024 * 1024);
+        write_backup_metadata(&target, "20240103-120000", 300 * 1024 * 1024);
+        // Interrupted backups don't count
+        write_backup_metadata(&target, "20240104-120000", 900 * 1024 * 1024);
+        fs::write(target.join("macos-backup-suite/data/20240104-120000").join(IN_PROGRESS_MARKER), "").unwrap();
+        
+        let projection = project_capacity(target.to_string_lossy().to_string()).unwrap();
+        let timestamps: Vec<&str> = projection.backups.iter().map(|b| b.timestamp.as_str()).collect();
+        assert_eq!(timestamps, ["20240101-120000", "20240102-120000", "20240103-120000"]);
+        assert_eq!(projection.average_size_bytes, 200 * 1024 * 1024);
+        assert!((projection.growth_bytes_per_backup - 100.0 * 1024.0 * 1024.0).abs() < 1.0);
+        assert_eq!(projection.projected_remaining_backups.is_some(), projection.free_space_gb > 0.0);
+        
+        let empty = project_capacity(target.join("leer").to_string_lossy().to_string()).unwrap();
+        assert!(empty.backups.is_empty());
+        assert_eq!(empty.projected_remaining_backups, None);
+        let _ = fs::remove_dir_all(&target);
+    }
+    
+    #[test]
+    fn killed_tar_leaves_no_partial_archive() {
+        let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let root = scratch_dir("cancel");
+        let source = root.join("source");
+        fs::create_dir_all(&source).unwrap();
+        // Incompressible data, so tar is still writing when it gets killed
+        let mut data = Vec::new();
+        fs::File::open("/dev/urandom").unwrap().take(64 * 1024 * 1024).read_to_end(&mut data).unwrap();
+        fs::write(source.join("random.bin"), &data).unwrap();
+        
+        // Cut into volumes, so a partial volume set would be left behind as well
+        let target = root.join("source.tar.gz");
+        let archiving = {
+            let (source, target) = (source.clone(), target.clone());
+            std::thread::spawn(move || {
+                create_tar_gz(&source, &target, &[], &[], false, ArchiveFormat::Gz, Some(4 * 1024 * 1024), None, HashAlgorithm::Sha256, &BackupThrottle::default())
+            })
+        };
+        while TAR_PIDS.lock().unwrap().is_empty() {
+            assert!(!archiving.is_finished(), "tar wurde nicht gestartet");
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        BACKUP_CANCELLED.store(true, Ordering::SeqCst);
+        signal_tar_processes(libc::SIGKILL);
+        let result = archiving.join().unwrap();
+        BACKUP_CANCELLED.store(false, Ordering::SeqCst);
+        
+        assert_eq!(result.err().as_deref(), Some(BACKUP_CANCELLED_ERROR));
+        assert!(!target.exists());
+        assert!(!volume_path(&target, 1).exists());
+        let leftovers: Vec<_> = fs::read_dir(&root).unwrap()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| name.starts_with("source.tar.gz"))
+            .collect();
+        assert!(leftovers.is_empty(), "Reste des abgebrochenen Archivs: {:?}", leftovers);
+        let _ = fs::remove_dir_all(&root);
+    }
+    
+    #[test]
+    fn split_directory_children_counts_only_archived_files() {
+        let root = scratch_dir("split");
+        let photos = root.join("Fotos");
+        for (name, size) in [("2022/a.jpg", 3000), ("2023/b.jpg", 2000), ("2024/c.jpg", 1000), ("2024/film.mov", 50_000), ("cache/x.bin", 40_000)] {
+            let path = photos.join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, vec![0u8; size]).unwrap();
+        }
+        // The excluded cache and the file above the size limit don't count towards a group
+        let groups = split_directory_children(&photos, 2, &["Fotos/cache".to_string()], Some(10_000));
+        assert_eq!(groups, [(vec!["2022".to_string()], 3000), (vec!["2023".to_string(), "2024".to_string()], 3000)]);
+        let _ = fs::remove_dir_all(&root);
+    }
+    
+    #[test]
+    fn special_item_in_volumes_restores_whole() {
+        let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let root = scratch_dir("special-volumes");
+        let staged = root.join("staged");
+        fs::create_dir_all(staged.join("Caches")).unwrap();
+        let mut data = Vec::new();
+        fs::File::open("/dev/urandom").unwrap().take(3 * 1024 * 1024).read_to_end(&mut data).unwrap();
+        fs::write(staged.join("Caches/paket.bottle"), &data).unwrap();
+        fs::write(staged.join("liste.txt"), self_test_content("liste.txt", 1000)).unwrap();
+        
+        for passphrase in [None, Some("geheim")] {
+            let backup_root = root.join(if passphrase.is_some() { "verschlüsselt" } else { "klartext" });
+            fs::create_dir_all(&backup_root).unwrap();
+            let item = archive_special_dir(&staged, &backup_root, "homebrew-cache", passphrase, &[], ArchiveFormat::Gz,
+                HashAlgorithm::Sha256, Some(1024 * 1024), &BackupThrottle::default()).unwrap();
+            assert!(item.multi_volume);
+            
+            let plain = plain_item_archive(&backup_root, &item, passphrase, &root.join("plain")).unwrap();
+            let restored = root.join("restored");
+            let _ = fs::remove_dir_all(&restored);
+            extract_archive_to(&plain, &restored).unwrap();
+            assert_eq!(fs::read(restored.join("staged/Caches/paket.bottle")).unwrap(), data);
+            assert_eq!(fs::read(restored.join("staged/liste.txt")).unwrap(), self_test_content("liste.txt", 1000));
+        }
+        let _ = fs::remove_dir_all(&root);
+    }
+    
+    #[test]
+    fn backup_verify_and_restore_round_trip() {
+        let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let root = scratch_dir("round-trip");
+        // Only the directory is archived; `~` paths resolve into an empty home, not the user's
+        let home = root.join("home");
+        let _home = HomeGuard::set(&home);
+        let source = home.join("Dokumente");
+        let files = [("notizen.txt", 100), ("Fotos/Urlaub 2024/bild.jpg", 300 * 1024), ("Prüfung äöü.txt", 512)];
+        for (path, size) in files {
+            let file = source.join(path);
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(&file, self_test_content(path, size)).unwrap();
+        }
+        let target = root.join("ziel").to_string_lossy().to_string();
+        fs::create_dir_all(&target).unwrap();
+        let dir = source.to_string_lossy().to_string();
+        let directories = vec![BackupDirectory {
+            path: dir.clone(),
+            label: None,
+            enabled: true,
+            priority: 0,
+            includes: Vec::new(),
+            excludes: Vec::new(),
+        }];
+        
+        let sink = CollectingSink::default();
+        let mut log = BackupLog::new(Box::new(NoopSink));
+        let config = BackupConfig { backup_special_items: false, ..BackupConfig::default() };
+        let metadata = run_backup(&config, target.clone(), directories, None, None, None, None, &NoopSink, &mut log)
+            .unwrap_or_else(|e| panic!("Backup fehlgeschlagen: {}", e));
+        let item = metadata.items.iter().find(|item| item.path == dir).expect("Verzeichnis fehlt im Backup");
+        assert_eq!(item.source_size_bytes, files.iter().map(|(_, size)| *size as u64).sum::<u64>());
+        
+        let verified = run_verify(&target, &metadata.timestamp, true, &sink).unwrap();
+        assert!(verified.success, "Verifizierung fehlgeschlagen: {:?}", verified.failed_files);
+        
+        let restore_dir = root.join("wiederhergestellt");
+        let restored = run_restore(
+            &target, &metadata.timestamp, std::slice::from_ref(&dir), false, None, Some(restore_dir.to_string_lossy().to_string()),
+            false, true, None, None, None, &sink,
+        ).unwrap();
+        assert_eq!((restored.restored_count, restored.error_count), (1, 0), "{:?}", sink.lines());
+        for (path, size) in files {
+            assert_eq!(fs::read(restore_dir.join("Dokumente").join(path)).ok(), Some(self_test_content(path, size)), "{}", path);
+        }
+        let _ = fs::remove_dir_all(&root);
+    }
+}
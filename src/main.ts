@@ -3,13 +3,29 @@ import { listen } from "@tauri-apps/api/event";
 import { getCurrentWindow, LogicalSize, LogicalPosition } from "@tauri-apps/api/window";
 import { open, save, ask } from "@tauri-apps/plugin-dialog";
 import { writeTextFile } from "@tauri-apps/plugin-fs";
-import { sendNotification } from "@tauri-apps/plugin-notification";
+import { isPermissionGranted, requestPermission } from "@tauri-apps/plugin-notification";
 
 // Types
+interface BackupDirectory {
+  path: string;
+  label?: string | null;
+  enabled: boolean;
+  priority: number;
+  includes?: string[];
+  excludes?: string[];
+}
+
+interface BackupThrottle {
+  nice?: number;
+  background_io?: boolean;
+  zstd_threads?: number;
+  network_rate_limit_mb?: number;
+}
+
 interface BackupConfig {
   target_volume: string;
   target_directory: string;
-  directories: string[];
+  directories: BackupDirectory[];
   backup_homebrew: boolean;
   backup_mas: boolean;
   default_directories: string[];
@@ -17,6 +33,75 @@ interface BackupConfig {
   theme: string;
   backup_homebrew_cache: boolean;
   backup_safari_settings: boolean;
+  exclude_patterns?: string[];
+  max_file_size_mb?: number | null;
+  estimated_compression_ratio?: number;
+  eject_after_backup?: boolean;
+  backup_dock?: boolean;
+  backup_chrome?: boolean;
+  backup_firefox?: boolean;
+  notifications_enabled?: boolean;
+  notification_permission_granted?: boolean | null;
+  exclude_system_files?: boolean;
+  schedule?: BackupSchedule | null;
+  include_private_keys?: boolean;
+  dotfiles?: string[];
+  backup_system_defaults?: boolean;
+  system_defaults_domains?: string[];
+  backup_system_config?: boolean;
+  git_repository_roots?: string[];
+  follow_symlinks?: boolean;
+  backup_fonts?: boolean;
+  backup_terminal_settings?: boolean;
+  homebrew_cache_max_gb?: number;
+  staging_dir?: string | null;
+  adaptive_compression?: boolean;
+  zstd_dictionary?: boolean;
+  parallel_backup_jobs?: number;
+  split_directory_min_gb?: number;
+  split_directory_parts?: number;
+  generate_source_manifest?: boolean;
+  hash_algorithm?: "sha256" | "blake3";
+  throttle?: BackupThrottle;
+  quick_restore_brews?: string[];
+  quick_restore_casks?: string[];
+  backup_special_items?: boolean;
+  config_version?: number;
+}
+
+interface BackupCoverage {
+  manual_apps: string[];
+  brews: string[];
+  casks: string[];
+  mas_apps: string[];
+  vscode_extensions: string[];
+  total_missing: number;
+}
+
+interface RecompressResult {
+  recompressed_files: number;
+  skipped: string[];
+  bytes_before: number;
+  bytes_after: number;
+  bytes_saved: number;
+}
+
+interface BackupPackages {
+  brews: string[];
+  casks: string[];
+}
+
+interface SelfTestReport {
+  success: boolean;
+  phases: { name: string; success: boolean; duration_seconds: number; detail: string }[];
+  log: string[];
+}
+
+interface BackupSchedule {
+  frequency: "daily" | "weekly";
+  hour: number;
+  minute: number;
+  weekday?: number;
 }
 
 interface BackupItem {
@@ -29,6 +114,9 @@ interface BackupFileInfo {
   archive: string;
   archive_size_bytes: number;
   source_size_bytes: number;
+  compression_ratio: number;
+  duration_seconds: number;
+  user?: string;
 }
 
 interface BackupDetails {
@@ -36,9 +124,15 @@ interface BackupDetails {
   items: BackupFileInfo[];
   total_source_size_bytes: number;
   total_archive_size_bytes: number;
+  average_compression_ratio: number;
   start_time: string;
   end_time: string;
   duration_seconds: number;
+  os_version?: string;
+  os_build?: string;
+  hostname?: string;
+  arch?: string;
+  app_version?: string;
 }
 
 interface RestoreResult {
@@ -55,6 +149,9 @@ interface BackupFileInfo {
   archive: string;
   archive_size_bytes: number;
   source_size_bytes: number;
+  compression_ratio: number;
+  duration_seconds: number;
+  user?: string;
 }
 
 interface BackupDetails {
@@ -62,9 +159,15 @@ interface BackupDetails {
   items: BackupFileInfo[];
   total_source_size_bytes: number;
   total_archive_size_bytes: number;
+  average_compression_ratio: number;
   start_time: string;
   end_time: string;
   duration_seconds: number;
+  os_version?: string;
+  os_build?: string;
+  hostname?: string;
+  arch?: string;
+  app_version?: string;
 }
 
 interface RestoreResult {
@@ -76,6 +179,12 @@ interface RestoreResult {
   errors: string[];
 }
 
+interface GitRepository {
+  path: string;
+  remote_url: string;
+  branch: string | null;
+}
+
 interface Volume {
   name: string;
   path: string;
@@ -83,6 +192,9 @@ interface Volume {
   writable: boolean;
   is_internal: boolean;
   free_space_gb: number;
+  total_space_gb: number;
+  low_space: boolean;
+  volume_type: "internal" | "external" | "network";
 }
 
 interface UserFolder {
@@ -92,6 +204,48 @@ interface UserFolder {
   is_current_user: boolean;
 }
 
+type BackupEvent =
+  | { type: "item_started" | "item_completed" | "item_skipped" | "item_failed"; path: string; bytes: number; error?: string | null }
+  | { type: "warning"; message: string }
+  | { type: "summary"; success: boolean; item_count: number; bytes: number; error?: string | null };
+
+interface DirectoryIssue {
+  path: string;
+  reason: string;
+  detail: string | null;
+}
+
+// Selections restored on the next launch (ui_state.json)
+interface UiState {
+  target_volume?: string | null;
+  restore_timestamp?: string | null;
+  restore_overwrite?: boolean;
+}
+
+interface BackupTargetCheck {
+  writable: boolean;
+  is_time_machine: boolean;
+  is_boot_volume: boolean;
+  free_space_gb: number;
+  file_size_limited: boolean;
+  suitable: boolean;
+  reason: string | null;
+}
+
+interface CapacityProjection {
+  backups: { timestamp: string; size_bytes: number }[];
+  average_size_bytes: number;
+  growth_bytes_per_backup: number;
+  free_space_gb: number;
+  projected_remaining_backups: number | null;
+}
+
+interface BackupResult {
+  timestamp: string;
+  skipped_directories?: DirectoryIssue[];
+  failed_directories?: DirectoryIssue[];
+}
+
 interface PermissionCheckResult {
   path: string;
   readable: boolean;
@@ -100,6 +254,7 @@ interface PermissionCheckResult {
 
 interface FullDiskAccessStatus {
   has_full_disk_access: boolean;
+  confidence: "high" | "medium" | "low";
   tested_paths: string[];
   inaccessible_paths: string[];
 }
@@ -119,6 +274,9 @@ const translations: Record<string, Record<string, string>> = {
     reset: "Zurücksetzen",
     actions: "Aktionen",
     createBackup: "Backup erstellen",
+    pauseBackup: "Pausieren",
+    continueBackup: "Fortsetzen",
+    backupPaused: "Backup pausiert",
     cancel: "Abbrechen",
     restore: "Wiederherstellen",
     existingBackups: "Vorhandene Backups",
@@ -156,6 +314,13 @@ const translations: Record<string, Record<string, string>> = {
     volumesFound: "beschreibbare Volumes gefunden (Time Machine ausgeschlossen).",
     folderAdded: "Ordner hinzugefügt:",
     folderReset: "Ordnerliste auf Standardwerte zurückgesetzt.",
+    directoryEnabled: "Beim Backup sichern",
+    directoryPriority: "Priorität (höhere Werte werden zuerst archiviert)",
+    directoryLabel: "Doppelklick zum Umbenennen",
+    directoryLabelPrompt: "Anzeigename (leer für Pfad):",
+    directoryRules: "Ein-/Ausschlussregeln für dieses Verzeichnis",
+    directoryIncludesPrompt: "Nur diese Unterordner sichern (relativ, kommagetrennt, leer für alles):",
+    directoryExcludesPrompt: "Zusätzlich ausschließen (relativ, kommagetrennt, z. B. Caches, Logs/*):",
     selectError: "Fehler beim Auswählen:",
     copySuccess: "Protokoll in Zwischenablage kopiert.",
     copyError: "Fehler beim Kopieren:",
@@ -168,8 +333,6 @@ const translations: Record<string, Record<string, string>> = {
     masFound: "Mac App Store CLI (mas) gefunden.",
     masNotInstalled: "Mac App Store CLI (mas) nicht installiert.",
     homebrewCheckFailed: "Homebrew-Check fehlgeschlagen.",
-    backupNotification: "Backup abgeschlossen",
-    backupNotificationBody: "Das Backup wurde erfolgreich erstellt.",
     selectRestoreBackup: "Bitte ein Backup zum Wiederherstellen auswählen!",
     restoreStarted: "Wiederherstellung von Backup gestartet...",
     restoreComingSoon: "Restore-Funktion wird in Kürze implementiert.",
@@ -196,14 +359,49 @@ const translations: Record<string, Record<string, string>> = {
     fullDiskAccessRequired: "Full Disk Access erforderlich",
     fullDiskAccessHint: "Um Ordner anderer Benutzer zu sichern, aktiviere Full Disk Access in den Systemeinstellungen. Nach dem Aktivieren muss die App neu gestartet werden.",
     openSettings: "Einstellungen öffnen",
+    incompleteBackupFound: "Unvollständiges Backup gefunden:",
+    directoryNotBackedUp: "Nicht vollständig gesichert:",
+    directoriesWithIssues: "Ordner mit Problemen",
+    scheduledBackupStarted: "Geplantes Backup gestartet",
+    changeShell: "Login-Shell ändern",
+    changeShellPrompt: "Das Backup verwendete eine andere Login-Shell. Wechseln zu",
+    cloneGitRepos: "Git-Repositories klonen",
+    cloneGitReposPrompt: "Git-Repositories aus dem Backup, die hier noch fehlen, jetzt klonen? Anzahl:",
+    shellChanged: "Login-Shell geändert:",
+    resumeBackup: "Backup fortsetzen",
+    resumeBackupPrompt: "Ein Backup wurde unterbrochen. Soll es fortgesetzt werden?",
     restartApp: "App neu starten",
     fullDiskAccessGranted: "Full Disk Access ist aktiviert.",
     fullDiskAccessMissing: "⚠️ Eingeschränkter Zugriff – Full Disk Access fehlt.",
+    fullDiskAccessUncertain: "⚠️ Full Disk Access konnte nicht eindeutig geprüft werden. Bitte in den Systemeinstellungen kontrollieren.",
     checkingAccess: "Prüfe Zugriffsrechte...",
     permissionDenied: "Zugriff verweigert:",
     addSystemConfigs: "System-Configs",
     systemConfigsAdded: "System-Konfigurationspfade hinzugefügt:",
     systemConfigsHint: "Wichtige Konfig-Dateien für schnelle Wiederherstellung",
+    fdaRequiredTitle: "Full Disk Access erforderlich",
+    fdaRequiredPrompt: "Diese Ordner konnten ohne Full Disk Access nicht gesichert werden. Systemeinstellungen öffnen und das Backup danach erneut starten?",
+    selfTest: "Selbsttest",
+    selfTestRun: "Selbsttest starten",
+    selfTestHint: "Sichert, prüft und stellt Testdateien in einem temporären Ordner wieder her",
+    selfTestStarted: "🩺 Selbsttest läuft...",
+    selfTestPassed: "✅ Selbsttest bestanden",
+    selfTestFailed: "❌ Selbsttest fehlgeschlagen",
+    capacityRemaining: "Platz für ca.",
+    capacityBackups: "weitere Backups",
+    capacityAverage: "Ø pro Backup",
+    essentialPackages: "⭐ Essentielle Pakete für den Quick-Restore",
+    essentialPackagesHint: "Markierte Pakete installiert der Quick-Restore zuerst.",
+    coverageTitle: "Nicht im Backup enthalten",
+    coverageComplete: "✅ Das Backup enthält alle aktuell installierte Software.",
+    coverageRerun: "Seit diesem Backup installiert – Backup erneut ausführen:",
+    coverageError: "Fehler bei der Abdeckungsprüfung:",
+    recompressPrompt: "Backup mit zstd-Stufe 19 neu komprimieren? Das kann lange dauern, spart aber Platz bei älteren Backups.",
+    recompressTitle: "Backup neu komprimieren",
+    recompressStarted: "🗜️ Komprimiere Backup neu (Stufe 19)...",
+    recompressDone: "✅ Neu komprimiert:",
+    recompressSaved: "eingespart",
+    recompressError: "Fehler beim Neukomprimieren:",
     restoreModalTitle: "Wiederherstellung",
     selectItemsToRestore: "Elemente zur Wiederherstellung auswählen:",
     overwriteExisting: "Bestehende Dateien überschreiben",
@@ -232,6 +430,9 @@ const translations: Record<string, Record<string, string>> = {
     reset: "Reset",
     actions: "Actions",
     createBackup: "Create Backup",
+    pauseBackup: "Pause",
+    continueBackup: "Continue",
+    backupPaused: "Backup paused",
     cancel: "Cancel",
     restore: "Restore",
     existingBackups: "Existing Backups",
@@ -269,6 +470,13 @@ const translations: Record<string, Record<string, string>> = {
     volumesFound: "writable volumes found (Time Machine excluded).",
     folderAdded: "Folder added:",
     folderReset: "Folder list reset to default.",
+    directoryEnabled: "Include in backup",
+    directoryPriority: "Priority (higher values are archived first)",
+    directoryLabel: "Double-click to rename",
+    directoryLabelPrompt: "Display name (empty for path):",
+    directoryRules: "Include/exclude rules for this directory",
+    directoryIncludesPrompt: "Only back up these subfolders (relative, comma-separated, empty for everything):",
+    directoryExcludesPrompt: "Also exclude (relative, comma-separated, e.g. Caches, Logs/*):",
     selectError: "Selection error:",
     copySuccess: "Log copied to clipboard.",
     copyError: "Copy error:",
@@ -281,8 +489,6 @@ const translations: Record<string, Record<string, string>> = {
     masFound: "Mac App Store CLI (mas) found.",
     masNotInstalled: "Mac App Store CLI (mas) not installed.",
     homebrewCheckFailed: "Homebrew check failed.",
-    backupNotification: "Backup Complete",
-    backupNotificationBody: "The backup was successfully created.",
     selectRestoreBackup: "Please select a backup to restore!",
     restoreStarted: "Restore from backup started...",
     restoreComingSoon: "Restore function coming soon.",
@@ -309,14 +515,49 @@ const translations: Record<string, Record<string, string>> = {
     fullDiskAccessRequired: "Full Disk Access Required",
     fullDiskAccessHint: "To backup folders of other users, enable Full Disk Access in System Settings. After enabling, the app must be restarted.",
     openSettings: "Open Settings",
+    incompleteBackupFound: "Incomplete backup found:",
+    directoryNotBackedUp: "Not fully backed up:",
+    directoriesWithIssues: "folders with problems",
+    scheduledBackupStarted: "Scheduled backup started",
+    changeShell: "Change Login Shell",
+    changeShellPrompt: "The backup used a different login shell. Switch to",
+    cloneGitRepos: "Clone Git Repositories",
+    cloneGitReposPrompt: "Clone the Git repositories from the backup that are missing here? Count:",
+    shellChanged: "Login shell changed:",
+    resumeBackup: "Resume Backup",
+    resumeBackupPrompt: "A backup was interrupted. Do you want to resume it?",
     restartApp: "Restart App",
     fullDiskAccessGranted: "Full Disk Access is enabled.",
     fullDiskAccessMissing: "⚠️ Limited access – Full Disk Access missing.",
+    fullDiskAccessUncertain: "⚠️ Full Disk Access could not be determined reliably. Please check System Settings.",
     checkingAccess: "Checking access rights...",
     permissionDenied: "Access denied:",
     addSystemConfigs: "System Configs",
     systemConfigsAdded: "System config paths added:",
     systemConfigsHint: "Important config files for quick restore",
+    fdaRequiredTitle: "Full Disk Access required",
+    fdaRequiredPrompt: "These folders could not be backed up without Full Disk Access. Open System Settings and run the backup again afterwards?",
+    selfTest: "Self-test",
+    selfTestRun: "Run self-test",
+    selfTestHint: "Backs up, verifies and restores sample files in a temporary folder",
+    selfTestStarted: "🩺 Running self-test...",
+    selfTestPassed: "✅ Self-test passed",
+    selfTestFailed: "❌ Self-test failed",
+    capacityRemaining: "Room for about",
+    capacityBackups: "more backups",
+    capacityAverage: "avg. per backup",
+    essentialPackages: "⭐ Essential packages for Quick-Restore",
+    essentialPackagesHint: "Quick-Restore installs the marked packages first.",
+    coverageTitle: "Not in backup",
+    coverageComplete: "✅ The backup contains all currently installed software.",
+    coverageRerun: "Installed since this backup – run the backup again:",
+    coverageError: "Coverage check failed:",
+    recompressPrompt: "Recompress the backup at zstd level 19? This can take a long time but saves space on older backups.",
+    recompressTitle: "Recompress backup",
+    recompressStarted: "🗜️ Recompressing backup (level 19)...",
+    recompressDone: "✅ Recompressed:",
+    recompressSaved: "saved",
+    recompressError: "Recompression failed:",
   }
 };
 
@@ -339,17 +580,22 @@ const addSystemConfigsBtn = document.getElementById("add-system-configs") as HTM
 const resetDirectoriesBtn = document.getElementById("reset-directories") as HTMLButtonElement;
 const btnBackup = document.getElementById("btn-backup") as HTMLButtonElement;
 const btnCancel = document.getElementById("btn-cancel") as HTMLButtonElement;
+const btnPause = document.getElementById("btn-pause") as HTMLButtonElement;
 const btnRestore = document.getElementById("btn-restore") as HTMLButtonElement;
 const btnRestoreTest = document.getElementById("btn-restore-test") as HTMLButtonElement;
 const backupSelect = document.getElementById("backup-select") as HTMLSelectElement;
+const capacityProjection = document.getElementById("capacity-projection");
 const showFilesBtn = document.getElementById("show-files") as HTMLButtonElement;
 const showManualAppsBtn = document.getElementById("show-manual-apps") as HTMLButtonElement;
+const showCoverageBtn = document.getElementById("show-coverage") as HTMLButtonElement;
 const btnDeleteBackup = document.getElementById("btn-delete-backup") as HTMLButtonElement;
+const btnRecompress = document.getElementById("btn-recompress") as HTMLButtonElement;
 const restoreModal = document.getElementById("restore-modal") as HTMLDivElement;
 const restoreItemsList = document.getElementById("restore-items-list") as HTMLDivElement;
 const restoreSelectAll = document.getElementById("restore-select-all") as HTMLButtonElement;
 const restoreDeselectAll = document.getElementById("restore-deselect-all") as HTMLButtonElement;
 const restoreOverwrite = document.getElementById("restore-overwrite") as HTMLInputElement;
+const restoreStartServices = document.getElementById("restore-start-services") as HTMLInputElement;
 const restoreCancel = document.getElementById("restore-cancel") as HTMLButtonElement;
 const restoreStart = document.getElementById("restore-start") as HTMLButtonElement;
 const progressMessage = document.getElementById("progress-message") as HTMLParagraphElement;
@@ -367,9 +613,12 @@ const defaultDirectoriesList = document.getElementById("default-directories-list
 const addDefaultDirectoryBtn = document.getElementById("add-default-directory") as HTMLButtonElement;
 const settingsCancelBtn = document.getElementById("settings-cancel") as HTMLButtonElement;
 const settingsSaveBtn = document.getElementById("settings-save") as HTMLButtonElement;
+const btnSelfTest = document.getElementById("btn-self-test") as HTMLButtonElement;
 const backupHomebrewCacheCheckbox = document.getElementById("backup-homebrew-cache") as HTMLInputElement;
 const backupSafariSettingsCheckbox = document.getElementById("backup-safari-settings") as HTMLInputElement;
 const restoreQuickBtn = document.getElementById("restore-quick") as HTMLButtonElement;
+const restoreEverythingBtn = document.getElementById("restore-everything") as HTMLButtonElement;
+const restoreSoftwareBtn = document.getElementById("restore-software") as HTMLButtonElement;
 const userFolderDialog = document.getElementById("user-folder-dialog") as HTMLDialogElement;
 const userFolderList = document.getElementById("user-folder-list") as HTMLUListElement;
 const userFolderCloseBtn = document.getElementById("user-folder-close") as HTMLButtonElement;
@@ -391,6 +640,10 @@ let config: BackupConfig = {
 
 let currentVolumes: Volume[] = [];
 let backupInProgress = false;
+// Set while the running backup is paused via pause_backup
+let backupPaused = false;
+let verifyInProgress = false;
+let restoreInProgress = false;
 let tempDefaultDirectories: string[] = [];
 let hasFDA = true; // Full Disk Access status
 let fdaMessageShown = false; // Track if FDA message was already shown
@@ -405,6 +658,11 @@ const INITIAL_DEFAULT_DIRECTORIES = [
   "~/.gitconfig",
   "~/.zshrc",
 ];
+// DirectoryIssue reason of folders the backend could not read without Full Disk Access
+const FDA_REQUIRED_REASON = "Full Disk Access erforderlich";
+// Error of a backup stopped with cancel_backup, as opposed to a failed one
+const BACKUP_CANCELLED_ERROR = "Backup wurde abgebrochen";
+
 // System configuration directories for quick restore after OS reinstall
 const SYSTEM_CONFIG_DIRECTORIES = [
   // Developer configs
@@ -506,6 +764,7 @@ function updateUITranslations(): void {
   
   btnBackup.innerHTML = `📤 ${t("createBackup")}`;
   btnCancel.textContent = t("cancel");
+  updatePauseButton();
   btnRestore.innerHTML = `📥 ${t("restore")}`;
   addDirectoryBtn.innerHTML = `+ ${t("addFolder")}`;
   if (addUserDirectoryBtn) {
@@ -547,7 +806,7 @@ async function loadConfig(): Promise<void> {
       config.default_directories = [...INITIAL_DEFAULT_DIRECTORIES];
     }
     if (config.directories.length === 0) {
-      config.directories = [...config.default_directories];
+      config.directories = config.default_directories.map(toBackupDirectory);
     }
     if (config.language) {
       applyLanguage(config.language);
@@ -560,7 +819,7 @@ async function loadConfig(): Promise<void> {
     log(t("configLoaded"));
   } catch (e) {
     config.default_directories = [...INITIAL_DEFAULT_DIRECTORIES];
-    config.directories = [...INITIAL_DEFAULT_DIRECTORIES];
+    config.directories = INITIAL_DEFAULT_DIRECTORIES.map(toBackupDirectory);
     updateDirectoriesList();
     log(t("defaultConfigUsed"));
   }
@@ -606,7 +865,9 @@ function updateVolumeSelect(): void {
     for (const vol of external) {
       const option = document.createElement("option");
       option.value = vol.path;
-      option.textContent = `${vol.name} (${formatBytes(vol.free_space_gb)} ${t("freeSpace")})`;
+      const networkTag = vol.volume_type === "network" ? " 🌐" : "";
+      const lowSpaceTag = vol.low_space ? " ⚠️" : "";
+      option.textContent = `${vol.name}${networkTag}${lowSpaceTag} (${formatBytes(vol.free_space_gb)} / ${formatBytes(vol.total_space_gb)} ${t("freeSpace")})`;
       extGroup.appendChild(option);
     }
     volumeSelect.appendChild(extGroup);
@@ -618,7 +879,8 @@ function updateVolumeSelect(): void {
     for (const vol of internal) {
       const option = document.createElement("option");
       option.value = vol.path;
-      option.textContent = `${vol.name} (${formatBytes(vol.free_space_gb)} ${t("freeSpace")})`;
+      const lowSpaceTag = vol.low_space ? " ⚠️" : "";
+      option.textContent = `${vol.name}${lowSpaceTag} (${formatBytes(vol.free_space_gb)} / ${formatBytes(vol.total_space_gb)} ${t("freeSpace")})`;
       intGroup.appendChild(option);
     }
     volumeSelect.appendChild(intGroup);
@@ -660,6 +922,7 @@ async function loadBackups(): Promise<void> {
   const targetPath = getFullTargetPath();
   if (!targetPath) {
     backupSelect.innerHTML = `<option value="">${t("selectTargetFirst")}</option>`;
+    if (capacityProjection) capacityProjection.textContent = "";
     return;
   }
   
@@ -681,11 +944,26 @@ async function loadBackups(): Promise<void> {
     if (backups.length === 0) {
       backupSelect.innerHTML = `<option value="">${t("noBackups")}</option>`;
     }
+    await updateCapacityProjection(targetPath);
   } catch (e) {
     log(`${t("selectError")} ${e}`);
   }
 }
 
+// Show how many more backups fit on the target at the current size trend
+async function updateCapacityProjection(targetPath: string): Promise<void> {
+  if (!capacityProjection) return;
+  capacityProjection.textContent = "";
+  try {
+    const projection = await invoke<CapacityProjection>("project_capacity", { targetPath });
+    if (projection.projected_remaining_backups === null) return;
+    const averageGb = projection.average_size_bytes / (1024 * 1024 * 1024);
+    capacityProjection.textContent = `${t("capacityRemaining")} ${projection.projected_remaining_backups} ${t("capacityBackups")} (${formatBytes(averageGb)} ${t("capacityAverage")})`;
+  } catch (e) {
+    console.error("Capacity projection failed:", e);
+  }
+}
+
 // Format timestamp from YYYYMMDD-HHMMSS to readable format
 function formatTimestamp(ts: string): string {
   if (ts.length !== 15) return ts;
@@ -697,26 +975,75 @@ function formatTimestamp(ts: string): string {
   return `${day}.${month}.${year} ${hour}:${min}`;
 }
 
+function toBackupDirectory(path: string): BackupDirectory {
+  return { path, label: null, enabled: true, priority: 0, includes: [], excludes: [] };
+}
+
+// Status of each directory in the running or last backup, from `backup-event`
+const directoryStatus = new Map<string, { icon: string; error?: string | null }>();
+const BACKUP_EVENT_ICONS: Record<string, string> = {
+  item_started: "⏳",
+  item_completed: "✅",
+  item_skipped: "⏭️",
+  item_failed: "❌",
+};
+
+function hasDirectory(path: string): boolean {
+  return config.directories.some((d) => d.path === path);
+}
+
 // Update directories list UI
 function updateDirectoriesList(): void {
   directoriesList.innerHTML = "";
   for (const dir of config.directories) {
     const li = document.createElement("li");
+    li.classList.toggle("disabled", !dir.enabled);
+    const status = directoryStatus.get(dir.path);
+    // Completed with an error means the archive is incomplete
+    const icon = status ? (status.icon === "✅" && status.error ? "⚠️" : status.icon) : "";
+    const hasRules = (dir.includes?.length ?? 0) + (dir.excludes?.length ?? 0) > 0;
     li.innerHTML = `
-      <span>${dir}</span>
-      <button class="remove-dir" data-path="${dir}">✕</button>
+      <input type="checkbox" class="dir-enabled" title="${t("directoryEnabled")}" ${dir.enabled ? "checked" : ""}>
+      <span title="${dir.path}${status?.error ? "\n" + status.error : ""}\n${t("directoryLabel")}">${icon ? icon + " " : ""}${dir.label || dir.path}</span>
+      <input type="number" class="dir-priority" title="${t("directoryPriority")}" value="${dir.priority}">
+      <button class="dir-rules${hasRules ? " active" : ""}" title="${t("directoryRules")}">⚙</button>
+      <button class="remove-dir">✕</button>
     `;
-    directoriesList.appendChild(li);
-  }
-  
-  document.querySelectorAll(".remove-dir").forEach((btn) => {
-    btn.addEventListener("click", (e) => {
-      const path = (e.target as HTMLButtonElement).dataset.path!;
-      config.directories = config.directories.filter((d) => d !== path);
+    
+    li.querySelector(".dir-enabled")!.addEventListener("change", (e) => {
+      dir.enabled = (e.target as HTMLInputElement).checked;
+      li.classList.toggle("disabled", !dir.enabled);
+      saveConfig();
+    });
+    li.querySelector("span")!.addEventListener("dblclick", () => {
+      const label = prompt(t("directoryLabelPrompt"), dir.label || "");
+      if (label === null) return;
+      dir.label = label.trim() || null;
       updateDirectoriesList();
       saveConfig();
     });
-  });
+    li.querySelector(".dir-priority")!.addEventListener("change", (e) => {
+      dir.priority = parseInt((e.target as HTMLInputElement).value, 10) || 0;
+      saveConfig();
+    });
+    li.querySelector(".dir-rules")!.addEventListener("click", () => {
+      const includes = prompt(t("directoryIncludesPrompt"), (dir.includes ?? []).join(", "));
+      if (includes === null) return;
+      const excludes = prompt(t("directoryExcludesPrompt"), (dir.excludes ?? []).join(", "));
+      if (excludes === null) return;
+      const split = (value: string) => value.split(",").map((p) => p.trim()).filter((p) => p);
+      dir.includes = split(includes);
+      dir.excludes = split(excludes);
+      updateDirectoriesList();
+      saveConfig();
+    });
+    li.querySelector(".remove-dir")!.addEventListener("click", () => {
+      config.directories = config.directories.filter((d) => d !== dir);
+      updateDirectoriesList();
+      saveConfig();
+    });
+    directoriesList.appendChild(li);
+  }
 }
 
 // Update default directories list in settings
@@ -745,6 +1072,9 @@ async function checkFullDiskAccess(): Promise<void> {
   try {
     const status = await invoke<FullDiskAccessStatus>("check_full_disk_access");
     hasFDA = status.has_full_disk_access;
+    if (status.confidence === "low") {
+      log(t("fullDiskAccessUncertain"));
+    }
     
     if (hasFDA) {
       if (!fdaMessageShown) {
@@ -938,8 +1268,8 @@ async function addFolderWithPermissionCheck(path: string): Promise<void> {
       displayPath = "~" + path.substring(homeDir.length);
     }
     
-    if (!config.directories.includes(displayPath) && !config.directories.includes(path)) {
-      config.directories.push(displayPath);
+    if (!hasDirectory(displayPath) && !hasDirectory(path)) {
+      config.directories.push(toBackupDirectory(displayPath));
       updateDirectoriesList();
       await saveConfig();
       log(`${t("folderAdded")} ${displayPath}`);
@@ -949,8 +1279,8 @@ async function addFolderWithPermissionCheck(path: string): Promise<void> {
   }
 }
 
-// Start backup
-async function startBackup(): Promise<void> {
+// Start backup, or continue the interrupted backup `resumeTimestamp`
+async function startBackup(resumeTimestamp?: string): Promise<void> {
   // Re-check FDA before starting backup
   await checkFullDiskAccess();
   if (!hasFDA) {
@@ -966,30 +1296,68 @@ async function startBackup(): Promise<void> {
   }
   
   backupInProgress = true;
+  directoryStatus.clear();
+  updateDirectoriesList();
   btnBackup.disabled = true;
   btnBackup.style.display = "none";
   btnCancel.style.display = "block";
+  setBackupPaused(false);
+  btnPause.style.display = "block";
   statusEl.textContent = t("backupRunning");
   progressMessage.textContent = t("startingBackup");
   progressFill.style.width = "0%";
+  await ensureNotificationPermission();
   
   try {
-    await invoke("create_backup", {
-      targetPath: targetPath,
-      directories: config.directories,
-    });
+    let result: BackupResult;
+    if (resumeTimestamp) {
+      result = await invoke<BackupResult>("resume_interrupted_backup", {
+        targetPath: targetPath,
+        timestamp: resumeTimestamp,
+        directories: config.directories,
+      });
+    } else {
+      result = await invoke<BackupResult>("create_backup", {
+        targetPath: targetPath,
+        directories: config.directories,
+      });
+    }
+    
+    // Tell the user exactly which folders are missing or incomplete
+    const issues = [...(result.skipped_directories || []), ...(result.failed_directories || [])];
+    for (const issue of issues) {
+      log(`⚠️ ${t("directoryNotBackedUp")} ${issue.path} (${issue.reason})`);
+    }
+    const needsFullDiskAccess = (result.failed_directories || []).filter(d => d.reason === FDA_REQUIRED_REASON);
+    if (needsFullDiskAccess.length > 0 && await ask(`${t("fdaRequiredPrompt")}\n\n${needsFullDiskAccess.map(d => d.path).join("\n")}`, { title: t("fdaRequiredTitle"), kind: "warning" })) {
+      await openPrivacySettings();
+    }
+    
+    // Cheap size check right after writing; the full hash verify stays a separate step.
+    // When the target is ejected the backend has already run it before ejecting.
+    if (!config.eject_after_backup) {
+      try {
+        const check = await invoke<{ success: boolean; failed_files: string[]; message: string }>("quick_verify", {
+          targetPath,
+          timestamp: result.timestamp,
+        });
+        log(`${check.success ? "✅" : "❌"} ${check.message}`);
+        for (const failure of check.failed_files) {
+          log(`  - ${failure}`);
+        }
+      } catch (e) {
+        log(`⚠️ ${e}`);
+      }
+    }
     
     if (backupInProgress) {
-      await sendNotification({
-        title: t("backupNotification"),
-        body: t("backupNotificationBody"),
-      });
-      
-      statusEl.textContent = t("backupComplete");
+      statusEl.textContent = issues.length > 0
+        ? `${t("backupComplete")} – ${issues.length} ${t("directoriesWithIssues")}`
+        : t("backupComplete");
     }
     await loadBackups();
   } catch (e) {
-    if (backupInProgress) {
+    if (backupInProgress && String(e) !== BACKUP_CANCELLED_ERROR) {
       log(`${t("backupFailed")} ${e}`);
       statusEl.textContent = t("backupFailed");
     } else {
@@ -1000,14 +1368,76 @@ async function startBackup(): Promise<void> {
     btnBackup.disabled = false;
     btnBackup.style.display = "block";
     btnCancel.style.display = "none";
+    btnPause.style.display = "none";
+    setBackupPaused(false);
+  }
+}
+
+// Pause/continue button label for the current state
+function updatePauseButton(): void {
+  btnPause.textContent = backupPaused ? `▶️ ${t("continueBackup")}` : `⏸️ ${t("pauseBackup")}`;
+}
+
+// Show whether the running backup is paused; the progress bar stands still while it is
+function setBackupPaused(paused: boolean): void {
+  backupPaused = paused;
+  updatePauseButton();
+  progressFill.classList.toggle("paused", paused);
+  if (backupInProgress) {
+    statusEl.textContent = paused ? t("backupPaused") : t("backupRunning");
+  }
+}
+
+// Pause the running backup (tar is stopped) or let it continue
+async function togglePauseBackup(): Promise<void> {
+  if (!backupInProgress) return;
+  try {
+    await invoke(backupPaused ? "resume_backup" : "pause_backup");
+    setBackupPaused(!backupPaused);
+  } catch (e) {
+    log(`⚠️ ${e}`);
+  }
+}
+
+// Offer to resume the newest interrupted backup on the current target
+async function checkIncompleteBackups(): Promise<void> {
+  const targetPath = getFullTargetPath();
+  if (!targetPath) return;
+  
+  try {
+    const incomplete = await invoke<string[]>("find_incomplete_backups", { targetPath });
+    if (incomplete.length === 0) return;
+    
+    const timestamp = incomplete[0];
+    log(`⚠️ ${t("incompleteBackupFound")} ${formatTimestamp(timestamp)}`);
+    const resume = await ask(t("resumeBackupPrompt") + "\n\n" + formatTimestamp(timestamp), {
+      title: t("resumeBackup"),
+      kind: "warning",
+    });
+    if (resume) {
+      await startBackup(timestamp);
+    }
+  } catch (e) {
+    // Target not reachable - nothing to resume
   }
 }
 
 // Cancel backup
 async function cancelBackup(): Promise<void> {
+  if (verifyInProgress) {
+    await invoke("cancel_verify");
+    return;
+  }
+  if (restoreInProgress) {
+    await invoke("cancel_restore");
+    log("⏹️ Wiederherstellung wird abgebrochen...");
+    return;
+  }
   if (!backupInProgress) return;
   
   backupInProgress = false;
+  btnPause.style.display = "none";
+  setBackupPaused(false);
   try {
     await invoke("cancel_backup");
     log(t("backupCancelled"));
@@ -1027,21 +1457,99 @@ async function setupEventListeners(): Promise<void> {
     log(event.payload);
   });
   
-  await listen<{ progress: number; message: string }>("backup-progress", (event) => {
-    progressMessage.textContent = event.payload.message;
-    progressFill.style.width = `${event.payload.progress}%`;
+  await listen<BackupEvent>("backup-event", (event) => {
+    const payload = event.payload;
+    if (payload.type === "warning" || payload.type === "summary") return;
+    if (!hasDirectory(payload.path)) return;
+    directoryStatus.set(payload.path, { icon: BACKUP_EVENT_ICONS[payload.type], error: payload.error });
+    updateDirectoriesList();
+  });
+  
+  await listen<{
+    progress?: number;
+    message: string;
+    paused?: boolean;
+    bytes_per_second?: number;
+    eta_seconds?: number | null;
+  }>("backup-progress", (event) => {
+    const { bytes_per_second, eta_seconds, paused } = event.payload;
+    if (paused !== undefined) {
+      setBackupPaused(paused);
+    } else if (backupPaused) {
+      // Progress of work that was already underway must not hide the paused state
+      return;
+    }
+    let message = event.payload.message;
+    if (bytes_per_second && bytes_per_second > 0) {
+      const rate = formatBytes(bytes_per_second / 1e9);
+      const eta = eta_seconds != null
+        ? ` · noch ${Math.floor(eta_seconds / 60)}m ${eta_seconds % 60}s`
+        : "";
+      message += ` (${rate}/s${eta})`;
+    }
+    progressMessage.textContent = message;
+    if (event.payload.progress !== undefined) {
+      progressFill.style.width = `${event.payload.progress}%`;
+    }
   });
 }
 
+async function saveUiState(): Promise<void> {
+  const state: UiState = {
+    target_volume: config.target_volume || null,
+    restore_timestamp: backupSelect.value || null,
+    restore_overwrite: restoreOverwrite.checked,
+  };
+  try {
+    await invoke("save_ui_state", { state });
+  } catch (_e) {
+    // UI state is a convenience only
+  }
+}
+
+// Re-apply the last session's target volume, backup selection and overwrite choice
+async function restoreUiState(): Promise<string | null> {
+  try {
+    const state = await invoke<UiState>("get_ui_state");
+    if (state.target_volume && state.target_volume !== config.target_volume) {
+      config.target_volume = state.target_volume;
+      config.target_directory = "";
+    }
+    restoreOverwrite.checked = state.restore_overwrite ?? false;
+    return state.restore_timestamp ?? null;
+  } catch (_e) {
+    return null;
+  }
+}
+
 // Event handlers
+// Log why the selected target is unsuitable, or warnings such as a FAT file size limit
+async function checkBackupTarget(): Promise<void> {
+  try {
+    const check = await invoke<BackupTargetCheck>("validate_backup_target", { targetPath: getFullTargetPath() });
+    if (check.reason) {
+      log(`${check.suitable ? "⚠️" : "❌"} ${check.reason}`);
+    }
+  } catch (e) {
+    console.error("Target check failed:", e);
+  }
+}
+
 volumeSelect.addEventListener("change", async () => {
   config.target_volume = volumeSelect.value;
   config.target_directory = "";
   updateTargetPathDisplay();
   await saveConfig();
   await loadBackups();
+  await saveUiState();
+  if (config.target_volume) {
+    await checkBackupTarget();
+  }
 });
 
+backupSelect.addEventListener("change", saveUiState);
+restoreOverwrite.addEventListener("change", saveUiState);
+
 refreshVolumesBtn.addEventListener("click", () => {
   loadVolumes();
 });
@@ -1080,6 +1588,7 @@ browseTargetBtn?.addEventListener("click", async () => {
       await saveConfig();
       await loadBackups();
       log(`${t("backupTargetSet")} ${getFullTargetPath()}`);
+      await checkBackupTarget();
     }
   } catch (e) {
     log(`${t("selectError")} ${e}`);
@@ -1118,7 +1627,7 @@ async function addSystemConfigDirectories(): Promise<void> {
       : configPath;
     
     // Check if already in list
-    if (config.directories.includes(configPath)) {
+    if (hasDirectory(configPath)) {
       continue;
     }
     
@@ -1126,7 +1635,7 @@ async function addSystemConfigDirectories(): Promise<void> {
     try {
       const result = await invoke<PermissionCheckResult>("check_read_permission", { path: expandedPath });
       if (result.readable) {
-        config.directories.push(configPath);
+        config.directories.push(toBackupDirectory(configPath));
         addedCount++;
       }
     } catch (_e) {
@@ -1148,15 +1657,16 @@ if (addSystemConfigsBtn) {
 }
 
 resetDirectoriesBtn.addEventListener("click", () => {
-  config.directories = [...config.default_directories];
+  config.directories = config.default_directories.map(toBackupDirectory);
   updateDirectoriesList();
   saveConfig();
   log(t("folderReset"));
 });
 
-btnBackup.addEventListener("click", startBackup);
+btnBackup.addEventListener("click", () => startBackup());
 
 btnCancel.addEventListener("click", cancelBackup);
+btnPause.addEventListener("click", togglePauseBackup);
 
 btnRestore.addEventListener("click", async () => {
   const timestamp = backupSelect.value;
@@ -1202,21 +1712,75 @@ function showRestoreModal(details: BackupDetails): void {
       <input type="checkbox" class="restore-checkbox" value="${item.path}" checked />
       <span class="restore-item-icon">${icon}</span>
       <div class="restore-item-info">
-        <div class="restore-item-path">${item.path}</div>
+        <div class="restore-item-path">${item.path}${item.user ? ` <span class="restore-item-user">👤 ${item.user}</span>` : ""}</div>
         <div class="restore-item-size">${size}</div>
       </div>
     `;
     restoreItemsList.appendChild(div);
   }
+  if (details.items.some(item => item.path === "homebrew-packages")) {
+    void showEssentialPackages(details.timestamp);
+  }
   restoreModal.style.display = "flex";
 }
 
+// List the Brewfile packages of the backup so they can be marked as Quick-Restore essentials
+async function showEssentialPackages(timestamp: string): Promise<void> {
+  let packages: BackupPackages;
+  try {
+    packages = await invoke<BackupPackages>("list_backup_packages", {
+      targetPath: getFullTargetPath(),
+      timestamp,
+    });
+  } catch (e) {
+    log(`⚠️ Pakete des Backups konnten nicht gelesen werden: ${e}`);
+    return;
+  }
+  if (packages.brews.length === 0 && packages.casks.length === 0) return;
+
+  const section = document.createElement("details");
+  section.className = "essential-packages";
+  section.innerHTML = `
+    <summary>${t("essentialPackages")}</summary>
+    <p class="hint">${t("essentialPackagesHint")}</p>
+  `;
+  const addPackages = (names: string[], key: "quick_restore_brews" | "quick_restore_casks", prefix: string) => {
+    for (const name of names) {
+      const label = document.createElement("label");
+      label.className = "checkbox-label";
+      const checkbox = document.createElement("input");
+      checkbox.type = "checkbox";
+      checkbox.checked = (config[key] || []).includes(name);
+      checkbox.addEventListener("change", () => {
+        const list = (config[key] || []).filter(p => p !== name);
+        if (checkbox.checked) list.push(name);
+        config[key] = list;
+        saveConfig();
+      });
+      const text = document.createElement("span");
+      text.textContent = `${prefix}: ${name}`;
+      label.append(checkbox, text);
+      section.appendChild(label);
+    }
+  };
+  addPackages(packages.brews, "quick_restore_brews", "brew");
+  addPackages(packages.casks, "quick_restore_casks", "cask");
+  restoreItemsList.appendChild(section);
+}
+
 function getRestoreItemIcon(path: string): string {
   if (path === "homebrew-packages") return "🍺";
   if (path === "mas-apps") return "🛒";
   if (path === "vscode-extensions") return "💻";
   if (path === "homebrew-cache") return "📦";
   if (path === "safari-settings") return "🧭";
+  if (path === "chrome-settings" || path === "firefox-settings") return "🌐";
+  if (path === "scheduled-tasks") return "⏰";
+  if (path === "dotfiles") return "🐚";
+  if (path === "fonts") return "🔤";
+  if (path === "system-defaults") return "🎛️";
+  if (path === "system-config") return "🖨️";
+  if (path === "terminal-settings") return "⌨️";
   if (path.includes("ssh")) return "🔑";
   if (path.includes("config")) return "⚙️";
   if (path.includes("Documents")) return "📄";
@@ -1247,6 +1811,68 @@ restoreCancel.addEventListener("click", () => {
   restoreModal.style.display = "none";
 });
 
+// Run a restore command with the cancel button available while it runs
+async function runRestore(command: string, args: Record<string, unknown>): Promise<RestoreResult> {
+  restoreInProgress = true;
+  btnBackup.style.display = "none";
+  btnCancel.style.display = "block";
+  btnCancel.disabled = false;
+  try {
+    return await invoke<RestoreResult>(command, args);
+  } finally {
+    restoreInProgress = false;
+    btnBackup.style.display = "block";
+    btnCancel.style.display = "none";
+  }
+}
+
+// Restore a whole backup ("restore_all") or all of its software ("restore_software_only"), package managers first
+async function restoreWholeBackup(command: string): Promise<void> {
+  const timestamp = backupSelect.value;
+  const targetPath = getFullTargetPath();
+  
+  if (!timestamp || !targetPath) {
+    log("❌ Kein Backup oder Ziel ausgewählt");
+    return;
+  }
+  
+  restoreModal.style.display = "none";
+  
+  progressFill.style.width = "0%";
+  progressFill.classList.add("animating");
+  progressMessage.textContent = "Bereite Wiederherstellung vor...";
+  
+  try {
+    const result = await runRestore(command, {
+      targetPath: targetPath,
+      timestamp: timestamp,
+      overwrite: restoreOverwrite.checked,
+    });
+    
+    log(`✅ ${t("restoreComplete")}:`);
+    log(`   ${t("restoredItems")}: ${result.restored_count}`);
+    if (result.skipped_count > 0) {
+      log(`   ${t("skippedItems")}: ${result.skipped_count}`);
+    }
+    if (result.error_count > 0) {
+      log(`   ${t("errorItems")}: ${result.error_count}`);
+      for (const err of result.errors) {
+        log(`   ❌ ${err}`);
+      }
+    }
+    progressFill.classList.remove("animating");
+    progressFill.style.width = "100%";
+    progressMessage.textContent = t("restoreComplete");
+  } catch (e) {
+    log(`❌ Restore-Fehler: ${e}`);
+    progressFill.classList.remove("animating");
+    progressMessage.textContent = "Fehler bei Wiederherstellung";
+  }
+}
+
+restoreEverythingBtn?.addEventListener("click", () => restoreWholeBackup("restore_all"));
+restoreSoftwareBtn?.addEventListener("click", () => restoreWholeBackup("restore_software_only"));
+
 // Quick-Restore: Install essential packages first for rapid productivity
 if (restoreQuickBtn) {
   restoreQuickBtn.addEventListener("click", async () => {
@@ -1265,10 +1891,10 @@ if (restoreQuickBtn) {
     progressMessage.textContent = "⚡ Quick-Restore: Installiere essentielle Pakete...";
     
     log("⚡ Quick-Restore gestartet...");
-    log("   Installiert: git, vim, python, node, curl, wget, VS Code, iTerm2, etc.");
+    log(`   Installiert: ${[...(config.quick_restore_brews || []), ...(config.quick_restore_casks || [])].join(", ")}`);
     
     try {
-      const result = await invoke<RestoreResult>("quick_restore_essentials", {
+      const result = await runRestore("quick_restore_essentials", {
         targetPath: targetPath,
         timestamp: timestamp,
       });
@@ -1318,11 +1944,12 @@ restoreStart.addEventListener("click", async () => {
   log(`🔄 ${t("restoring")} ${selectedItems.length} Elemente...`);
   
   try {
-    const result = await invoke<RestoreResult>("restore_items", {
+    const result = await runRestore("restore_items", {
       targetPath: targetPath,
       timestamp: timestamp,
       items: selectedItems,
       overwrite: overwrite,
+      startServices: restoreStartServices.checked,
     });
     
     log(`✅ ${t("restoreComplete")}:`);
@@ -1339,6 +1966,29 @@ restoreStart.addEventListener("click", async () => {
     progressFill.classList.remove("animating");
     progressFill.style.width = "100%";
     progressMessage.textContent = t("restoreComplete");
+    
+    // Offer to switch to the login shell recorded with the dotfiles
+    if (selectedItems.includes("dotfiles")) {
+      const shell = await invoke<string | null>("get_recorded_shell", { targetPath, timestamp });
+      if (shell && await ask(`${t("changeShellPrompt")} ${shell}`, { title: t("changeShell"), kind: "info" })) {
+        try {
+          await invoke("change_login_shell", { shell });
+          log(`✅ ${t("shellChanged")} ${shell}`);
+        } catch (e) {
+          log(`❌ ${e}`);
+        }
+      }
+    }
+    
+    // Offer to clone the repositories recorded with the backup
+    const repositories = await invoke<GitRepository[]>("get_git_repositories", { targetPath, timestamp });
+    if (repositories.length > 0 && await ask(`${t("cloneGitReposPrompt")} ${repositories.length}`, { title: t("cloneGitRepos"), kind: "info" })) {
+      const cloned = await runRestore("restore_git_repositories", { targetPath, timestamp });
+      log(`   ${t("restoredItems")}: ${cloned.restored_count}, ${t("skippedItems")}: ${cloned.skipped_count}`);
+      for (const err of cloned.errors) {
+        log(`   ❌ ${err}`);
+      }
+    }
   } catch (e) {
     log(`❌ Restore-Fehler: ${e}`);
     progressFill.classList.remove("animating");
@@ -1369,6 +2019,10 @@ btnRestoreTest.addEventListener("click", async () => {
   }
   
   log(`${t("verifyStarted")} ${timestamp}...`);
+  verifyInProgress = true;
+  btnBackup.style.display = "none";
+  btnCancel.style.display = "block";
+  btnCancel.disabled = false;
   
   try {
     const result = await invoke<{
@@ -1392,6 +2046,10 @@ btnRestoreTest.addEventListener("click", async () => {
     }
   } catch (e) {
     log(`${t("backupFailed")} ${e}`);
+  } finally {
+    verifyInProgress = false;
+    btnBackup.style.display = "block";
+    btnCancel.style.display = "none";
   }
 });
 
@@ -1416,6 +2074,8 @@ showFilesBtn.addEventListener("click", async () => {
       archive: string;
       archive_size_bytes: number;
       source_size_bytes: number;
+      compression_ratio: number;
+      duration_seconds: number;
     }
     
     interface BackupDetails {
@@ -1423,9 +2083,15 @@ showFilesBtn.addEventListener("click", async () => {
       items: BackupFileInfo[];
       total_source_size_bytes: number;
       total_archive_size_bytes: number;
+      average_compression_ratio: number;
       start_time: string;
       end_time: string;
       duration_seconds: number;
+      os_version?: string;
+      os_build?: string;
+      hostname?: string;
+      arch?: string;
+      app_version?: string;
     }
     
     const details: BackupDetails = await invoke("list_backup_files", {
@@ -1448,20 +2114,35 @@ showFilesBtn.addEventListener("click", async () => {
     log(`   ${t("filesStartTime")}: ${details.start_time}`);
     log(`   ${t("filesEndTime")}: ${details.end_time}`);
     log(`   ${t("filesDuration")}: ${details.duration_seconds} ${t("filesSeconds")}`);
+    if (details.os_version) {
+      log(`💻 Erstellt auf macOS ${details.os_version} (${details.arch})${details.hostname ? ` – ${details.hostname}` : ""}`);
+    }
     log("");
-    log(`📊 ${details.items.length} ${t("filesItems")} | ${t("filesTotalOriginal")}: ${formatBytes(details.total_source_size_bytes)} | ${t("filesTotalArchive")}: ${formatBytes(details.total_archive_size_bytes)}`);
+    log(`📊 ${details.items.length} ${t("filesItems")} | ${t("filesTotalOriginal")}: ${formatBytes(details.total_source_size_bytes)} | ${t("filesTotalArchive")}: ${formatBytes(details.total_archive_size_bytes)} | ${t("filesRatio")}: ${details.average_compression_ratio.toFixed(2)}`);
     log("");
     
     for (const item of details.items) {
       const origSize = formatBytes(item.source_size_bytes);
       const archSize = formatBytes(item.archive_size_bytes);
       const ratio = item.source_size_bytes > 0 
-        ? ((1 - item.archive_size_bytes / item.source_size_bytes) * 100).toFixed(1)
+        ? ((1 - item.compression_ratio) * 100).toFixed(1)
         : "0";
       log(`📁 ${item.path}`);
       log(`   📦 ${item.archive} (${origSize} → ${archSize}, -${ratio}%)`);
     }
     
+    const slowest = details.items
+      .filter(item => item.duration_seconds > 0)
+      .sort((a, b) => b.duration_seconds - a.duration_seconds)
+      .slice(0, 5);
+    if (slowest.length > 0) {
+      log("");
+      log("⏱️ Langsamste Elemente:");
+      for (const item of slowest) {
+        log(`   ${item.path}: ${item.duration_seconds.toFixed(1)} ${t("filesSeconds")}`);
+      }
+    }
+    
     log("");
     log("✅ " + t("filesHeader") + " " + t("completed"));
   } catch (error) {
@@ -1516,6 +2197,88 @@ showManualAppsBtn.addEventListener("click", async () => {
   }
 });
 
+// Coverage gap handler: software installed now but missing from the selected backup
+showCoverageBtn?.addEventListener("click", async () => {
+  const timestamp = backupSelect.value;
+  if (!timestamp) {
+    log(t("selectBackupFirst"));
+    return;
+  }
+  
+  const fullPath = getFullTargetPath();
+  if (!fullPath) {
+    log(t("selectTargetFirst"));
+    return;
+  }
+  
+  try {
+    const coverage = await invoke<BackupCoverage>("backup_coverage", {
+      targetPath: fullPath,
+      timestamp: timestamp
+    });
+    
+    log("");
+    log(`=== 🧭 ${t("coverageTitle")} (${timestamp}) ===`);
+    if (coverage.total_missing === 0) {
+      log(t("coverageComplete"));
+    } else {
+      log(t("coverageRerun"));
+      const categories: [string, string[]][] = [
+        ["Homebrew", coverage.brews],
+        ["Casks", coverage.casks],
+        ["App Store", coverage.mas_apps],
+        ["VS Code", coverage.vscode_extensions],
+        ["Manuelle Apps", coverage.manual_apps],
+      ];
+      for (const [name, gaps] of categories) {
+        if (gaps.length === 0) continue;
+        log(`   ${name} (${gaps.length}): ${gaps.join(", ")}`);
+      }
+    }
+    log("");
+  } catch (error) {
+    log(`❌ ${t("coverageError")} ${error}`);
+  }
+});
+
+// Recompress handler: trade CPU time for space on cold backups
+btnRecompress?.addEventListener("click", async () => {
+  const timestamp = backupSelect.value;
+  if (!timestamp) {
+    log(t("selectBackupFirst"));
+    return;
+  }
+  
+  const targetPath = getFullTargetPath();
+  if (!targetPath) {
+    log(t("selectTargetFirst"));
+    return;
+  }
+  
+  if (!await ask(`${t("recompressPrompt")}\n\n${formatTimestamp(timestamp)}`, { title: t("recompressTitle"), kind: "info" })) {
+    return;
+  }
+  
+  btnRecompress.disabled = true;
+  log(t("recompressStarted"));
+  try {
+    const result = await invoke<RecompressResult>("recompress_backup", {
+      targetPath,
+      timestamp,
+      level: 19,
+    });
+    log(`${t("recompressDone")} ${result.recompressed_files}, ${formatRestoreBytes(result.bytes_saved)} ${t("recompressSaved")}`);
+    for (const skipped of result.skipped) {
+      log(`   ⏭️ ${skipped}`);
+    }
+    await loadBackups();
+  } catch (error) {
+    log(`❌ ${t("recompressError")} ${error}`);
+  } finally {
+    btnRecompress.disabled = false;
+  }
+});
+
 // Delete backup handler
 btnDeleteBackup.addEventListener("click", async () => {
   const selectedBackup = backupSelect.value;
@@ -1615,6 +2378,22 @@ settingsSaveBtn.addEventListener("click", async () => {
   settingsDialog.close();
 });
 
+btnSelfTest.addEventListener("click", async () => {
+  btnSelfTest.disabled = true;
+  log(t("selfTestStarted"));
+  try {
+    const report = await invoke<SelfTestReport>("run_self_test");
+    for (const phase of report.phases) {
+      log(`   ${phase.success ? "✅" : "❌"} ${phase.name} (${phase.duration_seconds.toFixed(1)} s): ${phase.detail}`);
+    }
+    log(report.success ? t("selfTestPassed") : t("selfTestFailed"));
+  } catch (error) {
+    log(`${t("selfTestFailed")}: ${error}`);
+  } finally {
+    btnSelfTest.disabled = false;
+  }
+});
+
 addDefaultDirectoryBtn.addEventListener("click", async () => {
   try {
     const selected = await open({
@@ -1653,14 +2432,59 @@ btnTheme.addEventListener("click", cycleTheme);
 btnLanguage.addEventListener("click", toggleLanguage);
 
 // Initialize
+// Ask for notification permission once, before the first backup, and remember the answer
+async function ensureNotificationPermission(): Promise<void> {
+  if (config.notifications_enabled === false || config.notification_permission_granted != null) return;
+  try {
+    let granted = await isPermissionGranted();
+    if (!granted) {
+      granted = (await requestPermission()) === "granted";
+    }
+    config.notification_permission_granted = granted;
+    await saveConfig();
+  } catch (e) {
+    console.error("Notification permission check failed:", e);
+  }
+}
+
+// Re-read the notification permission on every launch, since it can be changed in System Settings at any time
+async function refreshNotificationPermission(): Promise<void> {
+  if (config.notification_permission_granted == null) return;
+  try {
+    const granted = await isPermissionGranted();
+    if (granted !== config.notification_permission_granted) {
+      config.notification_permission_granted = granted;
+      await saveConfig();
+    }
+  } catch (e) {
+    console.error("Notification permission check failed:", e);
+  }
+}
+
 async function init(): Promise<void> {
   log(t("started"));
   await setupEventListeners();
   await loadConfig();
+  await refreshNotificationPermission();
+  const lastTimestamp = await restoreUiState();
   await loadVolumes();
+  updateTargetPathDisplay();
   await loadBackups();
+  if (lastTimestamp && Array.from(backupSelect.options).some((o) => o.value === lastTimestamp)) {
+    backupSelect.value = lastTimestamp;
+  }
   await checkFullDiskAccess();
   
+  // Started by the launchd agent: run the backup unattended and quit afterwards
+  if (await invoke<boolean>("is_scheduled_run")) {
+    log(t("scheduledBackupStarted"));
+    await startBackup();
+    await getCurrentWindow().close();
+    return;
+  }
+  
+  await checkIncompleteBackups();
+  
   try {
     const hasHomebrew = await invoke<boolean>("check_homebrew");
     if (hasHomebrew) {